@@ -2,7 +2,7 @@ use intricate::{
     datasets::mnist,
     layers::{
         activations::{ReLU, SoftMax, Sigmoid},
-        Conv2D, Dense, Layer,
+        Conv2D, Dense, Flatten, Layer,
     },
     loss_functions::CategoricalCrossEntropy,
     optimizers,
@@ -19,9 +19,10 @@ fn main() -> () {
     let state = setup_opencl(DeviceType::GPU).expect("unable to setup OpenCL");
 
     let mut mnist_model: Model = Model::new(vec![
-        Conv2D::new((28, 28), (3, 3)),
+        Conv2D::new((28, 28), 1, (3, 3), 1),
         ReLU::new(26 * 26),
 
+        Flatten::new(vec![1, 26, 26]),
         Dense::new(26 * 26, 10),
         SoftMax::new(10),
     ]);
@@ -91,6 +91,10 @@ pub fn enum_layer(_input: TokenStream) -> TokenStream {
     let layer_names_13 = layer_names.clone();
     let layer_names_14 = layer_names.clone();
     let layer_names_15 = layer_names.clone();
+    let layer_names_16 = layer_names.clone();
+    let layer_names_17 = layer_names.clone();
+    let layer_names_18 = layer_names.clone();
+    let layer_names_19 = layer_names.clone();
 
     TokenStream::from(quote! {
         impl<'a> crate::layers::Layer<'a> for #enum_name<'a> {
@@ -266,6 +270,40 @@ pub fn enum_layer(_input: TokenStream) -> TokenStream {
                     )*
                 }
             }
+
+            fn set_training_mode(&mut self, is_training: bool) -> () {
+                match self {
+                    #(
+                        #enum_name::#layer_names_16(layer) => layer.set_training_mode(is_training),
+                    )*
+                }
+            }
+
+            fn set_updating_running_statistics(&mut self, should_update: bool) -> () {
+                match self {
+                    #(
+                        #enum_name::#layer_names_17(layer) => layer.set_updating_running_statistics(
+                            should_update
+                        ),
+                    )*
+                }
+            }
+
+            fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+                match self {
+                    #(
+                        #enum_name::#layer_names_18(layer) => layer.get_inputs_shape(),
+                    )*
+                }
+            }
+
+            fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+                match self {
+                    #(
+                        #enum_name::#layer_names_19(layer) => layer.get_outputs_shape(),
+                    )*
+                }
+            }
         }
     })
 }
@@ -284,6 +322,12 @@ pub fn enum_layer(_input: TokenStream) -> TokenStream {
 /// - **last_outputs_buffer**
 /// - **last_inputs_buffer**
 /// - **opencl_state**
+///
+/// Any other property of the struct is considered to be a constant of the activation function,
+/// such as the `alpha` of a LeakyReLU, which will need a `DEFAULT_#PROPERTY` constant in scope
+/// (e.g. DEFAULT_ALPHA) for its initial value, will get a `set_#property` method and will be
+/// passed as a float into both kernels after all of the other arguments in the order they are
+/// declared.
 pub fn activation_layer(_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(_input as DeriveInput);
     let activation_name = &input.ident;
@@ -292,6 +336,45 @@ pub fn activation_layer(_input: TokenStream) -> TokenStream {
         input.ident.span(),
     );
 
+    let fields = if let Data::Struct(strct) = &input.data {
+        match &strct.fields {
+            Fields::Named(fields) => fields.named.clone(),
+            _ => panic!("The 'ActivationLayer' derive macro can only be used with structs with named fields!"),
+        }
+    } else {
+        panic!("The 'ActivationLayer' derive macro can only be used with structs!");
+    };
+
+    let constants: Vec<(Ident, syn::Type)> = fields
+        .iter()
+        .filter_map(|field| {
+            let name = field.ident.clone().unwrap();
+
+            match name.to_string().as_str() {
+                "inputs_amount" | "last_outputs_buffer" | "last_inputs_buffer" | "opencl_state" => {
+                    None
+                }
+                _ => Some((name, field.ty.clone())),
+            }
+        })
+        .collect();
+
+    let constant_names: Vec<&Ident> = constants.iter().map(|(name, _)| name).collect();
+    let constant_types: Vec<&syn::Type> = constants.iter().map(|(_, ty)| ty).collect();
+    let constant_defaults: Vec<Ident> = constant_names
+        .iter()
+        .map(|name| {
+            Ident::new(
+                ("DEFAULT_".to_string() + &name.to_string().to_uppercase()).as_str(),
+                name.span(),
+            )
+        })
+        .collect();
+    let constant_setters: Vec<Ident> = constant_names
+        .iter()
+        .map(|name| Ident::new(("set_".to_string() + &name.to_string()).as_str(), name.span()))
+        .collect();
+
     TokenStream::from(quote! {
         impl<'a> #activation_name<'a> {
             /// Creates a raw version of the #activation_name activation function, this is good for
@@ -300,6 +383,10 @@ pub fn activation_layer(_input: TokenStream) -> TokenStream {
                 #activation_name {
                     inputs_amount,
 
+                    #(
+                        #constant_names: #constant_defaults,
+                    )*
+
                     last_outputs_buffer: None,
                     last_inputs_buffer: None,
 
@@ -312,6 +399,16 @@ pub fn activation_layer(_input: TokenStream) -> TokenStream {
             pub fn new(inputs_amount: usize) -> crate::types::ModelLayer<'a> {
                 Self::new_raw(inputs_amount).into()
             }
+
+            #(
+                /// Sets the constant of the same name of the activation function and returns the
+                /// mutated Self.
+                pub fn #constant_setters(mut self, #constant_names: #constant_types) -> Self {
+                    self.#constant_names = #constant_names;
+
+                    self
+                }
+            )*
         }
 
         pub(crate) fn #compile_activation(
@@ -437,6 +534,9 @@ pub fn activation_layer(_input: TokenStream) -> TokenStream {
                     .set_arg(inputs)
                     .set_arg(&outputs_buffer)
                     .set_arg(&(outputs_total_count as opencl3::error_codes::cl_int))
+                    #(
+                        .set_arg(&(self.#constant_names as opencl3::device::cl_float))
+                    )*
                     .set_global_work_size(outputs_total_count)
                     .enqueue_nd_range(queue)?;
 
@@ -491,7 +591,7 @@ pub fn activation_layer(_input: TokenStream) -> TokenStream {
 
                 let queue = state.queues.first().unwrap();
 
-                if self.last_outputs_buffer.is_none() {
+                if self.last_outputs_buffer.is_none() || self.last_inputs_buffer.is_none() {
                     return Err(
                         crate::layers::LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation
                     );
@@ -523,10 +623,14 @@ pub fn activation_layer(_input: TokenStream) -> TokenStream {
                 opencl3::kernel::ExecuteKernel::new(back_prop_kernel)
                     .set_arg(layer_output_to_error_derivative)
                     .set_arg(self.last_outputs_buffer.as_ref().unwrap())
+                    .set_arg(self.last_inputs_buffer.as_ref().unwrap())
                     .set_arg(&loss_to_input_derivatives_buffer)
                     .set_arg(&(self.inputs_amount as opencl3::error_codes::cl_int))
                     .set_arg(&(samples_amount as opencl3::error_codes::cl_int))
                     .set_arg(&(self.inputs_amount as opencl3::error_codes::cl_int))
+                    #(
+                        .set_arg(&(self.#constant_names as opencl3::device::cl_float))
+                    )*
                     .set_global_work_sizes(&[samples_amount, self.inputs_amount])
                     .enqueue_nd_range(queue)?;
 
@@ -0,0 +1,117 @@
+//! The module that defines the callbacks that can be called throughout the training process of a
+//! Model, which can be used for things such as custom logging or checkpointing.
+
+use crate::{types::TrainingResults, GraphModel, Model};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What the training process should do after a callback is called.
+pub enum CallbackAction {
+    /// Keeps on training the Model normally.
+    Continue,
+    /// Stops the training process as soon as possible, which is right after the current training
+    /// step if the callback was called after a step.
+    StopTraining,
+}
+
+#[derive(Debug)]
+/// A mutable reference to the Model that is being trained, which is given to the callbacks so
+/// that they can do things such as saving the Model.
+pub enum TrainingModel<'b, 'a> {
+    /// The Model being trained is a sequential Model.
+    Model(&'b mut Model<'a>),
+    /// The Model being trained is a GraphModel.
+    GraphModel(&'b mut GraphModel<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The metrics of a Model after one of the epochs of the training process.
+pub struct EpochResults {
+    /// The average of the losses of all the training steps in the epoch, if the loss is being
+    /// computed
+    pub loss: Option<f32>,
+    /// The average of the accuracies of all the training steps in the epoch, if the accuracy is
+    /// being computed
+    pub accuracy: Option<f32>,
+    /// The loss on the validation data, if there is validation data and the loss is being
+    /// computed
+    pub validation_loss: Option<f32>,
+    /// The accuracy on the validation data, if there is validation data and the accuracy is being
+    /// computed
+    pub validation_accuracy: Option<f32>,
+}
+
+/// Something that is called at certain points of the training process of a Model and can
+/// request for it to stop.
+///
+/// All of the methods do nothing by default so that only the ones needed have to be implemented.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::callbacks::{CallbackAction, EpochResults, TrainingCallback, TrainingModel};
+///
+/// #[derive(Debug)]
+/// struct LossLogger {
+///     losses: Vec<f32>,
+/// }
+///
+/// impl<'a> TrainingCallback<'a> for LossLogger {
+///     fn on_epoch_end(
+///         &mut self,
+///         _epoch_index: usize,
+///         results: &EpochResults,
+///         _model: TrainingModel<'_, 'a>,
+///     ) -> CallbackAction {
+///         if let Some(loss) = results.loss {
+///             self.losses.push(loss);
+///         }
+///
+///         CallbackAction::Continue
+///     }
+/// }
+/// ```
+pub trait TrainingCallback<'a>
+where
+    Self: std::fmt::Debug,
+{
+    /// Called once before the first epoch.
+    fn on_train_begin(&mut self, _model: TrainingModel<'_, 'a>) -> CallbackAction {
+        CallbackAction::Continue
+    }
+
+    /// Called before each one of the epochs, with the index of the epoch starting at zero.
+    fn on_epoch_begin(
+        &mut self,
+        _epoch_index: usize,
+        _model: TrainingModel<'_, 'a>,
+    ) -> CallbackAction {
+        CallbackAction::Continue
+    }
+
+    /// Called after each one of the training steps with the loss and the accuracy of the step,
+    /// if they are being computed.
+    fn on_batch_end(
+        &mut self,
+        _epoch_index: usize,
+        _batch_index: usize,
+        _loss: Option<f32>,
+        _accuracy: Option<f32>,
+        _model: TrainingModel<'_, 'a>,
+    ) -> CallbackAction {
+        CallbackAction::Continue
+    }
+
+    /// Called after each one of the epochs, once the Model has been evaluated on the validation
+    /// data if there is any.
+    fn on_epoch_end(
+        &mut self,
+        _epoch_index: usize,
+        _results: &EpochResults,
+        _model: TrainingModel<'_, 'a>,
+    ) -> CallbackAction {
+        CallbackAction::Continue
+    }
+
+    /// Called once after the training process is over, even if it was stopped early.
+    fn on_train_end(&mut self, _results: &TrainingResults, _model: TrainingModel<'_, 'a>) -> () {}
+}
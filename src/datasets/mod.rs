@@ -5,11 +5,15 @@
 //!
 //! Currently contains the following datasets.
 //! - MNIST
+//!
+//! And also some helpers for preparing data for a Model.
+//! - Vocabulary
 
 use indicatif::ProgressIterator;
 
 /// The module containing the MNIST dataset
 pub mod mnist;
+pub mod vocabulary;
 
 #[allow(dead_code)]
 fn get_dimensions_of_ubyte_dataset(source: &[u8], dimensions_amount: usize) -> Vec<usize> {
@@ -0,0 +1,174 @@
+//! The module containing a vocabulary for turning tokens, such as words, into indices for an
+//! Embedding.
+
+use std::collections::HashMap;
+
+use savefile_derive::Savefile;
+
+/// The index that is used to pad token sequences that are shorter than the expected sequence
+/// length.
+pub const PADDING_INDEX: usize = 0;
+/// The index that is used for tokens that are not in the vocabulary.
+pub const UNKNOWN_INDEX: usize = 1;
+
+const PADDING_TOKEN: &str = "<pad>";
+const UNKNOWN_TOKEN: &str = "<unk>";
+
+#[derive(Debug, Savefile)]
+/// A mapping between tokens, such as words, and indices that can be sent into an
+/// `Embedding` layer.
+///
+/// The index 0 is reserved for padding and the index 1 for tokens that were not seen when
+/// building the vocabulary, the rest of the tokens are ordered from the most frequent to the
+/// least frequent.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::datasets::vocabulary::Vocabulary;
+///
+/// let sentences = vec![
+///     vec!["the", "cat", "sat"],
+///     vec!["the", "dog", "sat", "down"],
+/// ];
+///
+/// let vocabulary = Vocabulary::build(&sentences, None);
+///
+/// assert_eq!(vocabulary.len(), 7);
+/// assert_eq!(vocabulary.encode(&["the", "bird", "sat"], 4), vec![2.0, 1.0, 3.0, 0.0]);
+/// ```
+pub struct Vocabulary {
+    tokens: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl Vocabulary {
+    /// Builds a vocabulary out of all of the tokens in the sequences, keeping just the
+    /// **max_size** most frequent tokens if there is some max size, which includes the padding
+    /// and unknown tokens.
+    ///
+    /// Tokens that appear the same amount of times are ordered by when they first appear, and
+    /// tokens that are the same as the padding or unknown tokens are not added again.
+    pub fn build<T: AsRef<str>>(sequences: &[Vec<T>], max_size: Option<usize>) -> Self {
+        let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+
+        for token in sequences
+            .iter()
+            .flatten()
+            .filter(|token| ![PADDING_TOKEN, UNKNOWN_TOKEN].contains(&token.as_ref()))
+        {
+            let first_appearance = counts.len();
+            counts.entry(token.as_ref()).or_insert((0, first_appearance)).0 += 1;
+        }
+
+        let mut counted_tokens: Vec<(&str, (usize, usize))> = counts.into_iter().collect();
+        counted_tokens.sort_by(|(_, (count_a, first_a)), (_, (count_b, first_b))| {
+            count_b.cmp(count_a).then(first_a.cmp(first_b))
+        });
+
+        let mut tokens = vec![PADDING_TOKEN.to_string(), UNKNOWN_TOKEN.to_string()];
+        tokens.extend(counted_tokens.into_iter().map(|(token, _)| token.to_string()));
+
+        if let Some(max_size) = max_size {
+            tokens.truncate(max_size.max(2));
+        }
+
+        let indices = tokens
+            .iter()
+            .enumerate()
+            .map(|(index, token)| (token.clone(), index))
+            .collect();
+
+        Vocabulary { tokens, indices }
+    }
+
+    /// Gets the amount of tokens in the vocabulary, including the padding and unknown tokens,
+    /// which is what should be used as the vocabulary size of an `Embedding`.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Weather or not there are no tokens in the vocabulary, which is never the case for a
+    /// built vocabulary since it always has the padding and unknown tokens.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Gets the index of a token, or the unknown index if the token is not in the vocabulary.
+    pub fn get_index(&self, token: &str) -> usize {
+        *self.indices.get(token).unwrap_or(&UNKNOWN_INDEX)
+    }
+
+    /// Gets the token that has a certain index if there is one.
+    pub fn get_token(&self, index: usize) -> Option<&str> {
+        self.tokens.get(index).map(|token| token.as_str())
+    }
+
+    /// Turns a list of tokens into a vector of indices with exactly **sequence_length** indices,
+    /// padding it at the end or cutting off the last tokens when needed.
+    ///
+    /// The indices are floats so that they can be sent directly into a Model.
+    pub fn encode<T: AsRef<str>>(&self, tokens: &[T], sequence_length: usize) -> Vec<f32> {
+        let mut indices: Vec<f32> = tokens
+            .iter()
+            .take(sequence_length)
+            .map(|token| self.get_index(token.as_ref()) as f32)
+            .collect();
+
+        indices.resize(sequence_length, PADDING_INDEX as f32);
+
+        indices
+    }
+
+    /// Encodes each one of the sequences of tokens, just like `encode`.
+    pub fn encode_all<T: AsRef<str>>(
+        &self,
+        sequences: &[Vec<T>],
+        sequence_length: usize,
+    ) -> Vec<Vec<f32>> {
+        sequences
+            .iter()
+            .map(|tokens| self.encode(tokens, sequence_length))
+            .collect()
+    }
+}
+
+#[test]
+fn should_order_the_tokens_by_frequency() {
+    let sequences = vec![vec!["b", "a", "c"], vec!["c", "a", "d", "c"]];
+
+    let vocabulary = Vocabulary::build(&sequences, None);
+
+    assert_eq!(vocabulary.len(), 6);
+    assert_eq!(vocabulary.get_token(0), Some(PADDING_TOKEN));
+    assert_eq!(vocabulary.get_token(1), Some(UNKNOWN_TOKEN));
+    assert_eq!(vocabulary.get_token(2), Some("c"));
+    assert_eq!(vocabulary.get_token(3), Some("a"));
+    assert_eq!(vocabulary.get_token(4), Some("b"));
+    assert_eq!(vocabulary.get_token(5), Some("d"));
+}
+
+#[test]
+fn should_encode_tokens_with_padding_and_unknown_indices() {
+    let sequences = vec![vec!["b", "a", "c"], vec!["c", "a", "d", "c"]];
+
+    let vocabulary = Vocabulary::build(&sequences, Some(4));
+
+    assert_eq!(
+        vocabulary.encode_all(&[vec!["a", "d", "c"], vec!["c", "e", "a", "b", "c"]], 4),
+        vec![vec![3.0, 1.0, 2.0, 0.0], vec![2.0, 1.0, 3.0, 1.0]]
+    );
+}
+
+#[test]
+fn should_not_add_the_padding_and_unknown_tokens_again() {
+    let sequences = vec![vec!["a", "<pad>", "<pad>"], vec!["<unk>", "a", "b"]];
+
+    let vocabulary = Vocabulary::build(&sequences, None);
+
+    assert_eq!(vocabulary.len(), 4);
+    assert_eq!(vocabulary.get_index(PADDING_TOKEN), PADDING_INDEX);
+    assert_eq!(vocabulary.get_index(UNKNOWN_TOKEN), UNKNOWN_INDEX);
+    assert_eq!(vocabulary.get_token(2), Some("a"));
+    assert_eq!(vocabulary.get_token(3), Some("b"));
+}
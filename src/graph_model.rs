@@ -0,0 +1,1144 @@
+//! The module that implements a GraphModel, a Model whose layers are connected to each other by
+//! explicit edges instead of just one after the other, which allows for things such as residual
+//! connections and the concatenation of features.
+
+use std::mem;
+
+use intricate_macros::FromForAllUnnamedVariants;
+use opencl3::{
+    device::cl_float,
+    error_codes::{cl_int, ClError},
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use savefile::{load_noschema, save_noschema};
+use savefile_derive::Savefile;
+
+use crate::{
+    callbacks::TrainingModel,
+    layers::{Gradient, Layer, LayerInitializationError},
+    loss_functions::LossFunction,
+    model::{
+        fit_model, shapes_match, ModelFittingError, ModelGetLastPredictionError,
+        ModelGradientApplicationError, ModelGradientComputationError, ModelPredictionError,
+        TrainableModel, SAVED_PARAMETERS_VERSION,
+    },
+    optimizers::Optimizer,
+    types::{KernelNotFoundError, ModelLayer, ProgramNotFoundError, SyncDataError, TrainingOptions, TrainingResults},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperationError,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        BufferOperations, OpenCLState,
+    },
+};
+
+const GRAPH_MODEL_PROGRAM_SOURCE: &str = include_str!("kernels/graph_model.cl");
+const GRAPH_MODEL_PROGRAM_NAME: &str = "GRAPH_MODEL";
+const COPY_PER_SAMPLE_KERNEL_NAME: &str = "copy_per_sample";
+
+pub(crate) fn compile_graph_model(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[COPY_PER_SAMPLE_KERNEL_NAME.to_string()];
+
+    ensure_program(
+        opencl_state,
+        GRAPH_MODEL_PROGRAM_NAME.to_string(),
+        GRAPH_MODEL_PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, FromForAllUnnamedVariants)]
+/// An enum containing all of the errors that can happen when merging the outputs of some nodes
+/// or when taking back the derivatives that belong to each one of them.
+pub enum MergeError {
+    /// Happens if something goes wrong with OpenCL.
+    OpenCL(ClError),
+    /// Happens when a required program was not found
+    ProgramNotFound(ProgramNotFoundError),
+    /// Happens when a required kernel was not found in a program
+    KernelNotFound(KernelNotFoundError),
+    /// Happens when something goes wrong in a predefined buffer operation
+    BufferOperation(BufferOperationError),
+}
+
+impl From<MergeError> for ModelPredictionError {
+    fn from(err: MergeError) -> Self {
+        match err {
+            MergeError::OpenCL(err) => ModelPredictionError::OpenCL(err),
+            MergeError::ProgramNotFound(err) => ModelPredictionError::ProgramNotFound(err),
+            MergeError::KernelNotFound(err) => ModelPredictionError::KernelNotFound(err),
+            MergeError::BufferOperation(err) => ModelPredictionError::BufferOperation(err),
+        }
+    }
+}
+
+impl From<MergeError> for ModelGradientComputationError {
+    fn from(err: MergeError) -> Self {
+        match err {
+            MergeError::OpenCL(err) => ModelGradientComputationError::OpenCL(err),
+            MergeError::ProgramNotFound(err) => ModelGradientComputationError::ProgramNotFound(err),
+            MergeError::KernelNotFound(err) => ModelGradientComputationError::KernelNotFound(err),
+            MergeError::BufferOperation(err) => ModelGradientComputationError::BufferOperation(err),
+        }
+    }
+}
+
+#[derive(Debug, Default, Savefile)]
+/// A node of a GraphModel that merges the outputs of all of the nodes connected into it.
+pub struct MergeNode {
+    /// The amount of outputs of each one of the nodes connected into this one, in the same order
+    /// as their edges, which is filled in when creating the GraphModel.
+    pub inputs_amounts: Vec<usize>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last merge.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+}
+
+impl MergeNode {
+    fn get_samples_amount(&self, inputs: &Buffer<cl_float>) -> Result<usize, ClError> {
+        Ok(inputs.size()? / mem::size_of::<cl_float>() / self.inputs_amounts[0])
+    }
+
+    fn add(
+        &mut self,
+        inputs: &[&Buffer<cl_float>],
+        state: &OpenCLState,
+    ) -> Result<(), MergeError> {
+        let mut outputs = inputs[0].clone(state)?;
+
+        for other_inputs in inputs.iter().skip(1) {
+            outputs.add_inplc(other_inputs, state)?;
+        }
+
+        self.last_outputs_buffer = Some(outputs);
+
+        Ok(())
+    }
+
+    fn concatenate(
+        &mut self,
+        inputs: &[&Buffer<cl_float>],
+        state: &OpenCLState,
+    ) -> Result<(), MergeError> {
+        let queue = state.queues.first().unwrap();
+
+        let samples_amount = self.get_samples_amount(inputs[0])?;
+        let outputs_amount: usize = self.inputs_amounts.iter().sum();
+
+        let outputs = empty_buffer(samples_amount * outputs_amount, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(GRAPH_MODEL_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(COPY_PER_SAMPLE_KERNEL_NAME)?;
+
+        let mut offset = 0;
+
+        for (node_inputs, inputs_amount) in inputs.iter().zip(self.inputs_amounts.iter()) {
+            ExecuteKernel::new(kernel)
+                .set_arg(*node_inputs)
+                .set_arg(&outputs)
+                .set_arg(&(*inputs_amount as cl_int))
+                .set_arg(&(0 as cl_int))
+                .set_arg(&(outputs_amount as cl_int))
+                .set_arg(&(offset as cl_int))
+                .set_arg(&(*inputs_amount as cl_int))
+                .set_arg(&(samples_amount as cl_int))
+                .set_global_work_sizes(&[samples_amount, *inputs_amount])
+                .enqueue_nd_range(queue)?;
+
+            offset += inputs_amount;
+        }
+
+        queue.finish()?;
+
+        self.last_outputs_buffer = Some(outputs);
+
+        Ok(())
+    }
+
+    // takes the part of the derivatives of the concatenated outputs that belongs to one of the
+    // nodes connected into this one
+    fn slice_concatenated_derivatives(
+        &self,
+        loss_to_output_derivatives: &Buffer<cl_float>,
+        input_index: usize,
+        state: &OpenCLState,
+    ) -> Result<Buffer<cl_float>, MergeError> {
+        let queue = state.queues.first().unwrap();
+
+        let outputs_amount: usize = self.inputs_amounts.iter().sum();
+        let samples_amount =
+            loss_to_output_derivatives.size()? / mem::size_of::<cl_float>() / outputs_amount;
+
+        let inputs_amount = self.inputs_amounts[input_index];
+        let offset: usize = self.inputs_amounts.iter().take(input_index).sum();
+
+        let derivatives = empty_buffer(samples_amount * inputs_amount, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(GRAPH_MODEL_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(COPY_PER_SAMPLE_KERNEL_NAME)?)
+            .set_arg(loss_to_output_derivatives)
+            .set_arg(&derivatives)
+            .set_arg(&(outputs_amount as cl_int))
+            .set_arg(&(offset as cl_int))
+            .set_arg(&(inputs_amount as cl_int))
+            .set_arg(&(0 as cl_int))
+            .set_arg(&(inputs_amount as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, inputs_amount])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(derivatives)
+    }
+}
+
+#[derive(Debug, Savefile)]
+/// A node inside of a GraphModel.
+pub enum GraphNode<'a> {
+    /// A layer, which can have at most one node connected into it, and when it has none it
+    /// receives the inputs of the GraphModel.
+    Layer(ModelLayer<'a>),
+    /// Sums up the outputs of all of the nodes connected into it, which must all have the same
+    /// amount of outputs.
+    Add(MergeNode),
+    /// Concatenates the outputs of each sample of all of the nodes connected into it, in the same
+    /// order as their edges.
+    Concatenate(MergeNode),
+}
+
+impl<'a> From<ModelLayer<'a>> for GraphNode<'a> {
+    fn from(layer: ModelLayer<'a>) -> Self {
+        GraphNode::Layer(layer)
+    }
+}
+
+impl<'a> GraphNode<'a> {
+    /// Creates a new node that sums up the outputs of the nodes connected into it.
+    pub fn add() -> GraphNode<'a> {
+        GraphNode::Add(MergeNode::default())
+    }
+
+    /// Creates a new node that concatenates the outputs of the nodes connected into it.
+    pub fn concatenate() -> GraphNode<'a> {
+        GraphNode::Concatenate(MergeNode::default())
+    }
+
+    /// Gets the amount of outputs of each sample that come out of this node.
+    pub fn get_outputs_amount(&self) -> usize {
+        match self {
+            GraphNode::Layer(layer) => layer.get_outputs_amount(),
+            GraphNode::Add(merge) => merge.inputs_amounts.first().copied().unwrap_or_default(),
+            GraphNode::Concatenate(merge) => merge.inputs_amounts.iter().sum(),
+        }
+    }
+
+    /// Gets the shape of the outputs of each sample that come out of this node, if it is a layer
+    /// that has one.
+    pub fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+        match self {
+            GraphNode::Layer(layer) => layer.get_outputs_shape(),
+            GraphNode::Add(_) | GraphNode::Concatenate(_) => None,
+        }
+    }
+
+    /// Gets the outputs of the last forward pass through this node.
+    pub fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        match self {
+            GraphNode::Layer(layer) => layer.get_last_outputs(),
+            GraphNode::Add(merge) | GraphNode::Concatenate(merge) => {
+                merge.last_outputs_buffer.as_ref()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Savefile)]
+/// A Model whose nodes are connected by explicit edges, which makes it possible to have
+/// residual connections and concatenations of features, the Model receives the inputs
+/// in the nodes that have no edges going into them and results in the outputs of the last node.
+///
+/// The edges are pairs of the indices of the nodes they go from and to, and they must always go
+/// into a node that comes after the one they go from.
+///
+/// # Example
+///
+/// ```rust
+/// use intricate::{
+///     graph_model::{GraphModel, GraphNode},
+///     layers::{activations::TanH, Dense},
+/// };
+///
+/// // a residual connection that sums the outputs of the Dense with its activations
+/// let my_model: GraphModel = GraphModel::new(
+///     vec![
+///         Dense::new(2, 3).into(),
+///         TanH::new(3).into(),
+///         GraphNode::add(),
+///     ],
+///     vec![(0, 1), (0, 2), (1, 2)],
+/// );
+/// ```
+pub struct GraphModel<'a> {
+    /// The nodes of the Model, in an order such that every edge goes into a later node.
+    pub nodes: Vec<GraphNode<'a>>,
+    /// The connections between the nodes, as pairs of the index of the node the outputs come
+    /// from and the index of the node they go into.
+    pub edges: Vec<(usize, usize)>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// A optional reference to the current OpenCL state.
+    pub opencl_state: Option<&'a OpenCLState>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    // the amount of inputs taken in by the layers with no edges going into them, which is only
+    // known after the graph is validated
+    inputs_amount: usize,
+}
+
+fn accumulate_derivatives(
+    loss_to_outputs_derivatives: &mut [Option<Buffer<cl_float>>],
+    node_index: usize,
+    derivatives: Buffer<cl_float>,
+    state: &OpenCLState,
+) -> Result<(), BufferOperationError> {
+    // the derivatives of the nodes whose outputs go into many others are the sum of all of them
+    if let Some(accumulated_derivatives) = loss_to_outputs_derivatives[node_index].as_mut() {
+        accumulated_derivatives.add_inplc(&derivatives, state)?;
+    } else {
+        loss_to_outputs_derivatives[node_index] = Some(derivatives);
+    }
+
+    Ok(())
+}
+
+impl<'a> GraphModel<'a> {
+    /// Creates a new GraphModel from its nodes and the edges between them with an empty
+    /// OpenCLState.
+    ///
+    /// This does not initialize OpenCL in each of the layers, after calling this method, to do
+    /// anything with the GraphModel you **need** to call the `ìnit` method, which is also where
+    /// the edges and the shapes of the nodes are checked.
+    pub fn new(nodes: Vec<GraphNode<'a>>, edges: Vec<(usize, usize)>) -> GraphModel<'a> {
+        GraphModel {
+            nodes,
+            edges,
+            opencl_state: None,
+            inputs_amount: 0,
+        }
+    }
+
+    // checks that the nodes are connected in a way the GraphModel can forward pass through and
+    // fills in the amount of inputs of the merge nodes and of the GraphModel itself
+    fn validate_graph(&mut self) -> Result<(), LayerInitializationError> {
+        let nodes_amount = self.nodes.len();
+
+        for (from, to) in self.edges.iter() {
+            if from >= to || to >= &nodes_amount {
+                return Err(LayerInitializationError::InvalidEdge(*from, *to));
+            }
+        }
+
+        // the inputs amount and shape of the first layer that receives the inputs of the GraphModel
+        let mut model_inputs: Option<(usize, Option<Vec<usize>>)> = None;
+
+        for node_index in 0..nodes_amount {
+            let is_connected = self.edges.iter().any(|(from, _)| from == &node_index);
+
+            if node_index != nodes_amount - 1 && !is_connected {
+                return Err(LayerInitializationError::DisconnectedNode { node_index });
+            }
+
+            let sources = self.get_sources(node_index);
+            let sources_outputs_amounts: Vec<usize> = sources
+                .iter()
+                .map(|source| self.nodes[*source].get_outputs_amount())
+                .collect();
+            let source_outputs_shape = sources
+                .first()
+                .and_then(|source| self.nodes[*source].get_outputs_shape());
+
+            match &mut self.nodes[node_index] {
+                GraphNode::Layer(layer) => {
+                    if sources.len() > 1 {
+                        return Err(LayerInitializationError::TooManyNodesIntoLayer { node_index });
+                    }
+
+                    if let Some(source_outputs_amount) = sources_outputs_amounts.first() {
+                        if *source_outputs_amount != layer.get_inputs_amount()
+                            || !shapes_match(source_outputs_shape, layer.get_inputs_shape())
+                        {
+                            return Err(LayerInitializationError::NodesShapesDontMatch(
+                                sources[0], node_index,
+                            ));
+                        }
+                    } else if let Some((inputs_amount, inputs_shape)) = &model_inputs {
+                        if *inputs_amount != layer.get_inputs_amount()
+                            || !shapes_match(inputs_shape.clone(), layer.get_inputs_shape())
+                        {
+                            return Err(LayerInitializationError::InputNodesShapesDontMatch {
+                                node_index,
+                            });
+                        }
+                    } else {
+                        model_inputs = Some((layer.get_inputs_amount(), layer.get_inputs_shape()));
+                    }
+                }
+                GraphNode::Add(_) | GraphNode::Concatenate(_) if sources.is_empty() => {
+                    return Err(LayerInitializationError::NoNodesIntoMerge { node_index });
+                }
+                GraphNode::Add(merge) => {
+                    if let Some(position) = sources_outputs_amounts
+                        .iter()
+                        .position(|amount| amount != &sources_outputs_amounts[0])
+                    {
+                        return Err(LayerInitializationError::NodesShapesDontMatch(
+                            sources[position],
+                            node_index,
+                        ));
+                    }
+
+                    merge.inputs_amounts = sources_outputs_amounts;
+                }
+                GraphNode::Concatenate(merge) => {
+                    merge.inputs_amounts = sources_outputs_amounts;
+                }
+            }
+        }
+
+        self.inputs_amount = model_inputs.map_or(0, |(inputs_amount, _)| inputs_amount);
+
+        Ok(())
+    }
+
+    // the indices of the nodes that are connected into a certain node, in the order of the edges
+    fn get_sources(&self, node_index: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter(|(_, to)| to == &node_index)
+            .map(|(from, _)| *from)
+            .collect()
+    }
+
+    /// Sends the trained parameters in each layer from the GPU to the CPU.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if something goes wrong
+    /// while reading the buffers into the CPU.
+    pub fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        for node in self.nodes.iter_mut() {
+            if let GraphNode::Layer(layer) = node {
+                layer.sync_data_from_buffers_to_host()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Initializes all of the layers inside of the GraphModel and starts holding the reference to
+    /// the OpenCL state passed in as parameter.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - an edge goes into a node that does not come after the one it goes from;
+    /// - a node other than the last one has no edges going out of it;
+    /// - a layer has more than one node connected into it;
+    /// - a merge node has no nodes connected into it;
+    /// - the amount of outputs of a node does not match what the node it goes into expects;
+    /// - the initialization of any of the layers fails.
+    pub fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        self.validate_graph()?;
+
+        for node in self.nodes.iter_mut() {
+            if let GraphNode::Layer(layer) = node {
+                layer.init(opencl_state)?;
+            }
+        }
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    /// Tells all of the layers of the GraphModel weather or not they are being trained.
+    ///
+    /// This is already done by the `fit` method, so it is only needed when training the
+    /// GraphModel by hand with methods such as `compute_gradients` and `apply_gradients`.
+    pub fn set_training_mode(&mut self, is_training: bool) -> () {
+        for node in self.nodes.iter_mut() {
+            if let GraphNode::Layer(layer) = node {
+                layer.set_training_mode(is_training);
+            }
+        }
+    }
+
+    /// Tells all of the layers of the GraphModel weather or not the forward passes made while
+    /// training should update the statistics they keep for predicting, just like the
+    /// `set_updating_running_statistics` method of the sequential Model.
+    pub fn set_updating_running_statistics(&mut self, should_update: bool) -> () {
+        for node in self.nodes.iter_mut() {
+            if let GraphNode::Layer(layer) = node {
+                layer.set_updating_running_statistics(should_update);
+            }
+        }
+    }
+
+    /// Will fetch the outputs of the last node in the GraphModel.
+    ///
+    /// # Errors
+    ///
+    /// Yields an error if:
+    /// - the GraphModel was not intialized;
+    /// - the GraphModel has no nodes;
+    /// - the GraphModel has not yet predicted;
+    /// - something goes wrong when reading the data from the outputs buffer.
+    pub fn get_last_prediction(&self) -> Result<Vec<f32>, ModelGetLastPredictionError> {
+        if self.opencl_state.is_none() {
+            return Err(ModelGetLastPredictionError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if self.nodes.is_empty() {
+            return Err(ModelGetLastPredictionError::NoLayers);
+        }
+
+        let last_node = self.nodes.last().unwrap();
+
+        if last_node.get_last_outputs().is_none() {
+            return Err(ModelGetLastPredictionError::HasNotPredicted);
+        }
+
+        let buffer = last_node.get_last_outputs().unwrap();
+
+        Ok(Vec::<f32>::from_buffer(&buffer, false, state)?)
+    }
+
+    /// Will receive the inputs for the GraphModel and will give out a OpenCL buffer associated
+    /// with the outputs of its last node in the GPU.
+    ///
+    /// # Errors
+    ///
+    /// Yields an error if:
+    /// - the GraphModel was not initialized;
+    /// - there is no command queue in the OpenCLState;
+    /// - something goes wrong in the Vec to Buffer conversion;
+    /// - something goes wrong when predicting with the buffer.
+    pub fn predict(
+        &mut self,
+        input_samples: &Vec<Vec<f32>>,
+    ) -> Result<&Buffer<cl_float>, ModelPredictionError> {
+        if self.opencl_state.is_none() {
+            return Err(ModelPredictionError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(ModelPredictionError::NoCommandQueue);
+        }
+
+        assert!(input_samples.len() > 0);
+
+        let input_samples_buffer = input_samples
+            .par_iter()
+            .map(|x| x.to_vec())
+            .flatten()
+            .collect::<Vec<f32>>()
+            .to_buffer(false, state)?;
+
+        self.predict_with_buffer(&input_samples_buffer)?;
+
+        Ok(self.nodes.last().unwrap().get_last_outputs().unwrap())
+    }
+
+    /// This is the same as normal predict but it is made to run with a buffer instead of with a
+    /// Vec.
+    ///
+    /// # Errors
+    ///
+    /// Yields an error if:
+    /// - the GraphModel was not initialized;
+    /// - there is no OpenCL command queue in the OpenCLState;
+    /// - there are no nodes in the GraphModel;
+    /// - something goes wrong in the propagation of a layer or in a merge.
+    pub fn predict_with_buffer<'b>(
+        &'b mut self,
+        input_samples: &'b Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, ModelPredictionError> {
+        if self.opencl_state.is_none() {
+            return Err(ModelPredictionError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(ModelPredictionError::NoCommandQueue);
+        }
+
+        if self.nodes.is_empty() {
+            return Err(ModelPredictionError::NoLayers);
+        }
+
+        for node_index in 0..self.nodes.len() {
+            let sources = self.get_sources(node_index);
+
+            // the nodes that go into this one always come before it
+            let (previous_nodes, next_nodes) = self.nodes.split_at_mut(node_index);
+            let sources_outputs: Vec<&Buffer<cl_float>> = sources
+                .iter()
+                .map(|source| previous_nodes[*source].get_last_outputs().unwrap())
+                .collect();
+
+            match &mut next_nodes[0] {
+                GraphNode::Layer(layer) => {
+                    layer.propagate(sources_outputs.first().unwrap_or(&input_samples))?;
+                }
+                GraphNode::Add(merge) => merge.add(&sources_outputs, state)?,
+                GraphNode::Concatenate(merge) => merge.concatenate(&sources_outputs, state)?,
+            }
+        }
+
+        Ok(self.nodes.last().unwrap().get_last_outputs().unwrap())
+    }
+
+    /// Fits the GraphModel to best suit the training data in the same way as the `fit` method of
+    /// the sequential Model.
+    ///
+    /// # Errors
+    ///
+    /// Yields an error in the same cases as the `fit` method of the sequential Model.
+    pub fn fit(
+        &mut self,
+        training_input_samples: &Vec<Vec<f32>>,
+        training_expected_output_samples: &Vec<Vec<f32>>,
+        training_options: &mut TrainingOptions<'a>,
+    ) -> Result<TrainingResults, ModelFittingError> {
+        fit_model(
+            self,
+            training_input_samples,
+            training_expected_output_samples,
+            training_options,
+        )
+    }
+
+    /// Applies all the gradients calculated per node calling each layer's respective
+    /// **apply_gradients** function.
+    ///
+    /// The gradients are expected to be in the same order as the nodes, the same way they are
+    /// returned by the `compute_gradients` method.
+    ///
+    /// # Errors
+    ///
+    /// Yields an error if:
+    /// - the GraphModel was not initialiazed;
+    /// - there is no command queue inside the OpenCLState;
+    /// - there are no nodes inside the GraphModel;
+    /// - something goes wrong in the gradient application on a specific layer.
+    pub fn apply_gradients(
+        &mut self,
+        gradients_per_node: &[Vec<Gradient>],
+        optimizer: &mut dyn Optimizer<'a>,
+        timestep: usize,
+    ) -> Result<(), ModelGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(ModelGradientApplicationError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(ModelGradientApplicationError::NoCommandQueue);
+        }
+
+        if self.nodes.is_empty() {
+            return Err(ModelGradientApplicationError::NoLayers);
+        }
+
+        for (node_index, (node, gradients)) in self
+            .nodes
+            .iter_mut()
+            .zip(gradients_per_node.iter())
+            .enumerate()
+        {
+            if let GraphNode::Layer(layer) = node {
+                let result =
+                    layer.apply_gradients(gradients.as_slice(), optimizer, node_index, timestep);
+
+                if let Err(err) = result {
+                    return Err(ModelGradientApplicationError::LayerGradientApllication(
+                        node_index, err,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes the gradients for each one of the layers in the GraphModel going back through
+    /// the edges, summing up the derivatives of the nodes whose outputs go into many others.
+    ///
+    /// The gradients are returned in the same order as the nodes, with no gradients for the merge
+    /// nodes.
+    ///
+    /// # Errors
+    ///
+    /// Yields an error if:
+    /// - the GraphModel was not initialized;
+    /// - there is no command queue in the OpenClState;
+    /// - there are no nodes in the GraphModel;
+    /// - something goes wrong in the predicting with a buffer;
+    /// - something goes wrong in the initial loss fn gradients computation;
+    /// - something goes wrong in the layer gradient computation;
+    /// - something goes wrong when trying to pass on the derivatives between the nodes.
+    pub fn compute_gradients(
+        &mut self,
+        training_input_samples: &Buffer<cl_float>,
+        training_expected_output_samples: &Buffer<cl_float>,
+        loss_function: &dyn LossFunction,
+    ) -> Result<Vec<Vec<Gradient>>, ModelGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(ModelGradientComputationError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(ModelGradientComputationError::NoCommandQueue);
+        }
+
+        if self.nodes.is_empty() {
+            return Err(ModelGradientComputationError::NoLayers);
+        }
+
+        let nodes_amount = self.nodes.len();
+
+        let optimizing_for_softmax = loss_function.is_optimized_for_softmax()
+            && matches!(self.nodes.last(), Some(GraphNode::Layer(ModelLayer::SoftMax(_))));
+
+        let samples_amount = training_input_samples.size()?
+            / mem::size_of::<cl_float>()
+            / self.get_inputs_amount();
+
+        let training_actual_outputs = self.predict_with_buffer(training_input_samples)?;
+
+        let mut loss_to_outputs_derivatives: Vec<Option<Buffer<cl_float>>> =
+            (0..nodes_amount).map(|_| None).collect();
+
+        loss_to_outputs_derivatives[nodes_amount - 1] = Some(
+            loss_function.compute_loss_derivative_with_respect_to_output_samples(
+                training_actual_outputs,
+                training_expected_output_samples,
+                samples_amount,
+            )?,
+        );
+
+        let mut gradients: Vec<Vec<Gradient>> = (0..nodes_amount).map(|_| Vec::new()).collect();
+
+        for node_index in (0..nodes_amount).rev() {
+            // every node reaches the last one so all of them will have derivatives by now
+            let derivatives = loss_to_outputs_derivatives[node_index].take().unwrap();
+            let sources = self.get_sources(node_index);
+
+            match &self.nodes[node_index] {
+                GraphNode::Layer(_) if optimizing_for_softmax && node_index == nodes_amount - 1 => {
+                    if let Some(source) = sources.first() {
+                        accumulate_derivatives(
+                            &mut loss_to_outputs_derivatives,
+                            *source,
+                            derivatives,
+                            state,
+                        )?;
+                    }
+                }
+                GraphNode::Layer(layer) => {
+                    match layer.compute_gradients(&derivatives) {
+                        Ok(layer_gradients) => gradients[node_index] = layer_gradients,
+                        Err(err) => {
+                            return Err(ModelGradientComputationError::LayerGradientComputation(
+                                node_index, err,
+                            ))
+                        }
+                    }
+
+                    // the derivatives with respect to the inputs of the GraphModel are not needed
+                    if let Some(source) = sources.first() {
+                        match layer.compute_loss_to_input_derivatives(&derivatives) {
+                            Ok(loss_to_input_derivatives) => accumulate_derivatives(
+                                &mut loss_to_outputs_derivatives,
+                                *source,
+                                loss_to_input_derivatives,
+                                state,
+                            )?,
+                            Err(err) => {
+                                return Err(
+                                    ModelGradientComputationError::LayerLossToInputDifferentiation(
+                                        node_index, err,
+                                    ),
+                                )
+                            }
+                        }
+                    }
+                }
+                GraphNode::Add(_) => {
+                    for source in sources.iter() {
+                        accumulate_derivatives(
+                            &mut loss_to_outputs_derivatives,
+                            *source,
+                            derivatives.clone(state)?,
+                            state,
+                        )?;
+                    }
+                }
+                GraphNode::Concatenate(merge) => {
+                    for (input_index, source) in sources.iter().enumerate() {
+                        accumulate_derivatives(
+                            &mut loss_to_outputs_derivatives,
+                            *source,
+                            merge.slice_concatenated_derivatives(&derivatives, input_index, state)?,
+                            state,
+                        )?;
+                    }
+                }
+            }
+        }
+
+        Ok(gradients)
+    }
+}
+
+impl<'a> TrainableModel<'a> for GraphModel<'a> {
+    fn get_opencl_state(&self) -> Option<&'a OpenCLState> {
+        self.opencl_state
+    }
+
+    fn has_layers(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.nodes.last().unwrap().get_outputs_amount()
+    }
+
+    fn get_output_layer(&self) -> Option<&ModelLayer<'a>> {
+        match self.nodes.last()? {
+            GraphNode::Layer(layer) => Some(layer),
+            _ => None,
+        }
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.nodes.last()?.get_last_outputs()
+    }
+
+    fn set_training_mode(&mut self, is_training: bool) -> () {
+        GraphModel::set_training_mode(self, is_training)
+    }
+
+    fn set_updating_running_statistics(&mut self, should_update: bool) -> () {
+        GraphModel::set_updating_running_statistics(self, should_update)
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        timestep: usize,
+    ) -> Result<(), ModelFittingError> {
+        for (i, node) in self.nodes.iter_mut().enumerate() {
+            if let GraphNode::Layer(layer) = node {
+                if let Err(err) = layer.optimize_parameters(optimizer, i, timestep) {
+                    return Err(ModelFittingError::ParameterOptimization(i, err));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compute_gradients(
+        &mut self,
+        training_input_samples: &Buffer<cl_float>,
+        training_expected_output_samples: &Buffer<cl_float>,
+        loss_function: &dyn LossFunction,
+    ) -> Result<Vec<Vec<Gradient>>, ModelGradientComputationError> {
+        GraphModel::compute_gradients(
+            self,
+            training_input_samples,
+            training_expected_output_samples,
+            loss_function,
+        )
+    }
+
+    fn apply_gradients(
+        &mut self,
+        gradients_per_layer: &[Vec<Gradient>],
+        optimizer: &mut dyn Optimizer<'a>,
+        timestep: usize,
+    ) -> Result<(), ModelGradientApplicationError> {
+        GraphModel::apply_gradients(self, gradients_per_layer, optimizer, timestep)
+    }
+
+    fn predict_with_buffer<'b>(
+        &'b mut self,
+        input_samples: &'b Buffer<cl_float>,
+    ) -> Result<&'b Buffer<cl_float>, ModelPredictionError> {
+        GraphModel::predict_with_buffer(self, input_samples)
+    }
+
+    fn as_training_model(&mut self) -> TrainingModel<'_, 'a> {
+        TrainingModel::GraphModel(self)
+    }
+
+    fn save_parameters(&mut self) -> Result<Vec<u8>, SyncDataError> {
+        self.sync_data_from_buffers_to_host()?;
+
+        let mut parameters = Vec::new();
+        save_noschema(&mut parameters, SAVED_PARAMETERS_VERSION, &self.nodes)?;
+
+        Ok(parameters)
+    }
+
+    fn load_parameters(
+        &mut self,
+        mut parameters: &[u8],
+        opencl_state: &'a OpenCLState,
+    ) -> Result<(), LayerInitializationError> {
+        self.nodes = load_noschema(&mut parameters, SAVED_PARAMETERS_VERSION)?;
+
+        self.init(opencl_state)
+    }
+}
+
+#[cfg(test)]
+mod graph_model_tests {
+    use crate::{
+        layers::{activations::TanH, Dense, LayerInitializationError},
+        loss_functions::MeanSquared,
+        optimizers,
+        types::{HaltingCondition, ModelLayer, TrainingOptions, TrainingVerbosity},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::{GraphModel, GraphNode};
+
+    #[test]
+    fn should_merge_nodes_and_sum_the_derivatives_of_nodes_that_fan_out() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![0.1, 0.5, 0.7, -0.3];
+        let expected_outputs = vec![0.2, -0.1, 0.4, 0.5, 0.3, -0.2];
+
+        let mut first_dense = Dense::new_raw(2, 2);
+        first_dense.weights = vec![vec![0.3, -0.2], vec![0.4, 0.1]];
+        first_dense.biases = vec![0.1, -0.1];
+
+        let mut second_dense = Dense::new_raw(2, 1);
+        second_dense.weights = vec![vec![-0.5], vec![0.2]];
+        second_dense.biases = vec![0.2];
+
+        // the first Dense goes both into its activation and into a residual connection with it,
+        // which is then concatenated with the outputs of the second Dense
+        let mut model = GraphModel::new(
+            vec![
+                ModelLayer::from(first_dense).into(),
+                TanH::new(2).into(),
+                GraphNode::add(),
+                ModelLayer::from(second_dense).into(),
+                GraphNode::concatenate(),
+            ],
+            vec![(0, 1), (0, 2), (1, 2), (2, 4), (3, 4)],
+        );
+        model.init(&opencl_state).expect("unable to init the GraphModel");
+
+        let expected_predictions = vec![0.6485, -0.1399, 0.25, 0.3777, -0.5336, -0.21];
+        let expected_gradients = vec![
+            vec![-0.0277, -0.3782, 0.1659, 0.1477],
+            vec![0.2038, -0.563],
+            vec![-0.0073, -0.024],
+            vec![-0.0533],
+        ];
+
+        let inputs_buffer = inputs
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the inputs buffer");
+        let expected_outputs_buffer = expected_outputs
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the expected outputs buffer");
+
+        let loss = MeanSquared::new();
+
+        let gradients = model
+            .compute_gradients(&inputs_buffer, &expected_outputs_buffer, &loss)
+            .expect("unable to compute the gradients of the GraphModel");
+
+        let predictions = model
+            .get_last_prediction()
+            .expect("unable to get the last prediction of the GraphModel");
+
+        assert_approx_equal_distance(&predictions, &expected_predictions, 0.01);
+
+        assert!(gradients[1].is_empty());
+        assert!(gradients[2].is_empty());
+        assert!(gradients[4].is_empty());
+
+        let actual_gradients: Vec<Vec<f32>> = gradients[0]
+            .iter()
+            .chain(gradients[3].iter())
+            .map(|gradient| {
+                Vec::<f32>::from_buffer(&gradient.value, false, &opencl_state)
+                    .expect("unable to read a gradients buffer")
+            })
+            .collect();
+
+        for (actual_gradient, expected_gradient) in
+            actual_gradients.iter().zip(expected_gradients.iter())
+        {
+            assert_approx_equal_distance(actual_gradient, expected_gradient, 0.01);
+        }
+    }
+
+    #[test]
+    fn should_decrease_error_with_a_residual_connection() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let mut model = GraphModel::new(
+            vec![
+                Dense::new(2, 3).into(),
+                TanH::new(3).into(),
+                Dense::new(3, 3).into(),
+                GraphNode::add(),
+                Dense::new(3, 1).into(),
+                TanH::new(1).into(),
+            ],
+            vec![(0, 1), (1, 2), (1, 3), (2, 3), (3, 4), (4, 5)],
+        );
+        model.init(&opencl_state).expect("unable to init the GraphModel");
+
+        let training_input_samples = vec![
+            vec![0.0_f32, 0.0_f32],
+            vec![1.0_f32, 0.0_f32],
+            vec![0.0_f32, 1.0_f32],
+            vec![1.0_f32, 1.0_f32],
+        ];
+
+        let training_output_samples = vec![
+            vec![0.0_f32],
+            vec![1.0_f32],
+            vec![1.0_f32],
+            vec![0.0_f32],
+        ];
+
+        let mut loss = MeanSquared::new();
+        let mut optimizer = optimizers::Basic::new(0.1);
+
+        let training_results = model
+            .fit(
+                &training_input_samples,
+                &training_output_samples,
+                &mut TrainingOptions {
+                    loss_fn: &mut loss,
+                    verbosity: TrainingVerbosity {
+                        show_current_epoch: false,
+                        show_epoch_progress: false,
+                        show_epoch_elapsed: false,
+                        print_accuracy: false,
+                        print_loss: false,
+                        halting_condition_warning: false,
+                    },
+                    halting_condition: Some(HaltingCondition::MinLossReached(0.1)),
+                    compute_accuracy: false,
+                    compute_loss: true,
+                    optimizer: &mut optimizer,
+                    batch_size: 4,
+                    epochs: 10000,
+                    validation_data: None,
+                    shuffle: false,
+                    shuffle_seed: None,
+                    callbacks: Vec::new(),
+                },
+            )
+            .expect("unable to fit the GraphModel");
+
+        let last_loss = training_results.loss_per_training_steps.last().unwrap();
+
+        assert!(last_loss <= &0.1);
+    }
+
+    #[test]
+    fn should_not_initialize_invalid_graphs() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let mut backwards_model = GraphModel::new(
+            vec![Dense::new(2, 3).into(), TanH::new(3).into()],
+            vec![(1, 0)],
+        );
+
+        assert!(matches!(
+            backwards_model.init(&opencl_state),
+            Err(LayerInitializationError::InvalidEdge(1, 0))
+        ));
+
+        let mut mismatched_model = GraphModel::new(
+            vec![
+                Dense::new(2, 3).into(),
+                TanH::new(3).into(),
+                Dense::new(2, 3).into(),
+                GraphNode::add(),
+            ],
+            vec![(0, 1), (0, 2), (1, 3), (2, 3)],
+        );
+
+        assert!(matches!(
+            mismatched_model.init(&opencl_state),
+            Err(LayerInitializationError::NodesShapesDontMatch(0, 2))
+        ));
+
+        let mut disconnected_model = GraphModel::new(
+            vec![Dense::new(2, 3).into(), TanH::new(3).into(), GraphNode::add()],
+            vec![(0, 2)],
+        );
+
+        assert!(matches!(
+            disconnected_model.init(&opencl_state),
+            Err(LayerInitializationError::DisconnectedNode { node_index: 1 })
+        ));
+
+        let mut mismatched_inputs_model = GraphModel::new(
+            vec![
+                Dense::new(2, 3).into(),
+                Dense::new(4, 3).into(),
+                GraphNode::concatenate(),
+            ],
+            vec![(0, 2), (1, 2)],
+        );
+
+        assert!(matches!(
+            mismatched_inputs_model.init(&opencl_state),
+            Err(LayerInitializationError::InputNodesShapesDontMatch { node_index: 1 })
+        ));
+    }
+}
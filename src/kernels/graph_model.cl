@@ -0,0 +1,31 @@
+// copies `width` numbers of each sample from one buffer into another, starting at a possibly
+// different offset inside of each sample in both of them, this is used both for concatenating
+// the outputs of many nodes and for taking the part of the derivatives that belongs to each one
+kernel void copy_per_sample(
+    global float* source,
+    global float* destination,
+
+    int source_width,
+    int source_offset,
+
+    int destination_width,
+    int destination_offset,
+
+    int width,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int index = get_global_id(1);
+
+    if (index >= width) {
+        return;
+    }
+
+    destination[sample_index * destination_width + destination_offset + index] =
+        (float)source[sample_index * source_width + source_offset + index];
+}
@@ -15,4 +15,32 @@ kernel void compute_accuracy_per_output(
     float expected_output = (float)expected_outputs[index];
     float output = (float)outputs[index];
     accuracies[index] = 1.0f - fabs(output - expected_output) / fmax(expected_output, output);
-}
\ No newline at end of file
+}
+
+// copies each one of the samples into the position it was shuffled into, so that the training
+// samples can be shuffled without uploading them again
+kernel void gather_samples(
+    global float *samples,
+    global int *indices,
+
+    global float *gathered_samples,
+
+    int features_amount,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int feature_index = get_global_id(1);
+
+    if (feature_index >= features_amount) {
+        return;
+    }
+
+    int source_index = indices[sample_index] * features_amount + feature_index;
+
+    gathered_samples[sample_index * features_amount + feature_index] = (float)samples[source_index];
+}
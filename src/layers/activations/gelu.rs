@@ -0,0 +1,124 @@
+//! The module that contains the GELU activation function.
+
+use opencl3::{device::cl_float, memory::Buffer};
+
+use intricate_macros::ActivationLayer;
+
+use savefile_derive::Savefile;
+
+use crate::utils::OpenCLState;
+
+const PROGRAM_NAME: &str = "GELU";
+const PROGRAM_SOURCE: &str = include_str!("kernels/gelu.cl");
+const PROPAGATE_KERNEL_NAME: &str = "propagate";
+const BACK_PROPAGATE_KERNEL_NAME: &str = "back_propagate";
+
+#[derive(Debug, Savefile, ActivationLayer)]
+/// The Gaussian Error Linear Unit activation function, commonly used in Transformers, defined as
+/// `f(x)=x*Φ(x)` where `Φ` is the cumulative distribution function of the standard normal
+/// distribution. Intricate uses the usual tanh approximation of it which is
+/// `f(x)=0.5*x*(1+tanh(sqrt(2/π)*(x+0.044715*x^3)))`.
+///
+/// # Example
+///
+/// ```rust
+/// use intricate::layers::{
+///     activations::GELU,
+///     Layer,
+/// };
+///
+/// let my_gelu: GELU = GELU::new_raw(10);
+/// ```
+pub struct GELU<'a> {
+    /// The amount of inputs that this instance of the GELU function expects.
+    pub inputs_amount: usize,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned last inputs of this instance of GELU.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The last outputs of this instance of GELU.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+#[cfg(test)]
+mod gelu_tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::GELU;
+
+    #[test]
+    fn should_propagate_and_back_propagate_to_correct_values() {
+        let samples_amount = 30;
+        let numbers_amount = 20;
+
+        let mut rng = thread_rng();
+
+        let inputs: Vec<f32> = (0..(samples_amount * numbers_amount))
+            .map(|_| rng.gen_range(-5.0_f32..5.0_f32))
+            .collect();
+        let loss_to_output_derivatives: Vec<f32> = (0..(samples_amount * numbers_amount))
+            .map(|_| rng.gen_range(-1.0_f32..1.0_f32))
+            .collect();
+
+        let sqrt_2_over_pi = (2.0 / std::f32::consts::PI).sqrt();
+        let expected_outputs: Vec<f32> = inputs
+            .iter()
+            .map(|&x| 0.5 * x * (1.0 + (sqrt_2_over_pi * (x + 0.044715 * x.powi(3))).tanh()))
+            .collect();
+        let expected_loss_to_input_derivatives: Vec<f32> = inputs
+            .iter()
+            .zip(loss_to_output_derivatives.iter())
+            .map(|(&x, derivative)| {
+                let tanh_inner = (sqrt_2_over_pi * (x + 0.044715 * x.powi(3))).tanh();
+                let output_to_input_derivative = 0.5 * (1.0 + tanh_inner)
+                    + 0.5 * x * (1.0 - tanh_inner.powi(2))
+                        * sqrt_2_over_pi
+                        * (1.0 + 3.0 * 0.044715 * x.powi(2));
+                output_to_input_derivative * derivative
+            })
+            .collect();
+
+        let opencl_state = setup_opencl(DeviceType::GPU).unwrap();
+
+        let mut gelu = GELU::new_raw(numbers_amount);
+        gelu.init(&opencl_state).unwrap();
+
+        let inputs_buffer = inputs.to_buffer(false, &opencl_state).unwrap();
+        let outputs_buffer = gelu.propagate(&inputs_buffer).unwrap();
+        let actual_outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state).unwrap();
+
+        assert_approx_equal_distance(&actual_outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .unwrap();
+        let loss_to_input_derivatives_buffer = gelu
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .unwrap();
+        let actual_loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .unwrap();
+
+        assert_approx_equal_distance(
+            &actual_loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! The module that contains the HardSigmoid activation function.
+
+use opencl3::{device::cl_float, memory::Buffer};
+
+use intricate_macros::ActivationLayer;
+
+use savefile_derive::Savefile;
+
+use crate::utils::OpenCLState;
+
+const PROGRAM_NAME: &str = "HARD_SIGMOID";
+const PROGRAM_SOURCE: &str = include_str!("kernels/hard_sigmoid.cl");
+const PROPAGATE_KERNEL_NAME: &str = "propagate";
+const BACK_PROPAGATE_KERNEL_NAME: &str = "back_propagate";
+
+#[derive(Debug, Savefile, ActivationLayer)]
+/// The Hard Sigmoid activation function, a piecewise linear approximation of the Sigmoid that is
+/// much cheaper to compute, defined as `f(x)=max(0, min(1, x/6+0.5))`.
+///
+/// # Example
+///
+/// ```rust
+/// use intricate::layers::{
+///     activations::HardSigmoid,
+///     Layer,
+/// };
+///
+/// let my_hard_sigmoid: HardSigmoid = HardSigmoid::new_raw(10);
+/// ```
+pub struct HardSigmoid<'a> {
+    /// The amount of inputs that this instance of the HardSigmoid function expects.
+    pub inputs_amount: usize,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned last inputs of this instance of HardSigmoid.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The last outputs of this instance of HardSigmoid.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+#[cfg(test)]
+mod hard_sigmoid_tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::HardSigmoid;
+
+    #[test]
+    fn should_propagate_and_back_propagate_to_correct_values() {
+        let samples_amount = 30;
+        let numbers_amount = 20;
+
+        let mut rng = thread_rng();
+
+        let inputs: Vec<f32> = (0..(samples_amount * numbers_amount))
+            .map(|_| rng.gen_range(-5.0_f32..5.0_f32))
+            .collect();
+        let loss_to_output_derivatives: Vec<f32> = (0..(samples_amount * numbers_amount))
+            .map(|_| rng.gen_range(-1.0_f32..1.0_f32))
+            .collect();
+
+        let expected_outputs: Vec<f32> = inputs.iter().map(|&x| (x / 6.0 + 0.5).clamp(0.0, 1.0)).collect();
+        let expected_loss_to_input_derivatives: Vec<f32> = inputs
+            .iter()
+            .zip(loss_to_output_derivatives.iter())
+            .map(|(&x, derivative)| {
+                let output_to_input_derivative: f32 = if x > -3.0 && x < 3.0 {
+                    1.0 / 6.0
+                } else {
+                    0.0
+                };
+                output_to_input_derivative * derivative
+            })
+            .collect();
+
+        let opencl_state = setup_opencl(DeviceType::GPU).unwrap();
+
+        let mut hardsigmoid = HardSigmoid::new_raw(numbers_amount);
+        hardsigmoid.init(&opencl_state).unwrap();
+
+        let inputs_buffer = inputs.to_buffer(false, &opencl_state).unwrap();
+        let outputs_buffer = hardsigmoid.propagate(&inputs_buffer).unwrap();
+        let actual_outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state).unwrap();
+
+        assert_approx_equal_distance(&actual_outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .unwrap();
+        let loss_to_input_derivatives_buffer = hardsigmoid
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .unwrap();
+        let actual_loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .unwrap();
+
+        assert_approx_equal_distance(
+            &actual_loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
@@ -0,0 +1,54 @@
+// uses the same tanh approximation of the Gaussian Error Linear Unit that is used by GPT and BERT
+#define SQRT_2_OVER_PI 0.7978845608f
+#define GELU_CUBIC_COEFFICIENT 0.044715f
+
+kernel void propagate(
+    global float* flattened_input_samples,
+
+    global float* flattened_output_samples,
+
+    int size
+) {
+    int index = get_global_id(0);
+
+    if (index >= size) {
+        return;
+    }
+
+    float input = (float)flattened_input_samples[index];
+    flattened_output_samples[index] = 0.5f * input * (1.0f + tanh(SQRT_2_OVER_PI * (input + GELU_CUBIC_COEFFICIENT * input * input * input)));
+}
+
+kernel void back_propagate(
+    global float* flattened_loss_to_output_derivatives,
+    global float* flattened_output_samples,
+    global float* flattened_input_samples,
+
+    global float* flattened_loss_to_input_derivatives,
+
+    int outputs_amount,
+    int samples_amount,
+    int inputs_amount
+) {
+    int sample_index = get_global_id(0);
+    int input_index = get_global_id(1);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_i = sample_index * inputs_amount + input_index;
+
+    float input = (float)flattened_input_samples[flat_input_i];
+    float inner = SQRT_2_OVER_PI * (input + GELU_CUBIC_COEFFICIENT * input * input * input);
+    float tanh_inner = tanh(inner);
+    float output_to_input_derivative = 0.5f * (1.0f + tanh_inner)
+        + 0.5f * input * (1.0f - tanh_inner * tanh_inner)
+        * SQRT_2_OVER_PI * (1.0f + 3.0f * GELU_CUBIC_COEFFICIENT * input * input);
+
+    flattened_loss_to_input_derivatives[flat_input_i] = output_to_input_derivative
+        * (float)flattened_loss_to_output_derivatives[sample_index * outputs_amount + input_index];
+}
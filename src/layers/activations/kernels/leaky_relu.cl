@@ -0,0 +1,50 @@
+kernel void propagate(
+    global float* flattened_input_samples,
+
+    global float* flattened_output_samples,
+
+    int size,
+
+    float alpha
+) {
+    int index = get_global_id(0);
+
+    if (index >= size) {
+        return;
+    }
+
+    float input = (float)flattened_input_samples[index];
+    flattened_output_samples[index] = input > 0.0f ? input : alpha * input;
+}
+
+kernel void back_propagate(
+    global float* flattened_loss_to_output_derivatives,
+    global float* flattened_output_samples,
+    global float* flattened_input_samples,
+
+    global float* flattened_loss_to_input_derivatives,
+
+    int outputs_amount,
+    int samples_amount,
+    int inputs_amount,
+
+    float alpha
+) {
+    int sample_index = get_global_id(0);
+    int input_index = get_global_id(1);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_i = sample_index * inputs_amount + input_index;
+
+    float input = (float)flattened_input_samples[flat_input_i];
+    float output_to_input_derivative = input > 0.0f ? 1.0f : alpha;
+
+    flattened_loss_to_input_derivatives[flat_input_i] = output_to_input_derivative
+        * (float)flattened_loss_to_output_derivatives[sample_index * outputs_amount + input_index];
+}
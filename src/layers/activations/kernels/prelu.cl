@@ -0,0 +1,101 @@
+// every slope is shared by `features_per_slope` consecutive inputs, which is 1 when there is one
+// slope per feature and the volume of the images when there is one slope per channel
+
+kernel void propagate(
+    global float* flattened_input_samples,
+    global float* slopes,
+
+    global float* flattened_output_samples,
+
+    int inputs_amount,
+    int features_per_slope,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_i = sample_index * inputs_amount + input_index;
+
+    float input = (float)flattened_input_samples[flat_input_i];
+    float slope = (float)slopes[input_index / features_per_slope];
+
+    flattened_output_samples[flat_input_i] = input > 0.0f ? input : slope * input;
+}
+
+kernel void compute_slopes_gradients(
+    global float* flattened_input_samples,
+    global float* flattened_loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int inputs_amount,
+    int features_per_slope,
+
+    int samples_amount
+) {
+    int slope_index = get_global_id(0);
+
+    if (slope_index >= inputs_amount / features_per_slope) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int slope_start = sample_index * inputs_amount + slope_index * features_per_slope;
+
+        for (int feature_index = 0; feature_index < features_per_slope; feature_index++) {
+            float input = (float)flattened_input_samples[slope_start + feature_index];
+
+            // the slope only takes part in the outputs of the negative inputs
+            if (input < 0.0f) {
+                gradient += input * (float)flattened_loss_to_output_derivatives[slope_start + feature_index];
+            }
+        }
+    }
+
+    gradients[slope_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_input_derivatives(
+    global float* flattened_input_samples,
+    global float* slopes,
+    global float* flattened_loss_to_output_derivatives,
+
+    global float* flattened_loss_to_input_derivatives,
+
+    int inputs_amount,
+    int features_per_slope,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_i = sample_index * inputs_amount + input_index;
+
+    float input = (float)flattened_input_samples[flat_input_i];
+    float output_to_input_derivative = input > 0.0f ? 1.0f : (float)slopes[input_index / features_per_slope];
+
+    flattened_loss_to_input_derivatives[flat_input_i] =
+        output_to_input_derivative * (float)flattened_loss_to_output_derivatives[flat_input_i];
+}
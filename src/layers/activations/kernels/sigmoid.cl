@@ -17,6 +17,7 @@ kernel void propagate(
 kernel void back_propagate(
     global float* flattened_loss_to_output_derivatives,
     global float* flattened_output_samples,
+    global float* flattened_input_samples,
 
     global float* flattened_loss_to_input_derivatives,
 
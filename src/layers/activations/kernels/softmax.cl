@@ -152,4 +152,90 @@ kernel void back_propagate(
     }
 
     flattened_loss_to_input_derivatives[flat_input_i] = total;
-}
\ No newline at end of file
+}
+
+// the inputs of each sample are all of the positions of the first channel, then all of the
+// positions of the second channel and so on, and each position is normalized over the channels
+kernel void propagate_over_channels(
+    global float* inputs,
+    global float* outputs,
+
+    int channels,
+    int positions_amount,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int position_index = get_global_id(1);
+
+    if (position_index >= positions_amount) {
+        return;
+    }
+
+    int first_i = sample_index * channels * positions_amount + position_index;
+
+    float max_input = (float)inputs[first_i];
+    for (int channel_index = 1; channel_index < channels; channel_index++) {
+        max_input = max(max_input, (float)inputs[first_i + channel_index * positions_amount]);
+    }
+
+    float exponentials_sum = 0.0f;
+    for (int channel_index = 0; channel_index < channels; channel_index++) {
+        int flat_i = first_i + channel_index * positions_amount;
+        float exponential = exp((float)inputs[flat_i] - max_input);
+
+        outputs[flat_i] = exponential;
+        exponentials_sum += exponential;
+    }
+
+    for (int channel_index = 0; channel_index < channels; channel_index++) {
+        int flat_i = first_i + channel_index * positions_amount;
+
+        outputs[flat_i] = (float)outputs[flat_i] / exponentials_sum;
+    }
+}
+
+kernel void back_propagate_over_channels(
+    global float* loss_to_output_derivatives,
+    global float* outputs,
+
+    global float* loss_to_input_derivatives,
+
+    int channels,
+    int positions_amount,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= channels * positions_amount) {
+        return;
+    }
+
+    int position_index = input_index % positions_amount;
+    int first_i = sample_index * channels * positions_amount + position_index;
+    int flat_input_i = sample_index * channels * positions_amount + input_index;
+
+    // the derivative of the output of channel j with respect to the input of channel i
+    // is output_j * (1 - output_j) when i = j, and -output_i * output_j otherwise
+    float weighted_sum = 0.0f;
+    for (int channel_index = 0; channel_index < channels; channel_index++) {
+        int flat_i = first_i + channel_index * positions_amount;
+
+        weighted_sum += (float)loss_to_output_derivatives[flat_i] * (float)outputs[flat_i];
+    }
+
+    float output = (float)outputs[flat_input_i];
+
+    loss_to_input_derivatives[flat_input_i] =
+        output * ((float)loss_to_output_derivatives[flat_input_i] - weighted_sum);
+}
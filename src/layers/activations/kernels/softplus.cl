@@ -0,0 +1,46 @@
+kernel void propagate(
+    global float* flattened_input_samples,
+
+    global float* flattened_output_samples,
+
+    int size
+) {
+    int index = get_global_id(0);
+
+    if (index >= size) {
+        return;
+    }
+
+    float input = (float)flattened_input_samples[index];
+    flattened_output_samples[index] = fmax(input, 0.0f) + log1p(exp(-fabs(input)));
+}
+
+kernel void back_propagate(
+    global float* flattened_loss_to_output_derivatives,
+    global float* flattened_output_samples,
+    global float* flattened_input_samples,
+
+    global float* flattened_loss_to_input_derivatives,
+
+    int outputs_amount,
+    int samples_amount,
+    int inputs_amount
+) {
+    int sample_index = get_global_id(0);
+    int input_index = get_global_id(1);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_i = sample_index * inputs_amount + input_index;
+
+    float input = (float)flattened_input_samples[flat_input_i];
+    float output_to_input_derivative = 1.0f / (1.0f + exp(-input));
+
+    flattened_loss_to_input_derivatives[flat_input_i] = output_to_input_derivative
+        * (float)flattened_loss_to_output_derivatives[sample_index * outputs_amount + input_index];
+}
@@ -5,15 +5,36 @@
 //! - Sigmoid
 //! - TanH (Hyperbolic Tangent)
 //! - SoftMax
+//! - LeakyReLU (Leaky Rectified Linear Unit)
+//! - ELU (Exponential Linear Unit)
+//! - GELU (Gaussian Error Linear Unit)
+//! - Swish
+//! - Softplus
+//! - HardSigmoid
+//! - PReLU (Parametric Rectified Linear Unit)
 
+pub mod elu;
+pub mod gelu;
+pub mod hard_sigmoid;
+pub mod leaky_relu;
+pub mod prelu;
 pub mod relu;
 pub mod sigmoid;
 pub mod softmax;
+pub mod softplus;
+pub mod swish;
 pub mod tanh;
 
+pub use elu::ELU;
+pub use gelu::GELU;
+pub use hard_sigmoid::HardSigmoid;
+pub use leaky_relu::LeakyReLU;
+pub use prelu::PReLU;
 pub use relu::ReLU;
 pub use sigmoid::Sigmoid;
 pub use softmax::SoftMax;
+pub use softplus::Softplus;
+pub use swish::Swish;
 pub use tanh::TanH;
 
 use crate::utils::{opencl::EnsureKernelsAndProgramError, OpenCLState};
@@ -25,6 +46,13 @@ pub(crate) fn compile_activations(
     sigmoid::compile_sigmoid(opencl_state)?;
     softmax::compile_softmax(opencl_state)?;
     tanh::compile_tanh(opencl_state)?;
+    leaky_relu::compile_leakyrelu(opencl_state)?;
+    elu::compile_elu(opencl_state)?;
+    gelu::compile_gelu(opencl_state)?;
+    swish::compile_swish(opencl_state)?;
+    softplus::compile_softplus(opencl_state)?;
+    hard_sigmoid::compile_hardsigmoid(opencl_state)?;
+    prelu::compile_prelu(opencl_state)?;
 
     Ok(())
 }
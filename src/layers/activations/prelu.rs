@@ -0,0 +1,540 @@
+//! The module that contains the Parametric Rectified Linear Unit activation function.
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use savefile_derive::Savefile;
+
+use crate::{
+    layers::{
+        compute_update_vectors,
+        initializers::{ConstantInitializer, Initializer, InitializerTrait},
+        Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+        LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+        ParametersOptimizationError,
+    },
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+const PROGRAM_NAME: &str = "PRELU";
+const PROGRAM_SOURCE: &str = include_str!("kernels/prelu.cl");
+const PROPAGATE_KERNEL_NAME: &str = "propagate";
+const COMPUTE_SLOPES_GRADIENTS_KERNEL_NAME: &str = "compute_slopes_gradients";
+const COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_prelu(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        PROPAGATE_KERNEL_NAME.to_string(),
+        COMPUTE_SLOPES_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Savefile)]
+/// The Parametric Rectified Linear Unit activation function, defined as `f(x)=x` for positive
+/// inputs and `f(x)=a*x` otherwise, just like a LeakyReLU but with the slope `a` being a
+/// trainable parameter instead of a constant.
+///
+/// By default there is one slope for each input, but the slopes can also be shared by all of the
+/// pixels of a same channel when this comes after a convolutional layer by using
+/// `set_channels`.
+///
+/// # Example
+///
+/// ```rust
+/// use intricate::layers::activations::PReLU;
+///
+/// // one slope for each one of the 16 channels of the 28x28 images
+/// let my_prelu: PReLU = PReLU::new_raw(28 * 28 * 16).set_channels(16);
+/// ```
+pub struct PReLU<'a> {
+    /// The amount of inputs that this instance of the PReLU function expects.
+    pub inputs_amount: usize,
+    /// The amount of channels that share the same slope for all of their inputs, or None if
+    /// every input has its own slope.
+    pub channels: Option<usize>,
+
+    /// The slopes of the function for the negative inputs.
+    pub slopes: Vec<f32>,
+
+    /// The initializers that will generate the initial slopes of the PReLU, which is a
+    /// ConstantInitializer of 0.25 by default.
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the slopes of this PReLU.
+    pub slopes_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned last inputs of this instance of PReLU.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The last outputs of this instance of PReLU.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> PReLU<'a> {
+    /// Creates a raw version of the PReLU activation function, this is good for being used when
+    /// you don't want to use the layer in a Model.
+    pub fn new_raw(inputs_amount: usize) -> PReLU<'a> {
+        let mut initializers = HashMap::with_capacity(1);
+        initializers.insert("slopes".to_string(), ConstantInitializer::new(0.25).into());
+
+        PReLU {
+            inputs_amount,
+            channels: None,
+
+            slopes: Vec::default(),
+
+            initializers,
+
+            slopes_buffer: None,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the PReLU activation function, to be used with a Model.
+    pub fn new(inputs_amount: usize) -> ModelLayer<'a> {
+        Self::new_raw(inputs_amount).into()
+    }
+
+    /// Makes all of the inputs of a same channel share the same slope, considering the inputs
+    /// to be laid out channel by channel as they come out of a Conv2D, and returns the mutated
+    /// Self.
+    pub fn set_channels(mut self, channels: usize) -> Self {
+        assert!(
+            channels > 0 && self.inputs_amount % channels == 0,
+            "the inputs amount of a PReLU must be divisible by its amount of channels"
+        );
+        self.channels = Some(channels);
+
+        self
+    }
+
+    /// Gets the amount of slopes this PReLU has, one per channel or one per input.
+    pub fn get_slopes_amount(&self) -> usize {
+        self.channels.unwrap_or(self.inputs_amount)
+    }
+
+    fn get_features_per_slope(&self) -> usize {
+        self.inputs_amount / self.get_slopes_amount()
+    }
+}
+
+impl<'a> Layer<'a> for PReLU<'a> {
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "slopes" => Some(self.slopes.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.slopes_buffer.is_some() {
+            drop(self.slopes_buffer.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.slopes_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "slopes".to_string(),
+            });
+        }
+
+        self.slopes = Vec::<f32>::from_buffer(self.slopes_buffer.as_ref().unwrap(), false, state)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.slopes.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("slopes") {
+                self.slopes = initializer.initialize_1d(self.get_slopes_amount(), self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "slopes",
+                ));
+            }
+        }
+
+        self.slopes_buffer = Some(self.slopes.to_buffer(false, opencl_state)?);
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.inputs_amount != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.inputs_amount;
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let outputs = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(PROPAGATE_KERNEL_NAME)?)
+            .set_arg(inputs)
+            .set_arg(self.slopes_buffer.as_ref().unwrap())
+            .set_arg(&outputs)
+            .set_arg(&(self.inputs_amount as cl_int))
+            .set_arg(&(self.get_features_per_slope() as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.inputs_amount])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_outputs_buffer = Some(outputs);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivatives: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        if self.last_inputs_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivatives.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.inputs_amount != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.inputs_amount;
+
+        let slopes_gradients =
+            empty_buffer(self.get_slopes_amount(), CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_SLOPES_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&slopes_gradients)
+            .set_arg(&(self.inputs_amount as cl_int))
+            .set_arg(&(self.get_features_per_slope() as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_size(self.get_slopes_amount())
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(vec![Gradient {
+            optimizable: true,
+            parameter_id: "slopes".to_string(),
+            value: slopes_gradients,
+        }])
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.slopes_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "slopes".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.slopes_buffer.as_mut().unwrap(),
+            "slopes".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_model_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != 1 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_model_index,
+            timestep,
+            state,
+        )?;
+
+        let slopes_buffer = self.slopes_buffer.as_mut().unwrap();
+        slopes_buffer.subtract_inplc(&update_vectors[0], state)?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        if self.slopes_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::MissingParameter(
+                "slopes",
+            ));
+        }
+
+        if self.last_inputs_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.inputs_amount != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.inputs_amount;
+
+        let loss_to_input_derivatives_buffer =
+            empty_buffer(derivatives_total_count, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME)?)
+            .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+            .set_arg(self.slopes_buffer.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivative)
+            .set_arg(&loss_to_input_derivatives_buffer)
+            .set_arg(&(self.inputs_amount as cl_int))
+            .set_arg(&(self.get_features_per_slope() as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.inputs_amount])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod prelu_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::PReLU;
+
+    #[test]
+    fn should_propagate_and_compute_gradients_with_per_channel_slopes() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            1.0, -2.0, -0.5, 3.0,
+            -1.0, 0.5, 2.0, -4.0,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.5, 1.0, -1.0, 0.2,
+            0.4, -0.3, 0.6, 0.5,
+        ];
+
+        let expected_outputs = vec![
+            1.0, -0.2, -0.15, 3.0,
+            -0.1, 0.5, 2.0, -1.2,
+        ];
+        let expected_slopes_gradients = vec![-1.2, -0.75];
+        let expected_loss_to_input_derivatives = vec![
+            0.5, 0.1, -0.3, 0.2,
+            0.04, -0.3, 0.6, 0.15,
+        ];
+
+        let mut prelu = PReLU::new_raw(4).set_channels(2);
+        prelu.slopes = vec![0.1, 0.3];
+        prelu.init(&opencl_state).expect("unable to init PReLU");
+
+        let outputs_buffer = prelu
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the PReLU");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = prelu
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the PReLU");
+        let slopes_gradients =
+            Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+                .expect("unable to read the slopes gradients buffer");
+
+        assert_approx_equal_distance(&slopes_gradients, &expected_slopes_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = prelu
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the PReLU");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
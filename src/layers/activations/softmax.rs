@@ -20,14 +20,17 @@ use crate::{
     }, optimizers::Optimizer, types::ModelLayer,
 };
 
-const PROGRAM_NAME: &str = "SOFTMAX";
+pub(crate) const PROGRAM_NAME: &str = "SOFTMAX";
 const PROGRAM_SOURCE: &str = include_str!("kernels/softmax.cl");
-const PROPAGATE_KERNEL_NAME: &str = "propagate";
+pub(crate) const PROPAGATE_KERNEL_NAME: &str = "propagate";
 
-const CALCULATE_EXPONENTIALS_KERNEL_NAME: &str = "calculate_exponentials";
-const SUM_EXPONENTIALS_PER_SAMPLE_KERNEL_NAME: &str = "sum_exponentials_per_sample";
-const FIND_MAX_INPUT_PER_SAMPLE_KERNEL_NAME: &str = "calculate_max_input_per_sample";
-const BACK_PROPAGATE_KERNEL_NAME: &str = "back_propagate";
+pub(crate) const CALCULATE_EXPONENTIALS_KERNEL_NAME: &str = "calculate_exponentials";
+pub(crate) const SUM_EXPONENTIALS_PER_SAMPLE_KERNEL_NAME: &str = "sum_exponentials_per_sample";
+pub(crate) const FIND_MAX_INPUT_PER_SAMPLE_KERNEL_NAME: &str = "calculate_max_input_per_sample";
+pub(crate) const BACK_PROPAGATE_KERNEL_NAME: &str = "back_propagate";
+
+const PROPAGATE_OVER_CHANNELS_KERNEL_NAME: &str = "propagate_over_channels";
+const BACK_PROPAGATE_OVER_CHANNELS_KERNEL_NAME: &str = "back_propagate_over_channels";
 
 pub(crate) fn compile_softmax(
     opencl_state: &mut OpenCLState,
@@ -38,6 +41,8 @@ pub(crate) fn compile_softmax(
         SUM_EXPONENTIALS_PER_SAMPLE_KERNEL_NAME.to_string(),
         FIND_MAX_INPUT_PER_SAMPLE_KERNEL_NAME.to_string(),
         BACK_PROPAGATE_KERNEL_NAME.to_string(),
+        PROPAGATE_OVER_CHANNELS_KERNEL_NAME.to_string(),
+        BACK_PROPAGATE_OVER_CHANNELS_KERNEL_NAME.to_string(),
     ];
 
     ensure_program(
@@ -55,9 +60,25 @@ pub(crate) fn compile_softmax(
 /// The SoftMax activation function, this function will squash its inputs in such a way that only
 /// the numbers that are very close to the largest number be more "considered" than others.
 /// It is good for classification problems because it is very rigid.
+///
+/// By default all of the inputs of a sample are normalized together, but with `set_channels` the
+/// inputs are normalized over the channels at each position instead, such as at each pixel of the
+/// outputs of a Conv2D, which is what per-pixel classification needs.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::activations::SoftMax;
+///
+/// // this will make a SoftMax for 5 classes at each pixel of a 32x32 image
+/// let my_layer: SoftMax = SoftMax::new_raw(5 * 32 * 32).set_channels(5);
+/// ```
 pub struct SoftMax<'a> {
     /// The amount of inputs this instance of TanH expects.
     pub inputs_amount: usize,
+    /// The amount of channels the inputs are normalized over at each position, or None if all of
+    /// the inputs of a sample are normalized together.
+    pub channels: Option<usize>,
 
     #[savefile_ignore]
     #[savefile_introspect_ignore]
@@ -79,6 +100,7 @@ impl<'a> SoftMax<'a> {
     pub fn new_raw(inputs_amount: usize) -> SoftMax<'a> {
         SoftMax {
             inputs_amount,
+            channels: None,
 
             last_outputs_buffer: None,
             last_inputs_buffer: None,
@@ -92,6 +114,19 @@ impl<'a> SoftMax<'a> {
     pub fn new(inputs_amount: usize) -> crate::types::ModelLayer<'a> {
         Self::new_raw(inputs_amount).into()
     }
+
+    /// Makes the SoftMax normalize its inputs over **channels** at each position instead of all
+    /// of them together, where the inputs of a sample are all of the positions of the first
+    /// channel, then all of the positions of the second channel and so on.
+    pub fn set_channels(mut self, channels: usize) -> Self {
+        assert!(
+            channels > 0 && self.inputs_amount % channels == 0,
+            "the inputs amount of a SoftMax must be divisible by its amount of channels"
+        );
+
+        self.channels = Some(channels);
+        self
+    }
 }
 
 impl<'a> Layer<'a> for SoftMax<'a> {
@@ -172,6 +207,29 @@ impl<'a> Layer<'a> for SoftMax<'a> {
 
         self.last_inputs_buffer = Some(copied_last_inputs_buffer);
 
+        if let Some(channels) = self.channels {
+            let positions_amount = self.inputs_amount / channels;
+
+            let outputs_buffer = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+            let program = state.get_prgm(PROGRAM_NAME)?;
+
+            ExecuteKernel::new(program.get_krnl(PROPAGATE_OVER_CHANNELS_KERNEL_NAME)?)
+                .set_arg(inputs)
+                .set_arg(&outputs_buffer)
+                .set_arg(&(channels as cl_int))
+                .set_arg(&(positions_amount as cl_int))
+                .set_arg(&(samples_amount as cl_int))
+                .set_global_work_sizes(&[samples_amount, positions_amount])
+                .enqueue_nd_range(queue)?;
+
+            queue.finish()?;
+
+            self.last_outputs_buffer = Some(outputs_buffer);
+
+            return Ok(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
         let max_input_per_sample_buffer = empty_buffer(samples_amount, CL_MEM_READ_WRITE, state)?;
 
         let program = state.get_prgm(PROGRAM_NAME)?;
@@ -293,6 +351,23 @@ impl<'a> Layer<'a> for SoftMax<'a> {
         )?;
 
         let program = state.get_prgm(PROGRAM_NAME)?;
+
+        if let Some(channels) = self.channels {
+            ExecuteKernel::new(program.get_krnl(BACK_PROPAGATE_OVER_CHANNELS_KERNEL_NAME)?)
+                .set_arg(layer_output_to_error_derivative)
+                .set_arg(self.last_outputs_buffer.as_ref().unwrap())
+                .set_arg(&loss_to_input_derivatives_buffer)
+                .set_arg(&(channels as cl_int))
+                .set_arg(&((self.inputs_amount / channels) as cl_int))
+                .set_arg(&(samples_amount as cl_int))
+                .set_global_work_sizes(&[samples_amount, self.inputs_amount])
+                .enqueue_nd_range(queue)?;
+
+            queue.finish()?;
+
+            return Ok(loss_to_input_derivatives_buffer);
+        }
+
         let backprop_kernel = program.get_krnl(BACK_PROPAGATE_KERNEL_NAME)?;
 
         opencl3::kernel::ExecuteKernel::new(backprop_kernel)
@@ -324,7 +399,11 @@ mod softmax_tests {
 
     use crate::{
         layers::Layer,
-        utils::{approx_eq::assert_approx_equal_distance, opencl::DeviceType, setup_opencl},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
     };
 
     use super::SoftMax;
@@ -550,4 +629,45 @@ mod softmax_tests {
             0.05,
         );
     }
+
+    #[test]
+    fn should_normalize_over_the_channels_at_each_position() {
+        let opencl_state = setup_opencl(DeviceType::GPU).unwrap();
+
+        // two channels with two positions each
+        let inputs = vec![0.0, 3.0_f32.ln(), 0.0, 0.0];
+        let loss_to_output_derivatives = vec![1.0, 1.0, 0.0, 2.0];
+
+        let expected_outputs = vec![0.5, 0.75, 0.5, 0.25];
+        let expected_loss_to_input_derivatives = vec![0.25, -0.1875, -0.25, 0.1875];
+
+        let mut softmax = SoftMax::new_raw(4).set_channels(2);
+        softmax.init(&opencl_state).unwrap();
+
+        let outputs_buffer = softmax
+            .propagate(&inputs.to_buffer(false, &opencl_state).unwrap())
+            .unwrap();
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state).unwrap();
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_input_derivatives = Vec::<f32>::from_buffer(
+            &softmax
+                .compute_loss_to_input_derivatives(
+                    &loss_to_output_derivatives
+                        .to_buffer(false, &opencl_state)
+                        .unwrap(),
+                )
+                .unwrap(),
+            false,
+            &opencl_state,
+        )
+        .unwrap();
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
 }
\ No newline at end of file
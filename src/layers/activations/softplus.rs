@@ -0,0 +1,113 @@
+//! The module that contains the Softplus activation function.
+
+use opencl3::{device::cl_float, memory::Buffer};
+
+use intricate_macros::ActivationLayer;
+
+use savefile_derive::Savefile;
+
+use crate::utils::OpenCLState;
+
+const PROGRAM_NAME: &str = "SOFTPLUS";
+const PROGRAM_SOURCE: &str = include_str!("kernels/softplus.cl");
+const PROPAGATE_KERNEL_NAME: &str = "propagate";
+const BACK_PROPAGATE_KERNEL_NAME: &str = "back_propagate";
+
+#[derive(Debug, Savefile, ActivationLayer)]
+/// The Softplus activation function, a smooth version of the ReLU defined as `f(x)=ln(1+e^x)`.
+///
+/// # Example
+///
+/// ```rust
+/// use intricate::layers::{
+///     activations::Softplus,
+///     Layer,
+/// };
+///
+/// let my_softplus: Softplus = Softplus::new_raw(10);
+/// ```
+pub struct Softplus<'a> {
+    /// The amount of inputs that this instance of the Softplus function expects.
+    pub inputs_amount: usize,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned last inputs of this instance of Softplus.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The last outputs of this instance of Softplus.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+#[cfg(test)]
+mod softplus_tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::Softplus;
+
+    #[test]
+    fn should_propagate_and_back_propagate_to_correct_values() {
+        let samples_amount = 30;
+        let numbers_amount = 20;
+
+        let mut rng = thread_rng();
+
+        let inputs: Vec<f32> = (0..(samples_amount * numbers_amount))
+            .map(|_| rng.gen_range(-5.0_f32..5.0_f32))
+            .collect();
+        let loss_to_output_derivatives: Vec<f32> = (0..(samples_amount * numbers_amount))
+            .map(|_| rng.gen_range(-1.0_f32..1.0_f32))
+            .collect();
+
+        let expected_outputs: Vec<f32> = inputs.iter().map(|&x| (1.0 + x.exp()).ln()).collect();
+        let expected_loss_to_input_derivatives: Vec<f32> = inputs
+            .iter()
+            .zip(loss_to_output_derivatives.iter())
+            .map(|(&x, derivative)| {
+                let output_to_input_derivative: f32 = 1.0 / (1.0 + (-x).exp());
+                output_to_input_derivative * derivative
+            })
+            .collect();
+
+        let opencl_state = setup_opencl(DeviceType::GPU).unwrap();
+
+        let mut softplus = Softplus::new_raw(numbers_amount);
+        softplus.init(&opencl_state).unwrap();
+
+        let inputs_buffer = inputs.to_buffer(false, &opencl_state).unwrap();
+        let outputs_buffer = softplus.propagate(&inputs_buffer).unwrap();
+        let actual_outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state).unwrap();
+
+        assert_approx_equal_distance(&actual_outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .unwrap();
+        let loss_to_input_derivatives_buffer = softplus
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .unwrap();
+        let actual_loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .unwrap();
+
+        assert_approx_equal_distance(
+            &actual_loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
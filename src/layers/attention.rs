@@ -0,0 +1,1235 @@
+//! The module that contains the MultiHeadAttention layer.
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::{cl_int, ClError},
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use rayon::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{KernelNotFoundError, ModelLayer, ProgramNotFoundError, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    activations::softmax::{
+        BACK_PROPAGATE_KERNEL_NAME as SOFTMAX_BACK_PROPAGATE_KERNEL_NAME,
+        CALCULATE_EXPONENTIALS_KERNEL_NAME, FIND_MAX_INPUT_PER_SAMPLE_KERNEL_NAME,
+        PROGRAM_NAME as SOFTMAX_PROGRAM_NAME, PROPAGATE_KERNEL_NAME as SOFTMAX_PROPAGATE_KERNEL_NAME,
+        SUM_EXPONENTIALS_PER_SAMPLE_KERNEL_NAME,
+    },
+    compute_update_vectors,
+    dense::{
+        BIAS_GRADIENT_COMPUTATION_KERNEL_NAME, DENSE_BACKPROP_PROGRAM_NAME,
+        DENSE_PROP_PROGRAM_NAME, LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME as DENSE_LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME,
+        PROPAGATION_KERNEL_NAME as DENSE_PROPAGATION_KERNEL_NAME,
+        WEIGHTS_GRADIENT_COMPUTATION_KERNEL_NAME,
+    },
+    initializers::{ConstantInitializer, GlorotUniformInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+const PROGRAM_NAME: &str = "ATTENTION";
+const PROGRAM_SOURCE: &str = include_str!("kernels/attention.cl");
+
+const ADD_POSITIONAL_ENCODING_KERNEL_NAME: &str = "add_positional_encoding";
+const COMPUTE_ATTENTION_SCORES_KERNEL_NAME: &str = "compute_attention_scores";
+const COMPUTE_CONTEXT_KERNEL_NAME: &str = "compute_context";
+const COMPUTE_ATTENTION_WEIGHTS_DERIVATIVES_KERNEL_NAME: &str =
+    "compute_attention_weights_derivatives";
+const COMPUTE_VALUES_DERIVATIVES_KERNEL_NAME: &str = "compute_values_derivatives";
+const COMPUTE_QUERIES_OR_KEYS_DERIVATIVES_KERNEL_NAME: &str =
+    "compute_queries_or_keys_derivatives";
+
+pub(crate) fn compile_attention(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        ADD_POSITIONAL_ENCODING_KERNEL_NAME.to_string(),
+        COMPUTE_ATTENTION_SCORES_KERNEL_NAME.to_string(),
+        COMPUTE_CONTEXT_KERNEL_NAME.to_string(),
+        COMPUTE_ATTENTION_WEIGHTS_DERIVATIVES_KERNEL_NAME.to_string(),
+        COMPUTE_VALUES_DERIVATIVES_KERNEL_NAME.to_string(),
+        COMPUTE_QUERIES_OR_KEYS_DERIVATIVES_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+// the name of each projection followed by the ids of its weights and biases,
+// in the order their gradients are returned
+const PROJECTIONS: [(&str, &str, &str); 4] = [
+    ("query", "query_weights", "query_biases"),
+    ("key", "key_weights", "key_biases"),
+    ("value", "value_weights", "value_biases"),
+    ("output", "output_weights", "output_biases"),
+];
+
+#[derive(Debug, Default, Savefile)]
+/// The weights and biases of one of the linear projections of a MultiHeadAttention layer.
+pub struct AttentionProjection {
+    /// The weights of the projection with the shape (embedding_size, embedding_size).
+    pub weights: Vec<Vec<f32>>,
+    /// The biases of the projection.
+    pub biases: Vec<f32>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened weights.
+    pub weights_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the biases.
+    pub biases_buffer: Option<Buffer<cl_float>>,
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that does multi-head scaled dot-product self attention over sequences, with learnable
+/// projections for the queries, keys, values and outputs.
+///
+/// Expects the inputs of each sample to be a flattened sequence of `timesteps` embeddings of
+/// size `embedding_size`, which is split evenly between the heads, and outputs a sequence with
+/// the same shape.
+///
+/// # Examples
+///
+/// ```
+/// use intricate::layers::MultiHeadAttention;
+///
+/// let attention = MultiHeadAttention::new_raw(16, 64, 8)
+///     .set_causal(true)
+///     .set_positional_encoding(true);
+/// ```
+pub struct MultiHeadAttention<'a> {
+    /// The amount of timesteps in each input sequence.
+    pub timesteps: usize,
+    /// The size of the embedding of each timestep.
+    pub embedding_size: usize,
+    /// The amount of heads the attention is split into.
+    pub heads_amount: usize,
+
+    /// Weather or not each timestep can only attend to itself and to the timesteps before it.
+    pub causal: bool,
+    /// Weather or not sinusoidal positional encodings are added to the inputs before they are
+    /// projected.
+    pub positional_encoding: bool,
+
+    /// The projection of the inputs into the queries.
+    pub query_projection: AttentionProjection,
+    /// The projection of the inputs into the keys.
+    pub key_projection: AttentionProjection,
+    /// The projection of the inputs into the values.
+    pub value_projection: AttentionProjection,
+    /// The projection of the attended values into the outputs.
+    pub output_projection: AttentionProjection,
+
+    /// The initializers of each parameter, which are named after the projection and then
+    /// either weights or biases, such as `query_weights` or `output_biases`.
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this layer.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this layer.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The last inputs with the positional encodings added, only used if the positional encoding
+    /// is enabled.
+    pub last_encoded_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The queries of the last forward pass.
+    pub last_queries_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The keys of the last forward pass.
+    pub last_keys_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The values of the last forward pass.
+    pub last_values_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The softmaxed attention scores of the last forward pass, with the shape
+    /// (samples, heads, timesteps, timesteps).
+    pub last_attention_weights_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The values weighted by the attention of the last forward pass, before the output
+    /// projection.
+    pub last_context_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> MultiHeadAttention<'a> {
+    /// Creates a new MultiHeadAttention raw, meaning that it is not inside of a ModelLayer enum.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the `embedding_size` is not divisible by the `heads_amount`.
+    pub fn new_raw(
+        timesteps: usize,
+        embedding_size: usize,
+        heads_amount: usize,
+    ) -> MultiHeadAttention<'a> {
+        assert!(
+            heads_amount > 0 && embedding_size % heads_amount == 0,
+            "the embedding size of a MultiHeadAttention must be divisible by its amount of heads"
+        );
+
+        let mut initializers = HashMap::with_capacity(PROJECTIONS.len() * 2);
+        for (_, weights_id, biases_id) in PROJECTIONS {
+            initializers.insert(
+                weights_id.to_string(),
+                GlorotUniformInitializer::new().into(),
+            );
+            initializers.insert(biases_id.to_string(), ConstantInitializer::new(0.0).into());
+        }
+
+        MultiHeadAttention {
+            timesteps,
+            embedding_size,
+            heads_amount,
+
+            causal: false,
+            positional_encoding: false,
+
+            query_projection: AttentionProjection::default(),
+            key_projection: AttentionProjection::default(),
+            value_projection: AttentionProjection::default(),
+            output_projection: AttentionProjection::default(),
+
+            initializers,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+
+            last_encoded_inputs_buffer: None,
+            last_queries_buffer: None,
+            last_keys_buffer: None,
+            last_values_buffer: None,
+            last_attention_weights_buffer: None,
+            last_context_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a new MultiHeadAttention inside of a ModelLayer enum.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the `embedding_size` is not divisible by the `heads_amount`.
+    pub fn new(timesteps: usize, embedding_size: usize, heads_amount: usize) -> ModelLayer<'a> {
+        Self::new_raw(timesteps, embedding_size, heads_amount).into()
+    }
+
+    /// Sets weather or not the attention is masked so that each timestep can only attend to
+    /// itself and the timesteps before it, which is **false** by default.
+    pub fn set_causal(mut self, causal: bool) -> Self {
+        self.causal = causal;
+
+        self
+    }
+
+    /// Sets weather or not sinusoidal positional encodings are added to the inputs, which is
+    /// **false** by default.
+    pub fn set_positional_encoding(mut self, positional_encoding: bool) -> Self {
+        self.positional_encoding = positional_encoding;
+
+        self
+    }
+
+    fn get_projection(&self, projection_name: &str) -> Option<&AttentionProjection> {
+        match projection_name {
+            "query" => Some(&self.query_projection),
+            "key" => Some(&self.key_projection),
+            "value" => Some(&self.value_projection),
+            "output" => Some(&self.output_projection),
+            _ => None,
+        }
+    }
+
+    fn get_projection_mut(&mut self, projection_name: &str) -> Option<&mut AttentionProjection> {
+        match projection_name {
+            "query" => Some(&mut self.query_projection),
+            "key" => Some(&mut self.key_projection),
+            "value" => Some(&mut self.value_projection),
+            "output" => Some(&mut self.output_projection),
+            _ => None,
+        }
+    }
+
+    // the inputs that were projected into the queries, keys and values on the last forward pass
+    fn get_last_projected_inputs(&self) -> Option<&Buffer<cl_float>> {
+        if self.positional_encoding {
+            self.last_encoded_inputs_buffer.as_ref()
+        } else {
+            self.last_inputs_buffer.as_ref()
+        }
+    }
+
+    // goes back through the output projection and the attention itself computing the derivatives
+    // of the loss with respect to the queries, the keys and the values respectively
+    fn back_propagate_attention<E>(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+        samples_amount: usize,
+        state: &OpenCLState,
+    ) -> Result<(Buffer<cl_float>, Buffer<cl_float>, Buffer<cl_float>), E>
+    where
+        E: From<ClError> + From<ProgramNotFoundError> + From<KernelNotFoundError>,
+    {
+        let queue = state.queues.first().unwrap();
+
+        let rows_amount = samples_amount * self.timesteps;
+        let sequence_size = self.get_inputs_amount();
+        let scores_count = samples_amount * self.heads_amount * self.timesteps * self.timesteps;
+
+        let loss_to_context_derivatives = project_back::<E>(
+            self.output_projection.weights_buffer.as_ref().unwrap(),
+            layer_output_to_error_derivative,
+            rows_amount,
+            self.embedding_size,
+            state,
+        )?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+
+        let loss_to_attention_weights_derivatives =
+            empty_buffer(scores_count, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_ATTENTION_WEIGHTS_DERIVATIVES_KERNEL_NAME)?)
+            .set_arg(&loss_to_context_derivatives)
+            .set_arg(self.last_values_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_attention_weights_derivatives)
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(self.heads_amount as cl_int))
+            .set_global_work_sizes(&[
+                samples_amount * self.heads_amount,
+                self.timesteps,
+                self.timesteps,
+            ])
+            .enqueue_nd_range(queue)?;
+
+        let loss_to_values_derivatives =
+            empty_buffer(samples_amount * sequence_size, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_VALUES_DERIVATIVES_KERNEL_NAME)?)
+            .set_arg(self.last_attention_weights_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_context_derivatives)
+            .set_arg(&loss_to_values_derivatives)
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(self.heads_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.timesteps, self.embedding_size])
+            .enqueue_nd_range(queue)?;
+
+        // each row of the attention weights is a softmax of its own
+        let loss_to_scores_derivatives = empty_buffer(scores_count, CL_MEM_READ_WRITE, state)?;
+
+        let softmax_program = state.get_prgm(SOFTMAX_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(softmax_program.get_krnl(SOFTMAX_BACK_PROPAGATE_KERNEL_NAME)?)
+            .set_arg(&loss_to_attention_weights_derivatives)
+            .set_arg(self.last_attention_weights_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_scores_derivatives)
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&((scores_count / self.timesteps) as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_global_work_sizes(&[scores_count / self.timesteps, self.timesteps])
+            .enqueue_nd_range(queue)?;
+
+        let queries_or_keys_kernel = program.get_krnl(COMPUTE_QUERIES_OR_KEYS_DERIVATIVES_KERNEL_NAME)?;
+
+        let loss_to_queries_derivatives =
+            empty_buffer(samples_amount * sequence_size, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(queries_or_keys_kernel)
+            .set_arg(&loss_to_scores_derivatives)
+            .set_arg(self.last_keys_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_queries_derivatives)
+            .set_arg(&(0 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(self.heads_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.timesteps, self.embedding_size])
+            .enqueue_nd_range(queue)?;
+
+        let loss_to_keys_derivatives =
+            empty_buffer(samples_amount * sequence_size, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(queries_or_keys_kernel)
+            .set_arg(&loss_to_scores_derivatives)
+            .set_arg(self.last_queries_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_keys_derivatives)
+            .set_arg(&(1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(self.heads_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.timesteps, self.embedding_size])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok((
+            loss_to_queries_derivatives,
+            loss_to_keys_derivatives,
+            loss_to_values_derivatives,
+        ))
+    }
+}
+
+// applies a projection to each of the `rows_amount` embeddings in the inputs using the
+// Dense layer's propagation
+fn project<E>(
+    inputs: &Buffer<cl_float>,
+    projection: &AttentionProjection,
+    rows_amount: usize,
+    embedding_size: usize,
+    state: &OpenCLState,
+) -> Result<Buffer<cl_float>, E>
+where
+    E: From<ClError> + From<ProgramNotFoundError> + From<KernelNotFoundError>,
+{
+    let queue = state.queues.first().unwrap();
+
+    let outputs = empty_buffer(rows_amount * embedding_size, CL_MEM_READ_WRITE, state)?;
+
+    let program = state.get_prgm(DENSE_PROP_PROGRAM_NAME)?;
+
+    ExecuteKernel::new(program.get_krnl(DENSE_PROPAGATION_KERNEL_NAME)?)
+        .set_arg(inputs)
+        .set_arg(projection.biases_buffer.as_ref().unwrap())
+        .set_arg(projection.weights_buffer.as_ref().unwrap())
+        .set_arg(&outputs)
+        .set_arg(&(embedding_size as cl_int))
+        .set_arg(&(rows_amount as cl_int))
+        .set_arg(&(embedding_size as cl_int))
+        .set_global_work_sizes(&[rows_amount, embedding_size])
+        .enqueue_nd_range(queue)?;
+
+    Ok(outputs)
+}
+
+// the derivatives of the loss with respect to the inputs of a projection given the derivatives
+// with respect to its outputs, using the Dense layer's back propagation
+fn project_back<E>(
+    weights: &Buffer<cl_float>,
+    loss_to_output_derivatives: &Buffer<cl_float>,
+    rows_amount: usize,
+    embedding_size: usize,
+    state: &OpenCLState,
+) -> Result<Buffer<cl_float>, E>
+where
+    E: From<ClError> + From<ProgramNotFoundError> + From<KernelNotFoundError>,
+{
+    let queue = state.queues.first().unwrap();
+
+    let loss_to_input_derivatives =
+        empty_buffer(rows_amount * embedding_size, CL_MEM_READ_WRITE, state)?;
+
+    let program = state.get_prgm(DENSE_BACKPROP_PROGRAM_NAME)?;
+
+    ExecuteKernel::new(program.get_krnl(DENSE_LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME)?)
+        .set_arg(weights)
+        .set_arg(loss_to_output_derivatives)
+        .set_arg(&loss_to_input_derivatives)
+        .set_arg(&(rows_amount as cl_int))
+        .set_arg(&(embedding_size as cl_int))
+        .set_arg(&(embedding_size as cl_int))
+        .set_global_work_sizes(&[rows_amount, embedding_size])
+        .enqueue_nd_range(queue)?;
+
+    queue.finish()?;
+
+    Ok(loss_to_input_derivatives)
+}
+
+impl<'a> Layer<'a> for MultiHeadAttention<'a> {
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.timesteps * self.embedding_size
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.timesteps * self.embedding_size
+    }
+
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        let (projection_name, parameter_type) = parameter.split_once('_')?;
+        let projection = self.get_projection(projection_name)?;
+
+        match parameter_type {
+            "weights" => Some(projection.weights.par_iter().flatten().map(|x| *x).collect()),
+            "biases" => Some(projection.biases.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        let embedding_size = self.embedding_size;
+
+        for (projection_name, weights_id, biases_id) in PROJECTIONS {
+            if self.get_projection(projection_name).unwrap().weights.is_empty() {
+                let weights = if let Some(initializer) = self.initializers.get(weights_id) {
+                    initializer.initialize_2d((embedding_size, embedding_size), self)
+                } else {
+                    return Err(LayerInitializationError::MissingParameterInitializer(
+                        weights_id,
+                    ));
+                };
+
+                self.get_projection_mut(projection_name).unwrap().weights = weights;
+            }
+
+            if self.get_projection(projection_name).unwrap().biases.is_empty() {
+                let biases = if let Some(initializer) = self.initializers.get(biases_id) {
+                    initializer.initialize_1d(embedding_size, self)
+                } else {
+                    return Err(LayerInitializationError::MissingParameterInitializer(
+                        biases_id,
+                    ));
+                };
+
+                self.get_projection_mut(projection_name).unwrap().biases = biases;
+            }
+
+            let projection = self.get_projection_mut(projection_name).unwrap();
+
+            let flattened_weights: Vec<f32> =
+                projection.weights.par_iter().flatten().map(|x| *x).collect();
+
+            projection.weights_buffer = Some(flattened_weights.to_buffer(false, opencl_state)?);
+            projection.biases_buffer = Some(projection.biases.to_buffer(false, opencl_state)?);
+        }
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        for (projection_name, _, _) in PROJECTIONS {
+            let projection = self.get_projection(projection_name).unwrap();
+
+            if projection.weights_buffer.is_some() {
+                drop(projection.weights_buffer.as_ref().unwrap());
+            }
+
+            if projection.biases_buffer.is_some() {
+                drop(projection.biases_buffer.as_ref().unwrap());
+            }
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_encoded_inputs_buffer.is_some() {
+            drop(self.last_encoded_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_queries_buffer.is_some() {
+            drop(self.last_queries_buffer.as_ref().unwrap());
+        }
+
+        if self.last_keys_buffer.is_some() {
+            drop(self.last_keys_buffer.as_ref().unwrap());
+        }
+
+        if self.last_values_buffer.is_some() {
+            drop(self.last_values_buffer.as_ref().unwrap());
+        }
+
+        if self.last_attention_weights_buffer.is_some() {
+            drop(self.last_attention_weights_buffer.as_ref().unwrap());
+        }
+
+        if self.last_context_buffer.is_some() {
+            drop(self.last_context_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        let embedding_size = self.embedding_size;
+
+        for (projection_name, weights_id, biases_id) in PROJECTIONS {
+            let projection = self.get_projection_mut(projection_name).unwrap();
+
+            if projection.weights_buffer.is_none() {
+                return Err(SyncDataError::NotAllocatedInDevice {
+                    field_name: format!("{}_buffer", weights_id),
+                });
+            }
+
+            if projection.biases_buffer.is_none() {
+                return Err(SyncDataError::NotAllocatedInDevice {
+                    field_name: format!("{}_buffer", biases_id),
+                });
+            }
+
+            let flattened_weights = Vec::<f32>::from_buffer(
+                projection.weights_buffer.as_ref().unwrap(),
+                false,
+                state,
+            )?;
+
+            projection.weights = flattened_weights
+                .par_chunks(embedding_size)
+                .map(|row| row.to_vec())
+                .collect();
+            projection.biases =
+                Vec::<f32>::from_buffer(projection.biases_buffer.as_ref().unwrap(), false, state)?;
+        }
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_total_count = inputs.size()? / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+        let rows_amount = samples_amount * self.timesteps;
+        let scores_rows_amount = samples_amount * self.heads_amount * self.timesteps;
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+
+        if self.positional_encoding {
+            let encoded_inputs = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+            ExecuteKernel::new(program.get_krnl(ADD_POSITIONAL_ENCODING_KERNEL_NAME)?)
+                .set_arg(inputs)
+                .set_arg(&encoded_inputs)
+                .set_arg(&(samples_amount as cl_int))
+                .set_arg(&(self.timesteps as cl_int))
+                .set_arg(&(self.embedding_size as cl_int))
+                .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+                .enqueue_nd_range(queue)?;
+
+            self.last_encoded_inputs_buffer = Some(encoded_inputs);
+        } else {
+            self.last_encoded_inputs_buffer = None;
+        }
+
+        let projected_inputs = self.get_last_projected_inputs().unwrap();
+
+        let queries: Buffer<cl_float> = project::<LayerPropagationError>(
+            projected_inputs,
+            &self.query_projection,
+            rows_amount,
+            self.embedding_size,
+            state,
+        )?;
+        let keys: Buffer<cl_float> = project::<LayerPropagationError>(
+            projected_inputs,
+            &self.key_projection,
+            rows_amount,
+            self.embedding_size,
+            state,
+        )?;
+        let values: Buffer<cl_float> = project::<LayerPropagationError>(
+            projected_inputs,
+            &self.value_projection,
+            rows_amount,
+            self.embedding_size,
+            state,
+        )?;
+
+        let scores = empty_buffer(scores_rows_amount * self.timesteps, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_ATTENTION_SCORES_KERNEL_NAME)?)
+            .set_arg(&queries)
+            .set_arg(&keys)
+            .set_arg(&scores)
+            .set_arg(&(self.causal as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(self.heads_amount as cl_int))
+            .set_global_work_sizes(&[
+                samples_amount * self.heads_amount,
+                self.timesteps,
+                self.timesteps,
+            ])
+            .enqueue_nd_range(queue)?;
+
+        // the softmax of each row of scores, in the same way the SoftMax layer does it
+        let softmax_program = state.get_prgm(SOFTMAX_PROGRAM_NAME)?;
+
+        let max_score_per_row = empty_buffer(scores_rows_amount, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(softmax_program.get_krnl(FIND_MAX_INPUT_PER_SAMPLE_KERNEL_NAME)?)
+            .set_arg(&scores)
+            .set_arg(&max_score_per_row)
+            .set_arg(&(scores_rows_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_global_work_size(scores_rows_amount)
+            .enqueue_nd_range(queue)?;
+
+        let exponentials = empty_buffer(
+            scores_rows_amount * self.timesteps,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        ExecuteKernel::new(softmax_program.get_krnl(CALCULATE_EXPONENTIALS_KERNEL_NAME)?)
+            .set_arg(&scores)
+            .set_arg(&exponentials)
+            .set_arg(&max_score_per_row)
+            .set_arg(&(scores_rows_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_global_work_sizes(&[scores_rows_amount, self.timesteps])
+            .enqueue_nd_range(queue)?;
+
+        let exponentials_sum_per_row = empty_buffer(scores_rows_amount, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(softmax_program.get_krnl(SUM_EXPONENTIALS_PER_SAMPLE_KERNEL_NAME)?)
+            .set_arg(&exponentials)
+            .set_arg(&exponentials_sum_per_row)
+            .set_arg(&(scores_rows_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_global_work_size(scores_rows_amount)
+            .enqueue_nd_range(queue)?;
+
+        let attention_weights = empty_buffer(
+            scores_rows_amount * self.timesteps,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        ExecuteKernel::new(softmax_program.get_krnl(SOFTMAX_PROPAGATE_KERNEL_NAME)?)
+            .set_arg(&exponentials)
+            .set_arg(&attention_weights)
+            .set_arg(&exponentials_sum_per_row)
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&(scores_rows_amount as cl_int))
+            .set_global_work_sizes(&[scores_rows_amount, self.timesteps])
+            .enqueue_nd_range(queue)?;
+
+        let context = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_CONTEXT_KERNEL_NAME)?)
+            .set_arg(&attention_weights)
+            .set_arg(&values)
+            .set_arg(&context)
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.timesteps as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(self.heads_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.timesteps, self.embedding_size])
+            .enqueue_nd_range(queue)?;
+
+        let outputs: Buffer<cl_float> = project::<LayerPropagationError>(
+            &context,
+            &self.output_projection,
+            rows_amount,
+            self.embedding_size,
+            state,
+        )?;
+
+        queue.finish()?;
+
+        self.last_queries_buffer = Some(queries);
+        self.last_keys_buffer = Some(keys);
+        self.last_values_buffer = Some(values);
+        self.last_attention_weights_buffer = Some(attention_weights);
+        self.last_context_buffer = Some(context);
+        self.last_outputs_buffer = Some(outputs);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_total_count =
+            layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        if self.last_context_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+        let rows_amount = samples_amount * self.timesteps;
+
+        let (loss_to_queries_derivatives, loss_to_keys_derivatives, loss_to_values_derivatives) =
+            self.back_propagate_attention::<LayerGradientComputationError>(
+                layer_output_to_error_derivative,
+                samples_amount,
+                state,
+            )?;
+
+        let projected_inputs = self.get_last_projected_inputs().unwrap();
+
+        let projections_inputs_and_derivatives = [
+            (projected_inputs, &loss_to_queries_derivatives),
+            (projected_inputs, &loss_to_keys_derivatives),
+            (projected_inputs, &loss_to_values_derivatives),
+            (
+                self.last_context_buffer.as_ref().unwrap(),
+                layer_output_to_error_derivative,
+            ),
+        ];
+
+        let program = state.get_prgm(DENSE_BACKPROP_PROGRAM_NAME)?;
+        let weights_gradients_kernel = program.get_krnl(WEIGHTS_GRADIENT_COMPUTATION_KERNEL_NAME)?;
+        let biases_gradients_kernel = program.get_krnl(BIAS_GRADIENT_COMPUTATION_KERNEL_NAME)?;
+
+        let mut gradients = Vec::with_capacity(PROJECTIONS.len() * 2);
+
+        for ((_, weights_id, biases_id), (inputs, loss_to_output_derivatives)) in
+            PROJECTIONS.iter().zip(projections_inputs_and_derivatives)
+        {
+            let mut weights_gradients = empty_buffer(
+                self.embedding_size * self.embedding_size,
+                CL_MEM_READ_WRITE,
+                state,
+            )?;
+            let mut biases_gradients =
+                empty_buffer(self.embedding_size, CL_MEM_READ_WRITE, state)?;
+
+            ExecuteKernel::new(weights_gradients_kernel)
+                .set_arg(loss_to_output_derivatives)
+                .set_arg(inputs)
+                .set_arg(&weights_gradients)
+                .set_arg(&(rows_amount as cl_int))
+                .set_arg(&(self.embedding_size as cl_int))
+                .set_arg(&(self.embedding_size as cl_int))
+                .set_global_work_sizes(&[self.embedding_size, self.embedding_size])
+                .enqueue_nd_range(queue)?;
+
+            ExecuteKernel::new(biases_gradients_kernel)
+                .set_arg(loss_to_output_derivatives)
+                .set_arg(&biases_gradients)
+                .set_arg(&(rows_amount as cl_int))
+                .set_arg(&(self.embedding_size as cl_int))
+                .set_global_work_size(self.embedding_size)
+                .enqueue_nd_range(queue)?;
+
+            queue.finish()?;
+
+            // the Dense kernels average over all of the timesteps of all samples,
+            // but the gradients should be summed through the timesteps of each sample
+            weights_gradients.scale_inplc(self.timesteps as f32, state)?;
+            biases_gradients.scale_inplc(self.timesteps as f32, state)?;
+
+            gradients.push(Gradient {
+                parameter_id: weights_id.to_string(),
+                value: weights_gradients,
+                optimizable: true,
+            });
+            gradients.push(Gradient {
+                parameter_id: biases_id.to_string(),
+                value: biases_gradients,
+                optimizable: true,
+            });
+        }
+
+        Ok(gradients)
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        for (projection_name, weights_id, biases_id) in PROJECTIONS {
+            let projection = self.get_projection_mut(projection_name).unwrap();
+
+            if projection.weights_buffer.is_none() {
+                return Err(ParametersOptimizationError::EmptyParameter(
+                    weights_id.to_string(),
+                ));
+            }
+
+            if projection.biases_buffer.is_none() {
+                return Err(ParametersOptimizationError::EmptyParameter(
+                    biases_id.to_string(),
+                ));
+            }
+
+            optimizer.optimize_parameters(
+                projection.weights_buffer.as_mut().unwrap(),
+                weights_id.to_string(),
+                timestep,
+                layer_index,
+            )?;
+            optimizer.optimize_parameters(
+                projection.biases_buffer.as_mut().unwrap(),
+                biases_id.to_string(),
+                timestep,
+                layer_index,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != PROJECTIONS.len() * 2 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_index,
+            timestep,
+            state,
+        )?;
+
+        for ((projection_name, _, _), projection_update_vectors) in
+            PROJECTIONS.iter().zip(update_vectors.chunks(2))
+        {
+            let projection = self.get_projection_mut(projection_name).unwrap();
+
+            projection
+                .weights_buffer
+                .as_mut()
+                .unwrap()
+                .subtract_inplc(&projection_update_vectors[0], state)?;
+            projection
+                .biases_buffer
+                .as_mut()
+                .unwrap()
+                .subtract_inplc(&projection_update_vectors[1], state)?;
+        }
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        let derivatives_total_count =
+            layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        if self.last_context_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+        let rows_amount = samples_amount * self.timesteps;
+
+        let (loss_to_queries_derivatives, loss_to_keys_derivatives, loss_to_values_derivatives) =
+            self.back_propagate_attention::<LayerLossToInputDifferentiationError>(
+                layer_output_to_error_derivative,
+                samples_amount,
+                state,
+            )?;
+
+        // the positional encodings are constant so they don't change the derivatives
+        let mut loss_to_input_derivatives: Buffer<cl_float> = project_back::<
+            LayerLossToInputDifferentiationError,
+        >(
+            self.query_projection.weights_buffer.as_ref().unwrap(),
+            &loss_to_queries_derivatives,
+            rows_amount,
+            self.embedding_size,
+            state,
+        )?;
+
+        loss_to_input_derivatives.add_inplc(
+            &project_back::<LayerLossToInputDifferentiationError>(
+                self.key_projection.weights_buffer.as_ref().unwrap(),
+                &loss_to_keys_derivatives,
+                rows_amount,
+                self.embedding_size,
+                state,
+            )?,
+            state,
+        )?;
+        loss_to_input_derivatives.add_inplc(
+            &project_back::<LayerLossToInputDifferentiationError>(
+                self.value_projection.weights_buffer.as_ref().unwrap(),
+                &loss_to_values_derivatives,
+                rows_amount,
+                self.embedding_size,
+                state,
+            )?,
+            state,
+        )?;
+
+        Ok(loss_to_input_derivatives)
+    }
+}
+
+#[cfg(test)]
+mod attention_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::MultiHeadAttention;
+
+    fn to_matrix(flat: Vec<f32>, columns: usize) -> Vec<Vec<f32>> {
+        flat.chunks(columns).map(|row| row.to_vec()).collect()
+    }
+
+    #[test]
+    fn should_attend_causally_and_compute_derivatives_correctly() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            -0.2, -0.3, 0.2, -0.4, 0.0, -0.1, -0.4, 0.0, -0.5, -0.1, -0.4, -0.4,
+            -0.1, 0.3, -0.4, -0.3, 0.1, 0.4, 0.1, -0.1, 0.5, -0.5, 0.4, -0.2,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.1, -0.2, -0.5, -0.1, -0.1, 0.1, 0.5, 0.2, 0.0, 0.1, 0.2, -0.4,
+            0.4, 0.3, 0.4, 0.3, -0.1, -0.1, -0.4, 0.1, -0.4, -0.4, -0.3, -0.3,
+        ];
+
+        let expected_outputs = vec![
+            -0.466, -0.452, 0.092, -0.158, -0.4322, -0.3209, 0.2237, -0.2965, -0.3271, -0.2644,
+            0.1616, -0.3452, -0.538, -0.482, 0.326, -0.286, -0.5401, -0.3494, 0.3298, -0.2812,
+            -0.3186, -0.1023, 0.1514, -0.3345,
+        ];
+        let expected_gradients = vec![
+            vec![
+                -0.0053, 0.0043, 0.0357, -0.0314, 0.0022, -0.0011, -0.0198, 0.0199,
+                -0.0011, 0.0008, 0.0083, -0.0139, -0.0037, 0.0031, 0.0224, -0.0149,
+            ],
+            vec![-0.0053, 0.0039, 0.0279, -0.0188],
+            vec![
+                -0.0013, -0.0071, 0.0117, -0.0069, 0.0002, 0.007, -0.0159, 0.0251,
+                -0.0019, -0.0033, 0.0035, -0.0151, -0.0001, -0.0007, 0.0015, 0.0032,
+            ],
+            vec![0.0, 0.0, 0.0, 0.0],
+            vec![
+                0.0665, 0.0429, -0.1215, -0.0323, 0.034, 0.0224, 0.0799, -0.0381,
+                0.0402, 0.0099, -0.0347, -0.1144, 0.0241, 0.0075, -0.0438, -0.0085,
+            ],
+            vec![0.015, -0.01, -0.035, -0.03],
+            vec![
+                -0.0023, 0.0191, -0.0005, 0.0214, -0.003, -0.02, -0.0681, 0.0293,
+                -0.1398, -0.0571, -0.063, -0.0927, -0.0627, -0.0546, -0.004, -0.0365,
+            ],
+            vec![-0.05, -0.1, -0.05, -0.1],
+        ];
+        let expected_loss_to_input_derivatives = vec![
+            -0.0548, -0.0446, 0.0003, -0.0245, 0.0826, 0.0581, -0.07, 0.0335, 0.035, 0.0041,
+            -0.0539, -0.0141, 0.0512, -0.0443, 0.03, -0.0448, -0.0779, -0.0029, 0.0714, 0.0225,
+            -0.0996, 0.0549, -0.008, 0.0376,
+        ];
+
+        let mut layer = MultiHeadAttention::new_raw(3, 4, 2)
+            .set_causal(true)
+            .set_positional_encoding(true);
+        layer.query_projection.weights = to_matrix(
+            vec![
+                -0.4, -0.4, -0.2, 0.3, -0.3, 0.1, 0.1, -0.1,
+                0.0, -0.4, -0.4, -0.3, 0.2, -0.1, -0.2, 0.1,
+            ],
+            4,
+        );
+        layer.query_projection.biases = vec![0.0, -0.2, 0.3, 0.2];
+        layer.key_projection.weights = to_matrix(
+            vec![
+                -0.3, 0.1, 0.0, 0.4, 0.2, -0.2, 0.5, -0.4,
+                -0.1, 0.3, -0.3, 0.0, -0.5, 0.2, 0.3, 0.1,
+            ],
+            4,
+        );
+        layer.key_projection.biases = vec![0.4, -0.2, 0.2, 0.1];
+        layer.value_projection.weights = to_matrix(
+            vec![
+                0.1, 0.0, 0.3, 0.4, 0.0, 0.2, -0.4, 0.2,
+                0.1, 0.5, 0.3, -0.2, -0.1, 0.2, -0.5, 0.0,
+            ],
+            4,
+        );
+        layer.value_projection.biases = vec![-0.3, -0.4, -0.4, 0.3];
+        layer.output_projection.weights = to_matrix(
+            vec![
+                -0.4, -0.3, -0.1, 0.4, -0.4, -0.1, 0.0, 0.4,
+                0.3, 0.4, -0.2, -0.1, -0.1, 0.4, 0.5, -0.3,
+            ],
+            4,
+        );
+        layer.output_projection.biases = vec![-0.3, -0.3, -0.3, 0.0];
+        layer
+            .init(&opencl_state)
+            .expect("unable to init MultiHeadAttention");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the MultiHeadAttention");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the MultiHeadAttention");
+
+        for (gradient, expected_gradient) in gradients.iter().zip(expected_gradients.iter()) {
+            let gradient = Vec::<f32>::from_buffer(&gradient.value, false, &opencl_state)
+                .expect("unable to read a gradients buffer");
+
+            assert_approx_equal_distance(&gradient, expected_gradient, 0.01);
+        }
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the MultiHeadAttention");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
@@ -0,0 +1,855 @@
+//! The module that defines the BatchNorm layer.
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    initializers::{ConstantInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+const BATCH_NORM_PROGRAM_NAME: &str = "BATCH_NORM";
+const PROGRAM_SOURCE: &str = include_str!("kernels/batch_norm.cl");
+
+const COMPUTE_BATCH_STATISTICS_KERNEL_NAME: &str = "compute_batch_statistics";
+const NORMALIZE_KERNEL_NAME: &str = "normalize";
+const COMPUTE_GRADIENTS_KERNEL_NAME: &str = "compute_gradients";
+const LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_batch_norm(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        COMPUTE_BATCH_STATISTICS_KERNEL_NAME.to_string(),
+        NORMALIZE_KERNEL_NAME.to_string(),
+        COMPUTE_GRADIENTS_KERNEL_NAME.to_string(),
+        LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        BATCH_NORM_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that normalizes each one of its inputs using the mean and the variance of that input
+/// in the current batch, and then scales and shifts them with the trainable **gamma** and
+/// **beta** parameters.
+///
+/// While the Model is being trained this layer keeps a running mean and variance of the inputs
+/// that are then used instead of the batch statistics when predicting, these running statistics
+/// are also saved together with the parameters.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::BatchNorm;
+///
+/// let my_layer: BatchNorm = BatchNorm::new_raw(100).set_momentum(0.9);
+/// ```
+pub struct BatchNorm<'a> {
+    /// The amount of inputs (and outputs) this BatchNorm has.
+    pub inputs_amount: usize,
+
+    /// How much of the last running statistics is kept every time they are updated with the
+    /// statistics of a new batch.
+    pub momentum: f32,
+    /// A small number added to the variance to avoid dividing by zero.
+    pub epsilon: f32,
+
+    /// The factors by which the normalized inputs are multiplied, stored in the CPU.
+    pub gamma: Vec<f32>,
+    /// The numbers added to the normalized inputs after scaling them, stored in the CPU.
+    pub beta: Vec<f32>,
+
+    /// The running mean of each input, used when predicting.
+    pub running_mean: Vec<f32>,
+    /// The running variance of each input, used when predicting.
+    pub running_variance: Vec<f32>,
+
+    /// The initializers that will generate the initial parameters for the BatchNorm (gamma and
+    /// beta).
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// Weather or not the Model this layer is in is currently being trained.
+    pub is_training: bool,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    #[savefile_default_val = "true"]
+    /// Weather or not the forward passes made while training update the running statistics.
+    pub is_updating_running_statistics: bool,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the gamma of this BatchNorm.
+    pub gamma_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the beta of this BatchNorm.
+    pub beta_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the running mean of this BatchNorm.
+    pub running_mean_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the running variance of this BatchNorm.
+    pub running_variance_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this BatchNorm.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The inputs after being normalized in the last forward pass, before gamma and beta.
+    pub last_normalized_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The variance of each input in the last batch, only present if the last forward pass
+    /// happened while training.
+    pub last_batch_variance_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this BatchNorm.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> BatchNorm<'a> {
+    /// Creates a raw version of the BatchNorm layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(inputs_amount: usize) -> BatchNorm<'a> {
+        let mut initializers = HashMap::with_capacity(2);
+        initializers.insert("gamma".to_string(), ConstantInitializer::new(1.0).into());
+        initializers.insert("beta".to_string(), ConstantInitializer::new(0.0).into());
+
+        BatchNorm {
+            inputs_amount,
+
+            momentum: 0.99,
+            epsilon: 0.001,
+
+            gamma: Vec::default(),
+            beta: Vec::default(),
+
+            running_mean: Vec::default(),
+            running_variance: Vec::default(),
+
+            initializers,
+
+            is_training: false,
+            is_updating_running_statistics: true,
+
+            gamma_buffer: None,
+            beta_buffer: None,
+            running_mean_buffer: None,
+            running_variance_buffer: None,
+
+            last_inputs_buffer: None,
+            last_normalized_inputs_buffer: None,
+            last_batch_variance_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the BatchNorm layer, to be used with a Model.
+    pub fn new(inputs_amount: usize) -> ModelLayer<'a> {
+        Self::new_raw(inputs_amount).into()
+    }
+
+    /// Sets how much of the running statistics is kept when updating them with a new batch,
+    /// which is **0.99** by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the momentum is not in the range [0, 1].
+    pub fn set_momentum(mut self, momentum: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&momentum),
+            "the momentum of a BatchNorm must be in the range [0, 1]"
+        );
+
+        self.momentum = momentum;
+
+        self
+    }
+
+    /// Sets the small number that is added to the variance to avoid divisions by zero, which
+    /// is **0.001** by default.
+    pub fn set_epsilon(mut self, epsilon: f32) -> Self {
+        self.epsilon = epsilon;
+
+        self
+    }
+}
+
+impl<'a> Layer<'a> for BatchNorm<'a> {
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "gamma" => Some(self.gamma.to_vec()),
+            "beta" => Some(self.beta.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.gamma_buffer.is_some() {
+            drop(self.gamma_buffer.as_ref().unwrap());
+        }
+
+        if self.beta_buffer.is_some() {
+            drop(self.beta_buffer.as_ref().unwrap());
+        }
+
+        if self.running_mean_buffer.is_some() {
+            drop(self.running_mean_buffer.as_ref().unwrap());
+        }
+
+        if self.running_variance_buffer.is_some() {
+            drop(self.running_variance_buffer.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_normalized_inputs_buffer.is_some() {
+            drop(self.last_normalized_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_batch_variance_buffer.is_some() {
+            drop(self.last_batch_variance_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.gamma_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "gamma_buffer".to_string(),
+            });
+        }
+
+        if self.beta_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "beta_buffer".to_string(),
+            });
+        }
+
+        if self.running_mean_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "running_mean_buffer".to_string(),
+            });
+        }
+
+        if self.running_variance_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "running_variance_buffer".to_string(),
+            });
+        }
+
+        self.gamma = Vec::<f32>::from_buffer(self.gamma_buffer.as_ref().unwrap(), false, state)?;
+        self.beta = Vec::<f32>::from_buffer(self.beta_buffer.as_ref().unwrap(), false, state)?;
+        self.running_mean =
+            Vec::<f32>::from_buffer(self.running_mean_buffer.as_ref().unwrap(), false, state)?;
+        self.running_variance =
+            Vec::<f32>::from_buffer(self.running_variance_buffer.as_ref().unwrap(), false, state)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.gamma.is_empty() {
+            if let Some(initializer) = self.initializers.get("gamma") {
+                self.gamma = initializer.initialize_1d(self.inputs_amount, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer("gamma"));
+            }
+        }
+
+        if self.beta.is_empty() {
+            if let Some(initializer) = self.initializers.get("beta") {
+                self.beta = initializer.initialize_1d(self.inputs_amount, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer("beta"));
+            }
+        }
+
+        if self.running_mean.is_empty() {
+            self.running_mean = vec![0.0; self.inputs_amount];
+        }
+
+        if self.running_variance.is_empty() {
+            self.running_variance = vec![1.0; self.inputs_amount];
+        }
+
+        self.gamma_buffer = Some(self.gamma.to_buffer(false, opencl_state)?);
+        self.beta_buffer = Some(self.beta.to_buffer(false, opencl_state)?);
+        self.running_mean_buffer = Some(self.running_mean.to_buffer(false, opencl_state)?);
+        self.running_variance_buffer = Some(self.running_variance.to_buffer(false, opencl_state)?);
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn set_training_mode(&mut self, is_training: bool) -> () {
+        self.is_training = is_training;
+    }
+
+    fn set_updating_running_statistics(&mut self, should_update: bool) -> () {
+        self.is_updating_running_statistics = should_update;
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.inputs_amount != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.inputs_amount;
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let program = state.get_prgm(BATCH_NORM_PROGRAM_NAME)?;
+
+        let batch_statistics;
+
+        if self.is_training {
+            let statistics_kernel = program.get_krnl(COMPUTE_BATCH_STATISTICS_KERNEL_NAME)?;
+
+            let means_buffer = empty_buffer(self.inputs_amount, CL_MEM_READ_WRITE, state)?;
+            let variances_buffer = empty_buffer(self.inputs_amount, CL_MEM_READ_WRITE, state)?;
+
+            ExecuteKernel::new(statistics_kernel)
+                .set_arg(inputs)
+                .set_arg(&means_buffer)
+                .set_arg(&variances_buffer)
+                .set_arg(self.running_mean_buffer.as_ref().unwrap())
+                .set_arg(self.running_variance_buffer.as_ref().unwrap())
+                .set_arg(&(self.momentum as cl_float))
+                .set_arg(&(self.is_updating_running_statistics as cl_int))
+                .set_arg(&(samples_amount as cl_int))
+                .set_arg(&(self.inputs_amount as cl_int))
+                .set_global_work_size(self.inputs_amount)
+                .enqueue_nd_range(queue)?;
+
+            queue.finish()?;
+
+            batch_statistics = Some((means_buffer, variances_buffer));
+        } else {
+            batch_statistics = None;
+        }
+
+        let (means_buffer, variances_buffer) = match &batch_statistics {
+            Some((means, variances)) => (means, variances),
+            None => (
+                self.running_mean_buffer.as_ref().unwrap(),
+                self.running_variance_buffer.as_ref().unwrap(),
+            ),
+        };
+
+        let normalized_inputs_buffer = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+        let outputs_buffer = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+        let normalize_kernel = program.get_krnl(NORMALIZE_KERNEL_NAME)?;
+
+        ExecuteKernel::new(normalize_kernel)
+            .set_arg(inputs)
+            .set_arg(means_buffer)
+            .set_arg(variances_buffer)
+            .set_arg(self.gamma_buffer.as_ref().unwrap())
+            .set_arg(self.beta_buffer.as_ref().unwrap())
+            .set_arg(&normalized_inputs_buffer)
+            .set_arg(&outputs_buffer)
+            .set_arg(&(self.epsilon as cl_float))
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.inputs_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.inputs_amount])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_normalized_inputs_buffer = Some(normalized_inputs_buffer);
+        self.last_batch_variance_buffer = batch_statistics.map(|(_, variances)| variances);
+        self.last_outputs_buffer = Some(outputs_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_total_count =
+            layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.inputs_amount != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        if self.last_normalized_inputs_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let samples_amount = derivatives_total_count / self.inputs_amount;
+
+        let gamma_gradients = empty_buffer(self.inputs_amount, CL_MEM_READ_WRITE, state)?;
+        let beta_gradients = empty_buffer(self.inputs_amount, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(BATCH_NORM_PROGRAM_NAME)?;
+        let gradients_kernel = program.get_krnl(COMPUTE_GRADIENTS_KERNEL_NAME)?;
+
+        ExecuteKernel::new(gradients_kernel)
+            .set_arg(layer_output_to_error_derivative)
+            .set_arg(self.last_normalized_inputs_buffer.as_ref().unwrap())
+            .set_arg(&gamma_gradients)
+            .set_arg(&beta_gradients)
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.inputs_amount as cl_int))
+            .set_global_work_size(self.inputs_amount)
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(vec![
+            Gradient {
+                parameter_id: "gamma".to_string(),
+                value: gamma_gradients,
+                optimizable: true,
+            },
+            Gradient {
+                parameter_id: "beta".to_string(),
+                value: beta_gradients,
+                optimizable: true,
+            },
+        ])
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.gamma_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "gamma".to_string(),
+            ));
+        }
+
+        if self.beta_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "beta".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.gamma_buffer.as_mut().unwrap(),
+            "gamma".to_string(),
+            timestep,
+            layer_index,
+        )?;
+        optimizer.optimize_parameters(
+            self.beta_buffer.as_mut().unwrap(),
+            "beta".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != 2 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_index,
+            timestep,
+            state,
+        )?;
+
+        let gamma_buffer = self.gamma_buffer.as_mut().unwrap();
+        let beta_buffer = self.beta_buffer.as_mut().unwrap();
+        gamma_buffer.subtract_inplc(&update_vectors[0], state)?;
+        beta_buffer.subtract_inplc(&update_vectors[1], state)?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_total_count =
+            layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.inputs_amount != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        if self.last_normalized_inputs_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let samples_amount = derivatives_total_count / self.inputs_amount;
+
+        let (variances_buffer, used_batch_statistics) = match &self.last_batch_variance_buffer {
+            Some(variances) => (variances, 1),
+            None => (self.running_variance_buffer.as_ref().unwrap(), 0),
+        };
+
+        let loss_to_input_derivatives =
+            empty_buffer(derivatives_total_count, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(BATCH_NORM_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME)?;
+
+        ExecuteKernel::new(kernel)
+            .set_arg(layer_output_to_error_derivative)
+            .set_arg(self.last_normalized_inputs_buffer.as_ref().unwrap())
+            .set_arg(variances_buffer)
+            .set_arg(self.gamma_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_input_derivatives)
+            .set_arg(&(self.epsilon as cl_float))
+            .set_arg(&(used_batch_statistics as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.inputs_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.inputs_amount])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives)
+    }
+}
+
+#[cfg(test)]
+mod batch_norm_tests {
+    use savefile::{load_noschema, save_noschema};
+
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::BatchNorm;
+
+    #[test]
+    fn should_normalize_with_batch_statistics_while_training() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5,
+            0.7, -0.3,
+            0.4, 0.9,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.2, -0.4,
+            0.6, 0.1,
+            -0.3, 0.5,
+        ];
+
+        let expected_outputs = vec![
+            -1.722, -0.0666,
+            1.922, -0.8668,
+            0.1, 0.3335,
+        ];
+        let expected_running_mean = vec![0.04, 0.0367];
+        let expected_running_variance = vec![0.906, 0.9249];
+        let expected_gamma_gradients = vec![0.162, 0.0978];
+        let expected_beta_gradients = vec![0.1667, 0.0667];
+        let expected_loss_to_input_derivatives = vec![
+            1.3972, -0.4929,
+            1.437, 0.1638,
+            -2.8342, 0.3291,
+        ];
+
+        let mut layer = BatchNorm::new_raw(2).set_momentum(0.9);
+        layer.gamma = vec![1.5, 0.5];
+        layer.beta = vec![0.1, -0.2];
+        layer.init(&opencl_state).expect("unable to init BatchNorm");
+        layer.set_training_mode(true);
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the BatchNorm");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        layer
+            .sync_data_from_buffers_to_host()
+            .expect("unable to sync the BatchNorm's data");
+
+        assert_approx_equal_distance(&layer.running_mean, &expected_running_mean, 0.01);
+        assert_approx_equal_distance(&layer.running_variance, &expected_running_variance, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the BatchNorm");
+        let gamma_gradients = Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+            .expect("unable to read the gamma gradients buffer");
+        let beta_gradients = Vec::<f32>::from_buffer(&gradients[1].value, false, &opencl_state)
+            .expect("unable to read the beta gradients buffer");
+
+        assert_approx_equal_distance(&gamma_gradients, &expected_gamma_gradients, 0.01);
+        assert_approx_equal_distance(&beta_gradients, &expected_beta_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the BatchNorm");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_normalize_with_running_statistics_while_predicting() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5,
+            0.7, -0.3,
+            0.4, 0.9,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.2, -0.4,
+            0.6, 0.1,
+            -0.3, 0.5,
+        ];
+
+        let expected_outputs = vec![
+            0.1945, 0.0408,
+            1.1395, -0.3749,
+            0.667, 0.2486,
+        ];
+        let expected_loss_to_input_derivatives = vec![
+            0.315, -0.2079,
+            0.945, 0.052,
+            -0.4725, 0.2598,
+        ];
+
+        let mut layer = BatchNorm::new_raw(2);
+        layer.gamma = vec![1.5, 0.5];
+        layer.beta = vec![0.1, -0.2];
+        layer.running_mean = vec![0.04, 0.0367];
+        layer.running_variance = vec![0.906, 0.9249];
+        layer.init(&opencl_state).expect("unable to init BatchNorm");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the BatchNorm");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(
+                &loss_to_output_derivatives
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the loss to output derivatives buffer"),
+            )
+            .expect("unable to compute the loss to input derivatives of the BatchNorm");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_update_the_running_statistics_after_being_loaded() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![1.0, 2.0, 3.0, 4.0];
+
+        let mut layer_bytes = Vec::new();
+        save_noschema(&mut layer_bytes, 0, &BatchNorm::new_raw(2).set_momentum(0.5))
+            .expect("unable to save the BatchNorm");
+        let mut layer: BatchNorm = load_noschema(&mut layer_bytes.as_slice(), 0)
+            .expect("unable to load the BatchNorm");
+
+        assert!(layer.is_updating_running_statistics);
+
+        layer.init(&opencl_state).expect("unable to init BatchNorm");
+        layer.set_training_mode(true);
+        layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the BatchNorm");
+        layer
+            .sync_data_from_buffers_to_host()
+            .expect("unable to sync the running statistics");
+
+        // halfway between the initial zeros and the batch mean of (2, 3)
+        assert_approx_equal_distance(&layer.running_mean, &vec![1.0, 1.5], 0.01);
+    }
+}
@@ -0,0 +1,789 @@
+//! The module that defines the one dimensional covolutional layer
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+    types::cl_int,
+};
+use rayon::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    conv2d::get_output_size_and_padding_for_axis,
+    initializers::{ConstantInitializer, GlorotUniformInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+const CONV1D_PROGRAM_NAME: &str = "CONV1D";
+const PROGRAM_SOURCE: &str = include_str!("kernels/conv1d.cl");
+
+const PROPAGATION_KERNEL_NAME: &str = "convolute";
+const COMPUTE_WEIGHTS_GRADIENTS_KERNEL_NAME: &str = "compute_weights_gradients";
+const COMPUTE_BIASES_GRADIENTS_KERNEL_NAME: &str = "compute_biases_gradients";
+const COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_conv1d(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        PROPAGATION_KERNEL_NAME.to_string(),
+        COMPUTE_WEIGHTS_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_BIASES_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        CONV1D_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Savefile)]
+/// The padding that is added to both ends of the sequences before passing the filters through
+/// them, the padded values are always zeros.
+pub enum Conv1DPadding {
+    /// No padding at all, the filters only go through positions where they fit entirely inside
+    /// of the sequence, making the outputs shorter than the inputs.
+    Valid,
+    /// Pads the sequences in a way that the outputs have the same length as the inputs divided
+    /// by the stride (rounded up), the extra padding goes to the end when the needed padding is
+    /// odd.
+    Same,
+    /// Pads both ends of the sequences with a certain amount of zeros.
+    Explicit(usize),
+}
+
+impl Default for Conv1DPadding {
+    fn default() -> Self {
+        Self::Valid
+    }
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that passes filters along one dimensional sequences, such as audio or any other kind
+/// of signal, in the same way that a Conv2D does with images.
+///
+/// The inputs of this layer are expected to be flattened per sample with all of the values of
+/// the first channel, then all of the values of the second channel and so on, and the outputs
+/// come out the same way but with one channel per filter, so that Conv1D layers can be stacked.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::{Conv1D, Conv1DPadding};
+/// use intricate::types::ModelLayer;
+///
+/// // this will make a conv layer that goes through a signal of 128 values with 2 channels
+/// // with 16 filters of size 5, keeping the same length
+/// let my_layer: ModelLayer = Conv1D::new_raw(128, 2, 5, 16)
+///     .set_padding(Conv1DPadding::Same)
+///     .into();
+/// ```
+pub struct Conv1D<'a> {
+    /// The length of the inputs of each channel.
+    pub inputs_length: usize,
+    /// The amount of channels that each input sequence has.
+    pub channels: usize,
+    /// The size of the filters.
+    pub filter_size: usize,
+    /// The amount of filters that will be passed through the sequences, which is also the amount
+    /// of channels that the outputs will have.
+    pub filters: usize,
+
+    /// How many values the filters move at each step.
+    pub stride: usize,
+    /// The padding of zeros that is added to the ends of the sequences.
+    pub padding: Conv1DPadding,
+    /// The spacing between the weights of the filters, a dilation of 1 means the filters are
+    /// contiguous.
+    pub dilation: usize,
+
+    /// This is a vec containing the weights of the filters in the shape
+    /// `[filters][channels][filter_size]`.
+    pub weights: Vec<Vec<Vec<f32>>>,
+
+    /// This is a vec containing one bias for each filter.
+    pub biases: Vec<f32>,
+
+    /// The initializer that will be used to generate the initial parameters for the filter's
+    /// weights.
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened filter weights.
+    pub weights_buff: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the filter biases.
+    pub biases_buff: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The buffer that contains the flattened inputs per sample that were last forwad passed into
+    /// this Conv1D layer.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The buffer that contains the flattened outputs per sample that last came out of a forward
+    /// pass into this Conv1D layer.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> Conv1D<'a> {
+    /// Creates a new 1D Convolutional layer with random filters ready for being used
+    /// in a Model.
+    pub fn new(
+        inputs_length: usize,
+        channels: usize,
+        filter_size: usize,
+        filters: usize,
+    ) -> ModelLayer<'a> {
+        Self::new_raw(inputs_length, channels, filter_size, filters).into()
+    }
+
+    /// Crates a new raw 1D Convolutional layer with random filters.
+    pub fn new_raw(
+        inputs_length: usize,
+        channels: usize,
+        filter_size: usize,
+        filters: usize,
+    ) -> Self {
+        let mut initializers = HashMap::with_capacity(2);
+        initializers.insert(
+            "weights".to_string(),
+            GlorotUniformInitializer::new().into(),
+        );
+        initializers.insert("biases".to_string(), ConstantInitializer::new(0.0).into());
+
+        Conv1D {
+            inputs_length,
+            channels,
+            filter_size,
+            filters,
+            stride: 1,
+            padding: Conv1DPadding::Valid,
+            dilation: 1,
+            weights: Vec::default(),
+            biases: Vec::default(),
+            initializers,
+            weights_buff: None,
+            biases_buff: None,
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+            opencl_state: None,
+        }
+    }
+
+    /// Sets how many values the filters move at each step and returns the mutated Self.
+    pub fn set_stride(mut self, stride: usize) -> Self {
+        assert!(stride > 0, "the stride of a Conv1D must be at least 1");
+        self.stride = stride;
+
+        self
+    }
+
+    /// Sets the padding that is added to the ends of the sequences and returns the mutated Self.
+    pub fn set_padding(mut self, padding: Conv1DPadding) -> Self {
+        self.padding = padding;
+
+        self
+    }
+
+    /// Sets the spacing between the weights of the filters and returns the mutated Self.
+    pub fn set_dilation(mut self, dilation: usize) -> Self {
+        assert!(dilation > 0, "the dilation of a Conv1D must be at least 1");
+        self.dilation = dilation;
+
+        self
+    }
+
+    fn get_outputs_length_and_padding(&self) -> (usize, usize) {
+        get_output_size_and_padding_for_axis(
+            self.inputs_length,
+            self.filter_size,
+            self.stride,
+            self.dilation,
+            self.padding,
+        )
+    }
+
+    /// Gets the length of each one of the sequences that come out of the convolution.
+    pub fn get_outputs_length(&self) -> usize {
+        let (outputs_length, _) = self.get_outputs_length_and_padding();
+
+        outputs_length
+    }
+
+    /// Gets the amount of zeros that are padded to the start of the sequences.
+    pub fn get_padding_before(&self) -> usize {
+        let (_, padding) = self.get_outputs_length_and_padding();
+
+        padding
+    }
+}
+
+impl<'a> Layer<'a> for Conv1D<'a> {
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "weights" => Some(
+                self.weights
+                    .par_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|x| *x)
+                    .collect(),
+            ),
+            "biases" => Some(self.biases.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_length * self.channels
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.get_outputs_length() * self.filters
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.weights_buff.is_some() {
+            drop(self.weights_buff.as_ref().unwrap());
+        }
+
+        if self.biases_buff.is_some() {
+            drop(self.biases_buff.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.weights_buff.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "weights".to_string(),
+            });
+        }
+
+        if self.biases_buff.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "biases".to_string(),
+            });
+        }
+
+        let weights = Vec::<f32>::from_buffer(self.weights_buff.as_ref().unwrap(), false, state)?;
+
+        self.weights = weights
+            .par_chunks(self.channels * self.filter_size)
+            .map(|filter| {
+                filter
+                    .chunks(self.filter_size)
+                    .map(|channel| channel.to_vec())
+                    .collect()
+            })
+            .collect();
+
+        self.biases = Vec::<f32>::from_buffer(self.biases_buff.as_ref().unwrap(), false, state)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.get_outputs_amount() == 0 {
+            return Err(LayerInitializationError::FilterBiggerThanInputs);
+        }
+
+        if self.weights.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("weights") {
+                self.weights = initializer.initialize_3d(
+                    (self.filters, self.channels, self.filter_size),
+                    self,
+                );
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "weights",
+                ));
+            }
+        }
+
+        if self.biases.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("biases") {
+                self.biases = initializer.initialize_1d(self.filters, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "biases",
+                ));
+            }
+        }
+
+        self.weights_buff = Some(
+            self.weights
+                .par_iter()
+                .flatten()
+                .flatten()
+                .map(|x| *x)
+                .collect::<Vec<f32>>()
+                .to_buffer(false, opencl_state)?,
+        );
+
+        self.biases_buff = Some(self.biases.to_buffer(false, opencl_state)?);
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let (outputs_length, padding) = self.get_outputs_length_and_padding();
+
+        let outputs = empty_buffer(
+            self.get_outputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(CONV1D_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(PROPAGATION_KERNEL_NAME)?;
+
+        ExecuteKernel::new(kernel)
+            .set_arg(inputs)
+            .set_arg(self.weights_buff.as_ref().unwrap())
+            .set_arg(self.biases_buff.as_ref().unwrap())
+            .set_arg(&outputs)
+            .set_arg(&(self.inputs_length as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_length as cl_int))
+            .set_arg(&(self.filter_size as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride as cl_int))
+            .set_arg(&(padding as cl_int))
+            .set_arg(&(self.dilation as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_outputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_outputs_buffer = Some(outputs);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivatives: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        if self.last_inputs_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivatives.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let (outputs_length, padding) = self.get_outputs_length_and_padding();
+
+        let program = state.get_prgm(CONV1D_PROGRAM_NAME)?;
+
+        let weights_gradients = empty_buffer(
+            self.filters * self.channels * self.filter_size,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_WEIGHTS_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&weights_gradients)
+            .set_arg(&(self.inputs_length as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_length as cl_int))
+            .set_arg(&(self.filter_size as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride as cl_int))
+            .set_arg(&(padding as cl_int))
+            .set_arg(&(self.dilation as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[self.filters, self.channels, self.filter_size])
+            .enqueue_nd_range(queue)?;
+
+        let biases_gradients = empty_buffer(self.filters, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_BIASES_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&biases_gradients)
+            .set_arg(&(outputs_length as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_size(self.filters)
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(vec![
+            Gradient {
+                optimizable: true,
+                parameter_id: "weights".to_string(),
+                value: weights_gradients,
+            },
+            Gradient {
+                optimizable: true,
+                parameter_id: "biases".to_string(),
+                value: biases_gradients,
+            },
+        ])
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.weights_buff.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "weights".to_string(),
+            ));
+        }
+
+        if self.biases_buff.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "biases".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.weights_buff.as_mut().unwrap(),
+            "weights".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        optimizer.optimize_parameters(
+            self.biases_buff.as_mut().unwrap(),
+            "biases".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_model_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != 2 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_model_index,
+            timestep,
+            state,
+        )?;
+
+        let weights_buff = self.weights_buff.as_mut().unwrap();
+        weights_buff.subtract_inplc(&update_vectors[0], state)?;
+        let biases_buff = self.biases_buff.as_mut().unwrap();
+        biases_buff.subtract_inplc(&update_vectors[1], state)?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        if self.weights_buff.is_none() {
+            return Err(LayerLossToInputDifferentiationError::MissingParameter(
+                "weights",
+            ));
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let (outputs_length, padding) = self.get_outputs_length_and_padding();
+
+        let loss_to_input_derivatives_buffer = empty_buffer(
+            self.get_inputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(CONV1D_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME)?;
+
+        ExecuteKernel::new(kernel)
+            .set_arg(self.weights_buff.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivative)
+            .set_arg(&loss_to_input_derivatives_buffer)
+            .set_arg(&(self.inputs_length as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_length as cl_int))
+            .set_arg(&(self.filter_size as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride as cl_int))
+            .set_arg(&(padding as cl_int))
+            .set_arg(&(self.dilation as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod conv1d_tests {
+    use crate::{
+        layers::{Layer, LayerInitializationError},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::{Conv1D, Conv1DPadding};
+
+    #[test]
+    fn should_convolute_and_compute_gradients_with_stride_padding_and_dilation() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.3, 0.7, 0.2, 0.9, 0.4,
+            0.8, 0.6, 0.3, 0.5, 0.1, 0.2, 0.9,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.1, 0.2, 0.3, 0.4,
+            0.5, 0.6, 0.7, 0.8,
+        ];
+
+        // with this padding and dilation only the odd positions of the inputs are ever used
+        let expected_outputs = vec![
+            0.44, 0.67, 0.58, 0.25,
+            -0.02, 0.45, 0.12, 0.39,
+        ];
+        let expected_weights_gradients = vec![
+            0.67, 0.46,
+            0.35, 0.22,
+
+            1.51, 1.3,
+            0.87, 0.74,
+        ];
+        let expected_biases_gradients = vec![1.0, 2.6];
+        let expected_loss_to_input_derivatives = vec![
+            0.0, 0.04, 0.0, 0.06, 0.0, 0.08, 0.0,
+            0.0, 0.92, 0.0, 1.14, 0.0, 1.36, 0.0,
+        ];
+
+        let mut layer = Conv1D::new_raw(7, 2, 2, 2)
+            .set_stride(2)
+            .set_padding(Conv1DPadding::Same)
+            .set_dilation(2);
+        assert_eq!(layer.get_outputs_length(), 4);
+        assert_eq!(layer.get_padding_before(), 1);
+
+        layer.weights = vec![
+            vec![vec![0.1, 0.2], vec![0.3, 0.4]],
+            vec![vec![0.5, -0.6], vec![0.7, 0.8]],
+        ];
+        layer.biases = vec![0.1, -0.2];
+        layer.init(&opencl_state).expect("unable to init Conv1D");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the Conv1D");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the Conv1D");
+        let weights_gradients = Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+            .expect("unable to read the weights gradients buffer");
+        let biases_gradients = Vec::<f32>::from_buffer(&gradients[1].value, false, &opencl_state)
+            .expect("unable to read the biases gradients buffer");
+
+        assert_approx_equal_distance(&weights_gradients, &expected_weights_gradients, 0.01);
+        assert_approx_equal_distance(&biases_gradients, &expected_biases_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the Conv1D");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_not_initialize_when_the_dilated_filter_is_bigger_than_the_inputs() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let mut layer = Conv1D::new_raw(4, 1, 3, 1).set_dilation(2);
+
+        assert_eq!(layer.get_outputs_amount(), 0);
+        assert!(matches!(
+            layer.init(&opencl_state),
+            Err(LayerInitializationError::FilterBiggerThanInputs)
+        ));
+    }
+}
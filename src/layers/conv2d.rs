@@ -1,7 +1,5 @@
 //! The module that defines the covolutional layer
 
-// TODO: Add stride, padding and improve gradient calculation that is tremendously slow rn
-
 use std::{collections::HashMap, mem};
 
 use opencl3::{
@@ -28,6 +26,7 @@ use crate::{
 
 use super::{
     compute_update_vectors,
+    conv1d::Conv1DPadding,
     initializers::{ConstantInitializer, GlorotUniformInitializer, Initializer, InitializerTrait},
     Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
     LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
@@ -39,7 +38,7 @@ const PROGRAM_SORUCE: &str = include_str!("kernels/conv2d.cl");
 
 const PROPAGATION_KERNEL_NAME: &str = "convolute";
 const COMPUTE_WEIGHT_GRADIENTS_KERNEL_NAME: &str = "compute_gradients_for_one_filter_pixel";
-// const COMPUTE_BIAS_GRADIENTS_KERNEL_NAME: &str = "compute_gradients_for_biases";
+const COMPUTE_BIAS_GRADIENTS_KERNEL_NAME: &str = "compute_gradients_for_biases";
 const COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
 
 pub(crate) fn compile_conv2d(
@@ -48,7 +47,7 @@ pub(crate) fn compile_conv2d(
     let prop_kernels = &[
         PROPAGATION_KERNEL_NAME.to_string(),
         COMPUTE_WEIGHT_GRADIENTS_KERNEL_NAME.to_string(),
-        // COMPUTE_BIAS_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_BIAS_GRADIENTS_KERNEL_NAME.to_string(),
         COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME.to_string(),
     ];
 
@@ -63,6 +62,96 @@ pub(crate) fn compile_conv2d(
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Savefile)]
+/// The padding that is added around the images before passing the filters through them, the
+/// padded pixels are always zeros.
+pub enum Conv2DPadding {
+    /// No padding at all, the filters only go through positions where they fit entirely inside
+    /// of the image, making the outputs smaller than the inputs.
+    Valid,
+    /// Pads the images in a way that the outputs have the same size as the inputs divided by the
+    /// stride (rounded up), the extra padding goes to the right and to the bottom when the
+    /// needed padding is odd.
+    Same,
+    /// Pads the images with a certain amount of pixels to the left and right, and to the top and
+    /// bottom respectively.
+    Explicit((usize, usize)),
+}
+
+impl Default for Conv2DPadding {
+    fn default() -> Self {
+        Self::Valid
+    }
+}
+
+// gets the size of the outputs along a single axis of a convolution and the padding added before
+// the inputs on that axis, which is also used by the Conv1D so that the padding rules are the same
+//
+// there are no outputs when the filter is bigger than the padded inputs, which the layers check
+// for when initializing
+pub(crate) fn get_output_size_and_padding_for_axis(
+    input_size: usize,
+    filter_size: usize,
+    stride: usize,
+    dilation: usize,
+    padding: Conv1DPadding,
+) -> (usize, usize) {
+    let dilated_filter_size = (filter_size - 1) * dilation + 1;
+    let get_fitting_size = |padded_input_size: usize| {
+        padded_input_size
+            .checked_sub(dilated_filter_size)
+            .map_or(0, |remaining_size| remaining_size / stride + 1)
+    };
+
+    match padding {
+        Conv1DPadding::Valid => (get_fitting_size(input_size), 0),
+        Conv1DPadding::Same => {
+            let output_size = (input_size + stride - 1) / stride;
+            let total_padding = ((output_size - 1) * stride + dilated_filter_size)
+                .saturating_sub(input_size);
+
+            (output_size, total_padding / 2)
+        }
+        Conv1DPadding::Explicit(padding) => (get_fitting_size(input_size + 2 * padding), padding),
+    }
+}
+
+// gets the size of the images that come out of a convolution and the padding added to the left
+// and to the top of the images, shared with the other layers that convolute the same way
+pub(crate) fn get_convolution_outputs_size_and_padding(
+    inputs_size: (usize, usize),
+    filter_size: (usize, usize),
+    stride: (usize, usize),
+    dilation: (usize, usize),
+    padding: Conv2DPadding,
+) -> ((usize, usize), (usize, usize)) {
+    let (padding_x, padding_y) = match padding {
+        Conv2DPadding::Valid => (Conv1DPadding::Valid, Conv1DPadding::Valid),
+        Conv2DPadding::Same => (Conv1DPadding::Same, Conv1DPadding::Same),
+        Conv2DPadding::Explicit((padding_x, padding_y)) => (
+            Conv1DPadding::Explicit(padding_x),
+            Conv1DPadding::Explicit(padding_y),
+        ),
+    };
+
+    let (outputs_width, padding_x) = get_output_size_and_padding_for_axis(
+        inputs_size.0,
+        filter_size.0,
+        stride.0,
+        dilation.0,
+        padding_x,
+    );
+    let (outputs_height, padding_y) = get_output_size_and_padding_for_axis(
+        inputs_size.1,
+        filter_size.1,
+        stride.1,
+        dilation.1,
+        padding_y,
+    );
+
+    ((outputs_width, outputs_height), (padding_x, padding_y))
+}
+
 #[derive(Debug, Savefile)]
 /// A layer that tries to compact data from a 2D image, or just a matrix,
 /// without loosing spatial information. It does this by passing a filter from
@@ -75,6 +164,10 @@ pub(crate) fn compile_conv2d(
 /// to pass the filter through the image, in a way that the max size of the local work group
 /// in your OpenCL device needs to be the at least the volume of your filter.**
 ///
+/// The inputs of this layer are expected to be flattened per sample with all of the pixels of
+/// the first channel, then all of the pixels of the second channel and so on, and the outputs
+/// come out the same way but with one channel per filter, so that Conv2D layers can be stacked.
+///
 /// This type of layer proves to be extremely useful when working with images,
 /// as it makes both the model much more memory efficient and does make the model
 /// perform much, much better. (take a look at the `MNIST` example)
@@ -84,20 +177,48 @@ pub(crate) fn compile_conv2d(
 /// ```rust
 /// use intricate::layers::Conv2D;
 ///
-/// // this will make a conv layer that will go through a 28x28 image
-/// // with a 3x3 filter
-/// let my_layer: Conv2D = Conv2D::new_raw((28, 28), (3, 3));
+/// // this will make a conv layer that will go through a 28x28 RGB image
+/// // with 8 filters of 3x3
+/// let my_layer: Conv2D = Conv2D::new_raw((28, 28), 3, (3, 3), 8);
+/// ```
+///
+/// The stride, padding and dilation of the convolution can be changed with the builder methods
+/// of the raw layer.
+///
+/// ```rust
+/// use intricate::layers::{Conv2D, Conv2DPadding};
+/// use intricate::types::ModelLayer;
+///
+/// // this will make a conv layer that halves the size of a 28x28 image
+/// let my_layer: ModelLayer = Conv2D::new_raw((28, 28), 1, (3, 3), 8)
+///     .set_stride((2, 2))
+///     .set_padding(Conv2DPadding::Same)
+///     .into();
 /// ```
 pub struct Conv2D<'a> {
     /// The size of the inputs, width and height respectively.
     pub inputs_size: (usize, usize),
+    /// The amount of channels that each input image has.
+    pub channels: usize,
     /// The size of the filter, width and height respectively.
     pub filter_size: (usize, usize),
-
-    /// This is a vec containing the certain weight for a pixel in the filter.
-    pub weights: Vec<Vec<f32>>,
-
-    /// This is a vec containing the biases for a pixel in the filter.
+    /// The amount of filters that will be passed through the image, which is also the amount of
+    /// channels that the outputs will have.
+    pub filters: usize,
+
+    /// How many pixels the filters move at each step, horizontally and vertically respectively.
+    pub stride: (usize, usize),
+    /// The padding of zeros that is added around the images.
+    pub padding: Conv2DPadding,
+    /// The spacing between the pixels of the filters, horizontally and vertically respectively.
+    /// A dilation of 1 means the filters are contiguous.
+    pub dilation: (usize, usize),
+
+    /// This is a vec containing the weights of each pixel of the filters in the shape
+    /// `[filters][channels][height][width]`.
+    pub weights: Vec<Vec<Vec<Vec<f32>>>>,
+
+    /// This is a vec containing one bias for each filter.
     pub biases: Vec<f32>,
 
     /// The initializer that will be used to generate the initial parameters for the filter's
@@ -131,14 +252,24 @@ pub struct Conv2D<'a> {
 }
 
 impl<'a> Conv2D<'a> {
-    /// Creates a new 2D Convolutional layer with a random filter ready for being used
+    /// Creates a new 2D Convolutional layer with random filters ready for being used
     /// in a Model.
-    pub fn new(inputs_size: (usize, usize), filter_size: (usize, usize)) -> ModelLayer<'a> {
-        Self::new_raw(inputs_size, filter_size).into()
+    pub fn new(
+        inputs_size: (usize, usize),
+        channels: usize,
+        filter_size: (usize, usize),
+        filters: usize,
+    ) -> ModelLayer<'a> {
+        Self::new_raw(inputs_size, channels, filter_size, filters).into()
     }
 
-    /// Crates a new raw 2D Convolutional layer with a random filter.
-    pub fn new_raw(inputs_size: (usize, usize), filter_size: (usize, usize)) -> Self {
+    /// Crates a new raw 2D Convolutional layer with random filters.
+    pub fn new_raw(
+        inputs_size: (usize, usize),
+        channels: usize,
+        filter_size: (usize, usize),
+        filters: usize,
+    ) -> Self {
         let mut initializers = HashMap::with_capacity(2);
         initializers.insert(
             "weights".to_string(),
@@ -148,7 +279,12 @@ impl<'a> Conv2D<'a> {
 
         Conv2D {
             inputs_size,
+            channels,
             filter_size,
+            filters,
+            stride: (1, 1),
+            padding: Conv2DPadding::Valid,
+            dilation: (1, 1),
             weights: Vec::default(),
             biases: Vec::default(),
             initializers,
@@ -159,12 +295,71 @@ impl<'a> Conv2D<'a> {
             opencl_state: None,
         }
     }
+
+    /// Sets how many pixels the filters move at each step, horizontally and vertically
+    /// respectively, and returns the mutated Self.
+    pub fn set_stride(mut self, stride: (usize, usize)) -> Self {
+        assert!(stride.0 > 0 && stride.1 > 0, "the stride of a Conv2D must be at least 1");
+        self.stride = stride;
+
+        self
+    }
+
+    /// Sets the padding that is added around the images and returns the mutated Self.
+    pub fn set_padding(mut self, padding: Conv2DPadding) -> Self {
+        self.padding = padding;
+
+        self
+    }
+
+    /// Sets the spacing between the pixels of the filters, horizontally and vertically
+    /// respectively, and returns the mutated Self.
+    pub fn set_dilation(mut self, dilation: (usize, usize)) -> Self {
+        assert!(dilation.0 > 0 && dilation.1 > 0, "the dilation of a Conv2D must be at least 1");
+        self.dilation = dilation;
+
+        self
+    }
+
+    fn get_outputs_size_and_padding(&self) -> ((usize, usize), (usize, usize)) {
+        get_convolution_outputs_size_and_padding(
+            self.inputs_size,
+            self.filter_size,
+            self.stride,
+            self.dilation,
+            self.padding,
+        )
+    }
+
+    /// Gets the size of each one of the images that come out of the convolution, width and
+    /// height respectively.
+    pub fn get_outputs_size(&self) -> (usize, usize) {
+        let (outputs_size, _) = self.get_outputs_size_and_padding();
+
+        outputs_size
+    }
+
+    /// Gets the amount of zeros that are padded to the left and to the top of the images
+    /// respectively.
+    pub fn get_padding_before(&self) -> (usize, usize) {
+        let (_, padding) = self.get_outputs_size_and_padding();
+
+        padding
+    }
 }
 
 impl<'a> Layer<'a> for Conv2D<'a> {
     fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
         match parameter {
-            "weights" => Some(self.weights.par_iter().flatten().map(|x| *x).collect()),
+            "weights" => Some(
+                self.weights
+                    .par_iter()
+                    .flatten()
+                    .flatten()
+                    .flatten()
+                    .map(|x| *x)
+                    .collect(),
+            ),
             "biases" => Some(self.biases.to_vec()),
             _ => None,
         }
@@ -192,12 +387,21 @@ impl<'a> Layer<'a> for Conv2D<'a> {
     }
 
     fn get_inputs_amount(&self) -> usize {
-        self.inputs_size.0 * self.inputs_size.1
+        self.inputs_size.0 * self.inputs_size.1 * self.channels
     }
 
     fn get_outputs_amount(&self) -> usize {
-        (self.inputs_size.0 - self.filter_size.0 + 1)
-            * (self.inputs_size.1 - self.filter_size.1 + 1)
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        outputs_width * outputs_height * self.filters
+    }
+
+    fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+        Some(vec![self.channels, self.inputs_size.1, self.inputs_size.0])
+    }
+
+    fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        Some(vec![self.filters, outputs_height, outputs_width])
     }
 
     fn clean_up_gpu_state(&mut self) -> () {
@@ -242,13 +446,18 @@ impl<'a> Layer<'a> for Conv2D<'a> {
 
         let filter_weights = Vec::<f32>::from_buffer(filter_weights_buffer, false, state)?;
 
-        self.weights = (0..self.filter_size.1)
-            .into_par_iter()
-            .map(|y| {
-                (0..self.filter_size.0)
-                    .map(|x| {
-                        let flat_index = y * self.filter_size.0 + x;
-                        filter_weights[flat_index]
+        let filter_volume = self.filter_size.0 * self.filter_size.1;
+
+        self.weights = filter_weights
+            .par_chunks(self.channels * filter_volume)
+            .map(|filter| {
+                filter
+                    .chunks(filter_volume)
+                    .map(|channel| {
+                        channel
+                            .chunks(self.filter_size.0)
+                            .map(|row| row.to_vec())
+                            .collect()
                     })
                     .collect()
             })
@@ -262,9 +471,21 @@ impl<'a> Layer<'a> for Conv2D<'a> {
     }
 
     fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.get_outputs_amount() == 0 {
+            return Err(LayerInitializationError::FilterBiggerThanInputs);
+        }
+
         if self.weights.is_empty() {
             if let Some(initializer) = self.get_initializer_for_parameter("weights") {
-                self.weights = initializer.initialize_2d((self.filter_size.1, self.filter_size.0), self);
+                self.weights = initializer.initialize_4d(
+                    (
+                        self.filters,
+                        self.channels,
+                        self.filter_size.1,
+                        self.filter_size.0,
+                    ),
+                    self,
+                );
             } else {
                 return Err(LayerInitializationError::MissingParameterInitializer(
                     "weights",
@@ -274,13 +495,7 @@ impl<'a> Layer<'a> for Conv2D<'a> {
 
         if self.biases.is_empty() {
             if let Some(initializer) = self.get_initializer_for_parameter("biases") {
-                self.biases = initializer.initialize_1d(
-                    // (
-                    //     self.inputs_size.0 - self.filter_size.0 + 1, // the output width
-                    //     self.inputs_size.1 - self.filter_size.1 + 1, // the output height
-                    // ),
-                    1, self,
-                );
+                self.biases = initializer.initialize_1d(self.filters, self);
             } else {
                 return Err(LayerInitializationError::MissingParameterInitializer(
                     "biases",
@@ -292,6 +507,8 @@ impl<'a> Layer<'a> for Conv2D<'a> {
             self.weights
                 .par_iter()
                 .flatten()
+                .flatten()
+                .flatten()
                 .map(|x| *x)
                 .collect::<Vec<f32>>()
                 .to_buffer(false, opencl_state)?,
@@ -323,21 +540,23 @@ impl<'a> Layer<'a> for Conv2D<'a> {
         let inputs_size = inputs.size()?;
         let inputs_volume = inputs_size / mem::size_of::<cl_float>();
 
-        let image_volume = self.get_inputs_amount();
-        let convolution_volume = self.get_outputs_amount();
+        let image_volume = self.inputs_size.0 * self.inputs_size.1;
+        let ((convolution_width, convolution_height), (padding_x, padding_y)) =
+            self.get_outputs_size_and_padding();
+        let convolution_volume = convolution_width * convolution_height;
 
-        if inputs_volume % image_volume != 0 {
+        if inputs_volume % self.get_inputs_amount() != 0 {
             return Err(LayerPropagationError::InputsDontMatchExpectedShape);
         }
 
-        let samples_amount = inputs_volume / image_volume;
+        let samples_amount = inputs_volume / self.get_inputs_amount();
 
         self.last_inputs_buffer = Some(inputs.clone(state)?);
         let program = state.get_prgm(CONV2D_PROGRAM_NAME)?;
         let kernel = program.get_krnl(PROPAGATION_KERNEL_NAME)?;
 
         let outputs = empty_buffer(
-            convolution_volume * samples_amount,
+            self.get_outputs_amount() * samples_amount,
             CL_MEM_READ_WRITE,
             state,
         )?;
@@ -362,16 +581,25 @@ impl<'a> Layer<'a> for Conv2D<'a> {
             // the max size for local workgroups has to fit the filter
             .set_arg_local_buffer(samples_local_size * filter_volume * std::mem::size_of::<f32>())
             .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
             .set_arg(&(image_volume as cl_int))
-            .set_arg(&((self.inputs_size.0 - self.filter_size.0 + 1) as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(convolution_width as cl_int))
             .set_arg(&(convolution_volume as cl_int))
             .set_arg(&(self.filter_size.0 as cl_int))
             .set_arg(&(self.filter_size.1 as cl_int))
             .set_arg(&(filter_volume as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(padding_x as cl_int))
+            .set_arg(&(padding_y as cl_int))
+            .set_arg(&(self.dilation.0 as cl_int))
+            .set_arg(&(self.dilation.1 as cl_int))
             .set_arg(&(samples_amount as cl_int))
             .set_global_work_sizes(&[
                 samples_global_size,
-                convolution_volume * filter_volume,
+                self.filters * convolution_volume * filter_volume,
             ])
             .set_local_work_sizes(&[
                 samples_local_size,
@@ -408,72 +636,65 @@ impl<'a> Layer<'a> for Conv2D<'a> {
         let derivatives_size = layer_output_to_error_derivatives.size()?;
         let derivatives_volume = derivatives_size / mem::size_of::<cl_float>();
 
-        let image_volume = self.get_inputs_amount();
-        let convolution_volume = self.get_outputs_amount();
+        let image_volume = self.inputs_size.0 * self.inputs_size.1;
+        let ((convolution_width, convolution_height), (padding_x, padding_y)) =
+            self.get_outputs_size_and_padding();
+        let convolution_volume = convolution_width * convolution_height;
 
-        if derivatives_volume % convolution_volume != 0 {
+        if derivatives_volume % self.get_outputs_amount() != 0 {
             return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
         }
 
-        let samples_amount = derivatives_volume / convolution_volume;
+        let samples_amount = derivatives_volume / self.get_outputs_amount();
 
         let filter_volume = self.filter_size.0 * self.filter_size.1;
 
-        let mut weights_gradients: Vec<f32> = Vec::with_capacity(filter_volume);
-
         let program = state.get_prgm(CONV2D_PROGRAM_NAME)?;
         let compute_gradient_weights_kernel =
             program.get_krnl(COMPUTE_WEIGHT_GRADIENTS_KERNEL_NAME)?;
-        // let compute_gradient_biases_kernel =
-        //     program.get_krnl(COMPUTE_BIAS_GRADIENTS_KERNEL_NAME)?;
-
-        for pixel_index in 0..filter_volume {
-            let filter_pixel_gradients = empty_buffer(
-                convolution_volume * samples_amount,
-                CL_MEM_READ_WRITE,
-                state,
-            )?;
-
-            let pixel_y =
-                ((pixel_index + 1) as f32 / self.filter_size.0 as f32).ceil() as usize - 1;
-            let pixel_x = pixel_index % self.filter_size.0;
-
-            let convolution_width = self.inputs_size.0 - self.filter_size.0 + 1;
-            let convolution_height = self.inputs_size.1 - self.filter_size.1 + 1;
-
-            ExecuteKernel::new(compute_gradient_weights_kernel)
-                .set_arg(self.last_inputs_buffer.as_ref().unwrap())
-                .set_arg(layer_output_to_error_derivatives)
-                .set_arg(&filter_pixel_gradients)
-                .set_arg(&(self.inputs_size.0 as cl_int))
-                .set_arg(&(image_volume as cl_int))
-                .set_arg(&(self.filter_size.0 as cl_int))
-                .set_arg(&(filter_volume as cl_int))
-                .set_arg(&(samples_amount.clone() as cl_int))
-                .set_arg(&(convolution_width as cl_int))
-                .set_arg(&(convolution_height as cl_int))
-                .set_arg(&(convolution_volume as cl_int))
-                .set_arg(&(pixel_index as cl_int))
-                .set_arg(&(pixel_y as cl_int))
-                .set_arg(&(pixel_x as cl_int))
-                .set_global_work_sizes(&[samples_amount, convolution_height, convolution_width])
-                .enqueue_nd_range(queue)?
-                .wait()?;
-
-            weights_gradients.push(filter_pixel_gradients.sum(state)? / samples_amount as f32);
-        }
+        let compute_gradient_biases_kernel =
+            program.get_krnl(COMPUTE_BIAS_GRADIENTS_KERNEL_NAME)?;
 
-        let bias_gradients = layer_output_to_error_derivatives.sum(state)? / samples_amount as f32;
-        // empty_buffer(self.get_outputs_amount(), CL_MEM_READ_WRITE, state)?;
+        let weights_gradients = empty_buffer(
+            self.filters * self.channels * filter_volume,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
 
-        // ExecuteKernel::new(compute_gradient_biases_kernel)
-        //     .set_arg(layer_output_to_error_derivatives)
-        //     .set_arg(&bias_gradients)
-        //     .set_arg(&(samples_amount as cl_int))
-        //     .set_arg(&(convolution_volume as cl_int))
-        //     .set_global_work_size(convolution_volume)
-        //     .enqueue_nd_range(queue)?
-        //     .wait()?;
+        ExecuteKernel::new(compute_gradient_weights_kernel)
+            .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&weights_gradients)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
+            .set_arg(&(image_volume as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(self.filter_size.0 as cl_int))
+            .set_arg(&(filter_volume as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(padding_x as cl_int))
+            .set_arg(&(padding_y as cl_int))
+            .set_arg(&(self.dilation.0 as cl_int))
+            .set_arg(&(self.dilation.1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(convolution_width as cl_int))
+            .set_arg(&(convolution_height as cl_int))
+            .set_arg(&(convolution_volume as cl_int))
+            .set_global_work_sizes(&[self.filters, self.channels, filter_volume])
+            .enqueue_nd_range(queue)?;
+
+        let bias_gradients = empty_buffer(self.filters, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(compute_gradient_biases_kernel)
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&bias_gradients)
+            .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(convolution_volume as cl_int))
+            .set_global_work_size(self.filters)
+            .enqueue_nd_range(queue)?;
 
         queue.finish()?;
 
@@ -481,12 +702,12 @@ impl<'a> Layer<'a> for Conv2D<'a> {
             Gradient {
                 optimizable: true,
                 parameter_id: "weights".to_string(),
-                value: weights_gradients.to_buffer(false, state)?,
+                value: weights_gradients,
             },
             Gradient {
                 optimizable: true,
                 parameter_id: "biases".to_string(),
-                value: vec![bias_gradients].to_buffer(false, state)?,
+                value: bias_gradients,
             },
         ])
     }
@@ -582,7 +803,7 @@ impl<'a> Layer<'a> for Conv2D<'a> {
         let derivatives_size = layer_output_to_error_derivative.size()?;
         let derivatives_volume = derivatives_size / mem::size_of::<cl_float>();
 
-        let image_volume = self.get_inputs_amount();
+        let image_volume = self.inputs_size.0 * self.inputs_size.1;
         let convolution_volume = self.get_outputs_amount();
 
         if derivatives_volume % convolution_volume != 0 {
@@ -604,8 +825,8 @@ impl<'a> Layer<'a> for Conv2D<'a> {
         let samples_amount = derivatives_volume / convolution_volume;
         let filter_width = self.filter_size.0;
         let filter_height = self.filter_size.1;
-        let outputs_width = self.inputs_size.0 - self.filter_size.0 + 1;
-        let outputs_height = self.inputs_size.1 - self.filter_size.1 + 1;
+        let ((outputs_width, outputs_height), (padding_x, padding_y)) =
+            self.get_outputs_size_and_padding();
 
         let program = state.get_prgm(CONV2D_PROGRAM_NAME)?;
         let kernel = program.get_krnl(COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME)?;
@@ -621,13 +842,21 @@ impl<'a> Layer<'a> for Conv2D<'a> {
             .set_arg(layer_output_to_error_derivative)
             .set_arg(&loss_to_input_derivatives_buffer)
             .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(self.filters as cl_int))
             .set_arg(&(filter_width as cl_int))
             .set_arg(&(filter_height as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(padding_x as cl_int))
+            .set_arg(&(padding_y as cl_int))
+            .set_arg(&(self.dilation.0 as cl_int))
+            .set_arg(&(self.dilation.1 as cl_int))
             .set_arg(&(outputs_height as cl_int))
             .set_arg(&(outputs_width as cl_int))
             .set_arg(&(image_volume as cl_int))
             .set_arg(&(self.inputs_size.0 as cl_int))
-            .set_global_work_sizes(&[samples_amount, image_volume])
+            .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
             .enqueue_nd_range(queue)?;
 
         queue.finish()?;
@@ -638,9 +867,9 @@ impl<'a> Layer<'a> for Conv2D<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::Conv2D;
+    use super::{Conv2D, Conv2DPadding};
     use crate::{
-        layers::Layer,
+        layers::{Layer, LayerInitializationError},
         utils::{
             approx_eq::{self, assert_approx_equal_distance},
             opencl::{BufferLike, DeviceType},
@@ -748,7 +977,7 @@ mod tests {
             .to_buffer(false, &opencl_state)
             .expect("unable to get the output to loss derivatives buffer");
 
-        let mut conv2d = Conv2D::new_raw((4, 4), (3, 3));
+        let mut conv2d = Conv2D::new_raw((4, 4), 1, (3, 3), 1);
         conv2d
             .init(&opencl_state)
             .expect("unable to initialize raw conv2D layer");
@@ -792,7 +1021,7 @@ mod tests {
             1.458, 1.53, 2.1852999
         ];
 
-        let mut layer = Conv2D::new_raw((5, 3), (3, 3));
+        let mut layer = Conv2D::new_raw((5, 3), 1, (3, 3), 1);
         layer.init(&opencl_state).expect("unable to init Conv2D");
         layer.weights_buff = Some(filter);
         layer.biases_buff = Some(bias);
@@ -805,4 +1034,290 @@ mod tests {
 
         assert_approx_equal_distance(&result, &expected_result, 0.01);
     }
+
+    #[test]
+    fn should_convolute_multiple_channels_with_multiple_filters_correctly() -> () {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+        let image = vec![
+            0.1, 0.5, 0.3,
+            0.7, 0.2, 0.9,
+            0.4, 0.8, 0.6,
+
+            0.2, 0.4, 0.1,
+            0.3, 0.9, 0.5,
+            0.6, 0.7, 0.8,
+        ]
+        .to_buffer(false, &opencl_state)
+        .expect("unable to get image buffer");
+        let filters = vec![
+            0.1, 0.2,
+            0.3, 0.4,
+
+            0.5, 0.6,
+            0.7, 0.8,
+
+
+            0.9, 0.1,
+            0.2, 0.3,
+
+            0.4, 0.5,
+            0.6, 0.7,
+        ]
+        .to_buffer(false, &opencl_state)
+        .expect("unable to get filters buffer");
+        let biases = vec![0.1, 0.2]
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the biases buffer");
+        let expected_result = vec![
+            1.77, 1.92,
+            2.32, 2.66,
+
+            1.63, 2.09,
+            2.59, 2.4,
+        ];
+
+        let mut layer = Conv2D::new_raw((3, 3), 2, (2, 2), 2);
+        layer.init(&opencl_state).expect("unable to init Conv2D");
+        layer.weights_buff = Some(filters);
+        layer.biases_buff = Some(biases);
+
+        let result_buffer = layer
+            .propagate(&image)
+            .expect("unable to propagate conv2d layer");
+        let result = Vec::<f32>::from_buffer(result_buffer, false, &opencl_state)
+            .expect("unable to get resulting convolution buffer");
+
+        assert_approx_equal_distance(&result, &expected_result, 0.01);
+    }
+
+    #[test]
+    fn should_compute_loss_to_input_derivatives_correctly() -> () {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+        let image = vec![0.0; 18]
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get image buffer");
+        let filters = vec![
+            0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8,
+            0.9, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7,
+        ]
+        .to_buffer(false, &opencl_state)
+        .expect("unable to get filters buffer");
+        let loss_to_output_derivatives = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8]
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+        let expected_derivatives = vec![
+            0.46, 0.63, 0.1,
+            0.79, 1.26, 0.42,
+            0.23, 0.61, 0.4,
+
+            0.25, 0.65, 0.42,
+            0.8, 1.98, 1.22,
+            0.63, 1.49, 0.88,
+        ];
+
+        let mut layer = Conv2D::new_raw((3, 3), 2, (2, 2), 2);
+        layer.init(&opencl_state).expect("unable to init Conv2D");
+        layer.weights_buff = Some(filters);
+        layer.last_inputs_buffer = Some(image);
+
+        let derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives)
+            .expect("unable to compute the loss to input derivatives of the conv2d layer");
+        let derivatives = Vec::<f32>::from_buffer(&derivatives_buffer, false, &opencl_state)
+            .expect("unable to get the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(&derivatives, &expected_derivatives, 0.01);
+    }
+
+    #[test]
+    fn should_convolute_with_stride_and_same_padding_correctly() -> () {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+        let image = vec![
+            0.1, 0.5, 0.3, 0.2,
+            0.7, 0.2, 0.9, 0.4,
+            0.4, 0.8, 0.6, 0.3,
+            0.5, 0.1, 0.2, 0.9,
+            0.3, 0.6, 0.7, 0.1,
+        ]
+        .to_buffer(false, &opencl_state)
+        .expect("unable to get image buffer");
+        let filter = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9]
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get filter buffer");
+        let bias = vec![0.1]
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the biases buffer");
+        let expected_result = vec![
+            2.03, 1.27,
+            2.01, 1.52,
+            1.07, 0.63,
+        ];
+
+        let mut layer = Conv2D::new_raw((4, 5), 1, (3, 3), 1)
+            .set_stride((2, 2))
+            .set_padding(Conv2DPadding::Same);
+        assert_eq!(layer.get_outputs_size(), (2, 3));
+        assert_eq!(layer.get_padding_before(), (0, 1));
+
+        layer.init(&opencl_state).expect("unable to init Conv2D");
+        layer.weights_buff = Some(filter);
+        layer.biases_buff = Some(bias);
+
+        let result_buffer = layer
+            .propagate(&image)
+            .expect("unable to propagate conv2d layer");
+        let result = Vec::<f32>::from_buffer(result_buffer, false, &opencl_state)
+            .expect("unable to get resulting convolution buffer");
+
+        assert_approx_equal_distance(&result, &expected_result, 0.01);
+    }
+
+    #[test]
+    fn should_convolute_with_explicit_padding_and_dilation_correctly() -> () {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+        let image = vec![
+            0.1, 0.5, 0.3, 0.2,
+            0.7, 0.2, 0.9, 0.4,
+            0.4, 0.8, 0.6, 0.3,
+            0.5, 0.1, 0.2, 0.9,
+            0.3, 0.6, 0.7, 0.1,
+        ];
+
+        let mut padded_layer = Conv2D::new_raw((4, 5), 1, (3, 3), 1)
+            .set_padding(Conv2DPadding::Explicit((1, 0)));
+        padded_layer.init(&opencl_state).expect("unable to init Conv2D");
+        padded_layer.weights_buff = Some(
+            vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9]
+                .to_buffer(false, &opencl_state)
+                .expect("unable to get filter buffer"),
+        );
+        padded_layer.biases_buff = Some(
+            vec![0.1]
+                .to_buffer(false, &opencl_state)
+                .expect("unable to get the biases buffer"),
+        );
+
+        let padded_result_buffer = padded_layer
+            .propagate(
+                &image
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get image buffer"),
+            )
+            .expect("unable to propagate padded conv2d layer");
+        let padded_result = Vec::<f32>::from_buffer(padded_result_buffer, false, &opencl_state)
+            .expect("unable to get resulting padded convolution buffer");
+
+        assert_approx_equal_distance(
+            &padded_result,
+            &vec![
+                1.78, 2.68, 2.35, 1.39,
+                1.47, 2.01, 2.26, 1.52,
+                1.51, 2.17, 2.14, 1.32,
+            ],
+            0.01,
+        );
+
+        let mut dilated_layer = Conv2D::new_raw((4, 5), 1, (2, 2), 1)
+            .set_dilation((2, 2));
+        dilated_layer.init(&opencl_state).expect("unable to init Conv2D");
+        dilated_layer.weights_buff = Some(
+            vec![0.1, 0.2, 0.3, 0.4]
+                .to_buffer(false, &opencl_state)
+                .expect("unable to get filter buffer"),
+        );
+        dilated_layer.biases_buff = Some(
+            vec![0.0]
+                .to_buffer(false, &opencl_state)
+                .expect("unable to get the biases buffer"),
+        );
+
+        let dilated_result_buffer = dilated_layer
+            .propagate(
+                &image
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get image buffer"),
+            )
+            .expect("unable to propagate dilated conv2d layer");
+        let dilated_result = Vec::<f32>::from_buffer(dilated_result_buffer, false, &opencl_state)
+            .expect("unable to get resulting dilated convolution buffer");
+
+        assert_approx_equal_distance(
+            &dilated_result,
+            &vec![
+                0.43, 0.45,
+                0.48, 0.49,
+                0.53, 0.36,
+            ],
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_compute_gradients_and_input_derivatives_with_stride_and_padding_correctly() -> () {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+        let image = vec![
+            0.1, 0.5, 0.3, 0.2,
+            0.7, 0.2, 0.9, 0.4,
+            0.4, 0.8, 0.6, 0.3,
+            0.5, 0.1, 0.2, 0.9,
+            0.3, 0.6, 0.7, 0.1,
+        ]
+        .to_buffer(false, &opencl_state)
+        .expect("unable to get image buffer");
+        let filter = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9]
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get filter buffer");
+        let loss_to_output_derivatives = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6]
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let expected_weights_gradients = vec![0.94, 0.81, 0.37, 1.0, 0.81, 0.56, 0.48, 0.49, 0.15];
+        let expected_input_derivatives = vec![
+            0.04, 0.05, 0.14, 0.1,
+            0.1, 0.14, 0.36, 0.24,
+            0.12, 0.15, 0.34, 0.2,
+            0.26, 0.34, 0.76, 0.44,
+            0.2, 0.25, 0.54, 0.3,
+        ];
+
+        let mut layer = Conv2D::new_raw((4, 5), 1, (3, 3), 1)
+            .set_stride((2, 2))
+            .set_padding(Conv2DPadding::Same);
+        layer.init(&opencl_state).expect("unable to init Conv2D");
+        layer.weights_buff = Some(filter);
+        layer.last_inputs_buffer = Some(image);
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives)
+            .expect("unable to compute conv2d gradients");
+        let weights_gradients = Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+            .expect("unable to get the weights gradients buffer");
+
+        assert_approx_equal_distance(&weights_gradients, &expected_weights_gradients, 0.01);
+
+        let input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives)
+            .expect("unable to compute the loss to input derivatives of the conv2d layer");
+        let input_derivatives =
+            Vec::<f32>::from_buffer(&input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to get the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(&input_derivatives, &expected_input_derivatives, 0.01);
+    }
+
+    #[test]
+    fn should_not_initialize_when_the_dilated_filter_is_bigger_than_the_inputs() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        // the dilated filter is 5x5 and doesn't fit inside of the 4x4 padded images
+        let mut layer = Conv2D::new_raw((2, 2), 1, (3, 3), 1)
+            .set_dilation((2, 2))
+            .set_padding(Conv2DPadding::Explicit((1, 1)));
+
+        assert_eq!(layer.get_outputs_amount(), 0);
+        assert!(matches!(
+            layer.init(&opencl_state),
+            Err(LayerInitializationError::FilterBiggerThanInputs)
+        ));
+    }
 }
\ No newline at end of file
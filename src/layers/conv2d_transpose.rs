@@ -0,0 +1,764 @@
+//! The module that defines the transposed covolutional layer
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+    types::cl_int,
+};
+use rayon::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    initializers::{ConstantInitializer, GlorotUniformInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+const CONV2D_TRANSPOSE_PROGRAM_NAME: &str = "CONV2D_TRANSPOSE";
+const PROGRAM_SOURCE: &str = include_str!("kernels/conv2d_transpose.cl");
+
+const PROPAGATION_KERNEL_NAME: &str = "propagate";
+const COMPUTE_WEIGHTS_GRADIENTS_KERNEL_NAME: &str = "compute_weights_gradients";
+const COMPUTE_BIASES_GRADIENTS_KERNEL_NAME: &str = "compute_biases_gradients";
+const COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_conv2d_transpose(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        PROPAGATION_KERNEL_NAME.to_string(),
+        COMPUTE_WEIGHTS_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_BIASES_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        CONV2D_TRANSPOSE_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that does the opposite of what a Conv2D does in terms of shape, each pixel of the
+/// inputs is multiplied by the whole filter and the result is summed up into the outputs at the
+/// position of that pixel times the stride, which makes the images bigger.
+///
+/// The size of the outputs is `(inputs_size - 1) * stride + filter_size` for each one of the
+/// axes, so that with a stride of 2 and a filter of 2x2 the images have their size doubled.
+///
+/// The inputs and the outputs are laid out in the same way as with a Conv2D, that is, all of the
+/// pixels of the first channel, then all of the pixels of the second channel and so on, with the
+/// outputs having one channel per filter.
+///
+/// This type of layer is what is usually used to go back to the size of the original images in
+/// convolutional autoencoders and segmentation models.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::Conv2DTranspose;
+/// use intricate::types::ModelLayer;
+///
+/// // this will make a layer that doubles the size of 7x7 images with 8 channels
+/// // into 14x14 images with 4 channels
+/// let my_layer: ModelLayer = Conv2DTranspose::new_raw((7, 7), 8, (2, 2), 4)
+///     .set_stride((2, 2))
+///     .into();
+/// ```
+pub struct Conv2DTranspose<'a> {
+    /// The size of the inputs, width and height respectively.
+    pub inputs_size: (usize, usize),
+    /// The amount of channels that each input image has.
+    pub channels: usize,
+    /// The size of the filter, width and height respectively.
+    pub filter_size: (usize, usize),
+    /// The amount of filters, which is also the amount of channels that the outputs will have.
+    pub filters: usize,
+
+    /// How many pixels apart the inputs are put in the outputs, horizontally and vertically
+    /// respectively.
+    pub stride: (usize, usize),
+
+    /// This is a vec containing the weights of each pixel of the filters in the shape
+    /// `[filters][channels][height][width]`.
+    pub weights: Vec<Vec<Vec<Vec<f32>>>>,
+
+    /// This is a vec containing one bias for each filter.
+    pub biases: Vec<f32>,
+
+    /// The initializer that will be used to generate the initial parameters for the filter's
+    /// weights.
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened filter pixel weights.
+    pub weights_buff: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened filter biases.
+    pub biases_buff: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The buffer that contains the flattened inputs per sample that were last forwad passed into
+    /// this Conv2DTranspose layer.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The buffer that contains the flattened outputs per sample that last came out of a forward
+    /// pass into this Conv2DTranspose layer.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> Conv2DTranspose<'a> {
+    /// Creates a new 2D Transposed Convolutional layer with random filters ready for being used
+    /// in a Model.
+    pub fn new(
+        inputs_size: (usize, usize),
+        channels: usize,
+        filter_size: (usize, usize),
+        filters: usize,
+    ) -> ModelLayer<'a> {
+        Self::new_raw(inputs_size, channels, filter_size, filters).into()
+    }
+
+    /// Crates a new raw 2D Transposed Convolutional layer with random filters.
+    pub fn new_raw(
+        inputs_size: (usize, usize),
+        channels: usize,
+        filter_size: (usize, usize),
+        filters: usize,
+    ) -> Self {
+        let mut initializers = HashMap::with_capacity(2);
+        initializers.insert(
+            "weights".to_string(),
+            GlorotUniformInitializer::new().into(),
+        );
+        initializers.insert("biases".to_string(), ConstantInitializer::new(0.0).into());
+
+        Conv2DTranspose {
+            inputs_size,
+            channels,
+            filter_size,
+            filters,
+            stride: (1, 1),
+            weights: Vec::default(),
+            biases: Vec::default(),
+            initializers,
+            weights_buff: None,
+            biases_buff: None,
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+            opencl_state: None,
+        }
+    }
+
+    /// Sets how many pixels apart the inputs are put in the outputs, horizontally and vertically
+    /// respectively, and returns the mutated Self.
+    pub fn set_stride(mut self, stride: (usize, usize)) -> Self {
+        assert!(
+            stride.0 > 0 && stride.1 > 0,
+            "the stride of a Conv2DTranspose must be at least 1"
+        );
+        self.stride = stride;
+
+        self
+    }
+
+    /// Gets the size of each one of the images that come out of the transposed convolution,
+    /// width and height respectively.
+    pub fn get_outputs_size(&self) -> (usize, usize) {
+        (
+            (self.inputs_size.0 - 1) * self.stride.0 + self.filter_size.0,
+            (self.inputs_size.1 - 1) * self.stride.1 + self.filter_size.1,
+        )
+    }
+}
+
+impl<'a> Layer<'a> for Conv2DTranspose<'a> {
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "weights" => Some(
+                self.weights
+                    .par_iter()
+                    .flatten()
+                    .flatten()
+                    .flatten()
+                    .map(|x| *x)
+                    .collect(),
+            ),
+            "biases" => Some(self.biases.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_size.0 * self.inputs_size.1 * self.channels
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        outputs_width * outputs_height * self.filters
+    }
+
+    fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+        Some(vec![self.channels, self.inputs_size.1, self.inputs_size.0])
+    }
+
+    fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        Some(vec![self.filters, outputs_height, outputs_width])
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.weights_buff.is_some() {
+            drop(self.weights_buff.as_ref().unwrap());
+        }
+
+        if self.biases_buff.is_some() {
+            drop(self.biases_buff.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.weights_buff.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "weights".to_string(),
+            });
+        }
+
+        if self.biases_buff.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "biases".to_string(),
+            });
+        }
+
+        let weights = Vec::<f32>::from_buffer(self.weights_buff.as_ref().unwrap(), false, state)?;
+
+        let filter_volume = self.filter_size.0 * self.filter_size.1;
+
+        self.weights = weights
+            .par_chunks(self.channels * filter_volume)
+            .map(|filter| {
+                filter
+                    .chunks(filter_volume)
+                    .map(|channel| {
+                        channel
+                            .chunks(self.filter_size.0)
+                            .map(|row| row.to_vec())
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.biases = Vec::<f32>::from_buffer(self.biases_buff.as_ref().unwrap(), false, state)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.weights.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("weights") {
+                self.weights = initializer.initialize_4d(
+                    (
+                        self.filters,
+                        self.channels,
+                        self.filter_size.1,
+                        self.filter_size.0,
+                    ),
+                    self,
+                );
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "weights",
+                ));
+            }
+        }
+
+        if self.biases.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("biases") {
+                self.biases = initializer.initialize_1d(self.filters, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "biases",
+                ));
+            }
+        }
+
+        self.weights_buff = Some(
+            self.weights
+                .par_iter()
+                .flatten()
+                .flatten()
+                .flatten()
+                .map(|x| *x)
+                .collect::<Vec<f32>>()
+                .to_buffer(false, opencl_state)?,
+        );
+
+        self.biases_buff = Some(self.biases.to_buffer(false, opencl_state)?);
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+
+        let outputs = empty_buffer(
+            self.get_outputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(CONV2D_TRANSPOSE_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(PROPAGATION_KERNEL_NAME)?;
+
+        ExecuteKernel::new(kernel)
+            .set_arg(inputs)
+            .set_arg(self.weights_buff.as_ref().unwrap())
+            .set_arg(self.biases_buff.as_ref().unwrap())
+            .set_arg(&outputs)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_height as cl_int))
+            .set_arg(&(self.filter_size.0 as cl_int))
+            .set_arg(&(self.filter_size.1 as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_outputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_outputs_buffer = Some(outputs);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivatives: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        if self.last_inputs_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivatives.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        let filter_volume = self.filter_size.0 * self.filter_size.1;
+
+        let program = state.get_prgm(CONV2D_TRANSPOSE_PROGRAM_NAME)?;
+
+        let weights_gradients = empty_buffer(
+            self.filters * self.channels * filter_volume,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_WEIGHTS_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&weights_gradients)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_height as cl_int))
+            .set_arg(&(self.filter_size.0 as cl_int))
+            .set_arg(&(self.filter_size.1 as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[self.filters, self.channels, filter_volume])
+            .enqueue_nd_range(queue)?;
+
+        let biases_gradients = empty_buffer(self.filters, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_BIASES_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&biases_gradients)
+            .set_arg(&((outputs_width * outputs_height) as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_size(self.filters)
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(vec![
+            Gradient {
+                optimizable: true,
+                parameter_id: "weights".to_string(),
+                value: weights_gradients,
+            },
+            Gradient {
+                optimizable: true,
+                parameter_id: "biases".to_string(),
+                value: biases_gradients,
+            },
+        ])
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.weights_buff.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "weights".to_string(),
+            ));
+        }
+
+        if self.biases_buff.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "biases".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.weights_buff.as_mut().unwrap(),
+            "weights".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        optimizer.optimize_parameters(
+            self.biases_buff.as_mut().unwrap(),
+            "biases".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_model_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != 2 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_model_index,
+            timestep,
+            state,
+        )?;
+
+        let weights_buff = self.weights_buff.as_mut().unwrap();
+        weights_buff.subtract_inplc(&update_vectors[0], state)?;
+        let biases_buff = self.biases_buff.as_mut().unwrap();
+        biases_buff.subtract_inplc(&update_vectors[1], state)?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        if self.weights_buff.is_none() {
+            return Err(LayerLossToInputDifferentiationError::MissingParameter(
+                "weights",
+            ));
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+
+        let loss_to_input_derivatives_buffer = empty_buffer(
+            self.get_inputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(CONV2D_TRANSPOSE_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME)?;
+
+        ExecuteKernel::new(kernel)
+            .set_arg(self.weights_buff.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivative)
+            .set_arg(&loss_to_input_derivatives_buffer)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_height as cl_int))
+            .set_arg(&(self.filter_size.0 as cl_int))
+            .set_arg(&(self.filter_size.1 as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod conv2d_transpose_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::Conv2DTranspose;
+
+    #[test]
+    fn should_propagate_and_back_propagate_with_stride_correctly() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5,
+            0.7, 0.2,
+
+            0.4, 0.8,
+            0.6, 0.3,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.1, 0.2, 0.3, 0.4, 0.5,
+            0.6, 0.7, 0.8, 0.9, 1.0,
+            0.2, 0.4, 0.6, 0.8, 1.0,
+            0.3, 0.1, 0.5, 0.2, 0.4,
+            0.9, 0.7, 0.5, 0.3, 0.1,
+        ];
+
+        let expected_outputs = vec![
+            0.19, 0.16, 0.46, 0.28, 0.49,
+            0.26, 0.23, 0.64, 0.51, 0.48,
+            0.56, 0.54, 1.49, 0.89, 0.86,
+            0.56, 0.57, 0.75, 0.26, 0.25,
+            0.89, 0.9, 1.14, 0.38, 0.34,
+        ];
+        let expected_weights_gradients = vec![
+            0.42, 0.66, 0.9,
+            0.77, 0.63, 1.01,
+            1.05, 0.99, 0.93,
+
+            0.58, 0.88, 1.18,
+            1.21, 1.12, 1.54,
+            1.25, 1.31, 1.37,
+        ];
+        let expected_biases_gradients = vec![12.5];
+        let expected_loss_to_input_derivatives = vec![
+            2.21, 3.59,
+            2.39, 1.74,
+
+            0.91, 1.59,
+            1.25, 1.12,
+        ];
+
+        let mut layer = Conv2DTranspose::new_raw((2, 2), 2, (3, 3), 1).set_stride((2, 2));
+        assert_eq!(layer.get_outputs_size(), (5, 5));
+
+        layer.weights = vec![vec![
+            vec![vec![0.1, 0.2, 0.3], vec![0.4, 0.5, 0.6], vec![0.7, 0.8, 0.9]],
+            vec![vec![0.2, 0.1, 0.3], vec![0.3, 0.2, 0.1], vec![0.5, 0.4, 0.2]],
+        ]];
+        layer.biases = vec![0.1];
+        layer.init(&opencl_state).expect("unable to init Conv2DTranspose");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the Conv2DTranspose");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the Conv2DTranspose");
+        let weights_gradients = Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+            .expect("unable to read the weights gradients buffer");
+        let biases_gradients = Vec::<f32>::from_buffer(&gradients[1].value, false, &opencl_state)
+            .expect("unable to read the biases gradients buffer");
+
+        assert_approx_equal_distance(&weights_gradients, &expected_weights_gradients, 0.01);
+        assert_approx_equal_distance(&biases_gradients, &expected_biases_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the Conv2DTranspose");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
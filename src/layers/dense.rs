@@ -28,17 +28,17 @@ use super::{
     initializers::{Initializer, InitializerTrait, GlorotUniformInitializer, ConstantInitializer},
 };
 
-const DENSE_PROP_PROGRAM_NAME: &str = "DENSE_PROPAGATION";
-const DENSE_BACKPROP_PROGRAM_NAME: &str = "DENSE_BACKPROPAGATION";
+pub(crate) const DENSE_PROP_PROGRAM_NAME: &str = "DENSE_PROPAGATION";
+pub(crate) const DENSE_BACKPROP_PROGRAM_NAME: &str = "DENSE_BACKPROPAGATION";
 
 const PROPAGATION_PROGRAM_SORUCE: &str = include_str!("kernels/dense_propagation.cl");
 const BACK_PROPAGATION_PROGRAM_SOURCE: &str = include_str!("kernels/dense_back_propagation.cl");
 
-const PROPAGATION_KERNEL_NAME: &str = "dense_propagate";
+pub(crate) const PROPAGATION_KERNEL_NAME: &str = "dense_propagate";
 
-const WEIGHTS_GRADIENT_COMPUTATION_KERNEL_NAME: &str = "weights_gradient_calculation";
-const BIAS_GRADIENT_COMPUTATION_KERNEL_NAME: &str = "bias_gradient_calculation";
-const LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME: &str =
+pub(crate) const WEIGHTS_GRADIENT_COMPUTATION_KERNEL_NAME: &str = "weights_gradient_calculation";
+pub(crate) const BIAS_GRADIENT_COMPUTATION_KERNEL_NAME: &str = "bias_gradient_calculation";
+pub(crate) const LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME: &str =
     "compute_loss_derivative_with_respect_to_inputs";
 
 pub(crate) fn compile_dense(
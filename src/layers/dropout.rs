@@ -0,0 +1,358 @@
+//! The module that defines the Dropout layer.
+
+use std::mem;
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+    types::cl_uint,
+};
+use rand::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{empty_buffer, ensure_program, BufferOperations, EnsureKernelsAndProgramError},
+        OpenCLState,
+    },
+};
+
+use super::{
+    initializers::Initializer, Gradient, Layer, LayerGradientApplicationError,
+    LayerGradientComputationError, LayerInitializationError,
+    LayerLossToInputDifferentiationError, LayerPropagationError, ParametersOptimizationError,
+};
+
+const DROPOUT_PROGRAM_NAME: &str = "DROPOUT";
+const PROGRAM_SOURCE: &str = include_str!("kernels/dropout.cl");
+
+const GENERATE_MASK_KERNEL_NAME: &str = "generate_mask";
+
+pub(crate) fn compile_dropout(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[GENERATE_MASK_KERNEL_NAME.to_string()];
+
+    ensure_program(
+        opencl_state,
+        DROPOUT_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that randomly sets some of its inputs to zero while the Model is being trained, which
+/// helps a lot against overfitting since the next layers can't rely too much on any specific
+/// input.
+///
+/// The inputs that are kept are scaled by `1 / (1 - rate)` so that when the Model is predicting,
+/// and this layer just passes its inputs forward, the outputs are of the same magnitude.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::Dropout;
+///
+/// // this will make a dropout layer that drops 20% of its 100 inputs while training
+/// let my_layer: Dropout = Dropout::new_raw(100, 0.2);
+/// ```
+pub struct Dropout<'a> {
+    /// The amount of inputs this instance of Dropout expects.
+    pub inputs_amount: usize,
+    /// The probability of each one of the inputs being set to zero while training.
+    pub rate: f32,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// Weather or not the Model this layer is in is currently being trained.
+    pub is_training: bool,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this Dropout.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this Dropout.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The mask that was multiplied by the inputs in the last forward pass while training.
+    pub last_mask_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> Dropout<'a> {
+    /// Creates a raw version of the Dropout layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(inputs_amount: usize, rate: f32) -> Dropout<'a> {
+        assert!(
+            (0.0..1.0).contains(&rate),
+            "the rate of a Dropout must be in the range [0, 1)"
+        );
+
+        Dropout {
+            inputs_amount,
+            rate,
+
+            is_training: false,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+            last_mask_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the Dropout layer, to be used with a Model.
+    pub fn new(inputs_amount: usize, rate: f32) -> ModelLayer<'a> {
+        Self::new_raw(inputs_amount, rate).into()
+    }
+}
+
+impl<'a> Layer<'a> for Dropout<'a> {
+    fn get_flattened_parameter_data(&self, _parameter: &str) -> Option<Vec<f32>> {
+        None
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, _parameter: &str) -> Option<&'b Initializer> {
+        None
+    }
+
+    fn set_initializer_for_parameter(
+        self,
+        _initializer: Initializer,
+        _parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.into()
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_mask_buffer.is_some() {
+            drop(self.last_mask_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        Ok(())
+    }
+
+    fn set_training_mode(&mut self, is_training: bool) -> () {
+        self.is_training = is_training;
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.inputs_amount != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        if !self.is_training {
+            self.last_mask_buffer = None;
+            self.last_outputs_buffer = Some(inputs.clone(state)?);
+
+            return Ok(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
+        let mask_buffer = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(DROPOUT_PROGRAM_NAME)?;
+        let generate_mask_kernel = program.get_krnl(GENERATE_MASK_KERNEL_NAME)?;
+
+        let seed: cl_uint = thread_rng().gen();
+
+        ExecuteKernel::new(generate_mask_kernel)
+            .set_arg(&mask_buffer)
+            .set_arg(&(self.rate as cl_float))
+            .set_arg(&seed)
+            .set_arg(&(inputs_total_count as cl_int))
+            .set_global_work_size(inputs_total_count)
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_outputs_buffer = Some(inputs.multiply(&mask_buffer, state)?);
+        self.last_mask_buffer = Some(mask_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        _per_parameter_type_gradients: &[Gradient],
+        _optimizer: &mut dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        Ok(())
+    }
+
+    fn compute_gradients(
+        &self,
+        _layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        Ok(Vec::default())
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        _optimizer: &dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.inputs_amount != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        // the outputs are just the inputs when not training
+        if let Some(mask_buffer) = &self.last_mask_buffer {
+            Ok(layer_output_to_error_derivative.multiply(mask_buffer, state)?)
+        } else {
+            Ok(layer_output_to_error_derivative.clone(state)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod dropout_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::Dropout;
+
+    #[test]
+    fn should_drop_inputs_only_while_training() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs_amount = 1000;
+        let inputs = vec![1.0; inputs_amount * 2];
+        let loss_to_output_derivatives = vec![1.0; inputs_amount * 2];
+
+        let mut layer = Dropout::new_raw(inputs_amount, 0.5);
+        layer.init(&opencl_state).expect("unable to init Dropout");
+
+        let inputs_buffer = inputs
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the inputs buffer");
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let outputs_buffer = layer
+            .propagate(&inputs_buffer)
+            .expect("unable to propagate the Dropout while predicting");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &inputs, 0.0001);
+
+        layer.set_training_mode(true);
+
+        let outputs_buffer = layer
+            .propagate(&inputs_buffer)
+            .expect("unable to propagate the Dropout while training");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert!(outputs.iter().all(|x| *x == 0.0 || (x - 2.0).abs() < 0.0001));
+
+        let dropped_amount = outputs.iter().filter(|x| **x == 0.0).count();
+        assert!(dropped_amount > inputs_amount * 2 / 3 && dropped_amount < inputs_amount * 4 / 3);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the Dropout");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(&loss_to_input_derivatives, &outputs, 0.0001);
+    }
+}
@@ -0,0 +1,789 @@
+//! The module that defines the Embedding layer.
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use rayon::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferConversionError, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    initializers::{Initializer, InitializerTrait, UniformRandomInitializer},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+const EMBEDDING_PROGRAM_NAME: &str = "EMBEDDING";
+const PROGRAM_SOURCE: &str = include_str!("kernels/embedding.cl");
+
+const PROPAGATE_KERNEL_NAME: &str = "propagate";
+const COMPUTE_EMBEDDINGS_GRADIENTS_KERNEL_NAME: &str = "compute_embeddings_gradients";
+const APPLY_UPDATES_TO_USED_EMBEDDINGS_KERNEL_NAME: &str = "apply_updates_to_used_embeddings";
+const COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_embedding(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        PROPAGATE_KERNEL_NAME.to_string(),
+        COMPUTE_EMBEDDINGS_GRADIENTS_KERNEL_NAME.to_string(),
+        APPLY_UPDATES_TO_USED_EMBEDDINGS_KERNEL_NAME.to_string(),
+        COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        EMBEDDING_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that turns sequences of indices of tokens of a vocabulary, such as words or
+/// categories, into sequences of trainable vectors, one for each token in the vocabulary, which
+/// is much cheaper than one-hot encoding the tokens and then sending them into a Dense layer.
+///
+/// The inputs are the indices sent in as floats, which can be made from lists of tokens with
+/// `intricate::datasets::vocabulary::Vocabulary`, and the outputs are the embeddings of each
+/// token one after the other. The indices that are not inside of the vocabulary just look up
+/// zeros.
+///
+/// When applying gradients only the embeddings of the tokens that were in the last inputs are
+/// changed, which are only kept track of while in training mode. Optimizers that change the
+/// parameters before each step, such as the lookahead of the Nesterov optimizer, still go through
+/// all of the embeddings.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::Embedding;
+///
+/// // this will make a layer that takes sequences of 20 tokens out of a vocabulary of 1000 tokens
+/// // and gives out 20 vectors of 16 numbers
+/// let my_layer: Embedding = Embedding::new_raw(20, 1000, 16);
+/// ```
+pub struct Embedding<'a> {
+    /// The amount of indices in each one of the input sequences.
+    pub sequence_length: usize,
+    /// The amount of tokens in the vocabulary, the indices go from 0 up to this.
+    pub vocabulary_size: usize,
+    /// The amount of numbers in the vector of each token.
+    pub embedding_size: usize,
+
+    /// The vector of each token of the vocabulary, in the shape
+    /// `[vocabulary_size][embedding_size]`.
+    pub embeddings: Vec<Vec<f32>>,
+
+    /// The initializer that will be used to generate the initial embeddings, which is a uniform
+    /// distribution between -0.05 and 0.05 by default.
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened embeddings.
+    pub embeddings_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// Weather or not the Model this layer is in is currently being trained.
+    pub is_training: bool,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned indices last forward passed into this Embedding.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The embeddings that were looked up in the last forward pass into this Embedding.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    last_used_embeddings: Option<UsedEmbeddingsBuffers>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+// the embeddings that some inputs looked up, with the positions of the tokens grouped together by
+// the embedding that each one of them looked up, so that the kernels don't need to go through all
+// of the tokens for each one of the embeddings
+#[derive(Debug, PartialEq)]
+struct UsedEmbeddings {
+    // where the tokens of each embedding of the vocabulary start in the grouped token indices,
+    // with one more offset at the end for where the tokens of the last embedding end
+    tokens_offsets: Vec<cl_int>,
+    grouped_token_indices: Vec<cl_int>,
+    vocabulary_indices: Vec<cl_int>,
+}
+
+// the used embeddings of the last inputs while training, which are grouped once when propagating
+// instead of in each one of the methods that need them
+#[derive(Debug)]
+struct UsedEmbeddingsBuffers {
+    tokens_offsets: Buffer<cl_int>,
+    grouped_token_indices: Buffer<cl_int>,
+    vocabulary_indices: Buffer<cl_int>,
+    used_embeddings_amount: usize,
+}
+
+impl UsedEmbeddingsBuffers {
+    fn new(
+        used_embeddings: UsedEmbeddings,
+        state: &OpenCLState,
+    ) -> Result<UsedEmbeddingsBuffers, BufferConversionError> {
+        let used_embeddings_amount = used_embeddings.vocabulary_indices.len();
+
+        Ok(UsedEmbeddingsBuffers {
+            tokens_offsets: used_embeddings.tokens_offsets.to_buffer(false, state)?,
+            grouped_token_indices: non_empty(used_embeddings.grouped_token_indices)
+                .to_buffer(false, state)?,
+            vocabulary_indices: non_empty(used_embeddings.vocabulary_indices)
+                .to_buffer(false, state)?,
+            used_embeddings_amount,
+        })
+    }
+}
+
+// OpenCL can't allocate empty buffers, which happens when none of the tokens are in the
+// vocabulary, but then the kernels don't read anything from them
+fn non_empty(mut values: Vec<cl_int>) -> Vec<cl_int> {
+    if values.is_empty() {
+        values.push(0);
+    }
+
+    values
+}
+
+fn group_tokens_by_embedding(indices: &[f32], vocabulary_size: usize) -> UsedEmbeddings {
+    // the same as in the kernels, the indices that are not in the vocabulary don't look up anything
+    let tokens_vocabulary_indices: Vec<Option<usize>> = indices
+        .iter()
+        .map(|index| {
+            let index = index.round();
+
+            if index >= 0.0 && index < vocabulary_size as f32 {
+                Some(index as usize)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let mut tokens_amounts = vec![0; vocabulary_size];
+    for vocabulary_index in tokens_vocabulary_indices.iter().flatten() {
+        tokens_amounts[*vocabulary_index] += 1;
+    }
+
+    let mut tokens_offsets = Vec::with_capacity(vocabulary_size + 1);
+    tokens_offsets.push(0);
+    for tokens_amount in tokens_amounts.iter() {
+        tokens_offsets.push(tokens_offsets.last().unwrap() + tokens_amount);
+    }
+
+    let mut next_positions = tokens_offsets.clone();
+    let mut grouped_token_indices = vec![0; *tokens_offsets.last().unwrap()];
+    for (token_index, vocabulary_index) in tokens_vocabulary_indices.iter().enumerate() {
+        if let Some(vocabulary_index) = vocabulary_index {
+            grouped_token_indices[next_positions[*vocabulary_index]] = token_index as cl_int;
+            next_positions[*vocabulary_index] += 1;
+        }
+    }
+
+    UsedEmbeddings {
+        tokens_offsets: tokens_offsets
+            .iter()
+            .map(|offset| *offset as cl_int)
+            .collect(),
+        grouped_token_indices,
+        vocabulary_indices: tokens_amounts
+            .iter()
+            .enumerate()
+            .filter(|(_, tokens_amount)| **tokens_amount > 0)
+            .map(|(vocabulary_index, _)| vocabulary_index as cl_int)
+            .collect(),
+    }
+}
+
+impl<'a> Embedding<'a> {
+    /// Creates a new Embedding layer with random embeddings ready for being used in a Model.
+    pub fn new(
+        sequence_length: usize,
+        vocabulary_size: usize,
+        embedding_size: usize,
+    ) -> ModelLayer<'a> {
+        Self::new_raw(sequence_length, vocabulary_size, embedding_size).into()
+    }
+
+    /// Crates a new raw Embedding layer with random embeddings.
+    pub fn new_raw(sequence_length: usize, vocabulary_size: usize, embedding_size: usize) -> Self {
+        let mut initializers = HashMap::with_capacity(1);
+        initializers.insert(
+            "embeddings".to_string(),
+            UniformRandomInitializer::new(-0.05..0.05).into(),
+        );
+
+        Embedding {
+            sequence_length,
+            vocabulary_size,
+            embedding_size,
+            embeddings: Vec::default(),
+            initializers,
+            embeddings_buffer: None,
+            is_training: false,
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+            last_used_embeddings: None,
+            opencl_state: None,
+        }
+    }
+}
+
+impl<'a> Layer<'a> for Embedding<'a> {
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "embeddings" => Some(self.embeddings.par_iter().flatten().map(|x| *x).collect()),
+            _ => None,
+        }
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.sequence_length
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.sequence_length * self.embedding_size
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.embeddings_buffer.is_some() {
+            drop(self.embeddings_buffer.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_used_embeddings.is_some() {
+            drop(self.last_used_embeddings.as_ref().unwrap());
+        }
+    }
+
+    fn set_training_mode(&mut self, is_training: bool) -> () {
+        self.is_training = is_training;
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.embeddings_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "embeddings".to_string(),
+            });
+        }
+
+        let embeddings =
+            Vec::<f32>::from_buffer(self.embeddings_buffer.as_ref().unwrap(), false, state)?;
+
+        self.embeddings = embeddings
+            .par_chunks(self.embedding_size)
+            .map(|embedding| embedding.to_vec())
+            .collect();
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.embeddings.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("embeddings") {
+                self.embeddings =
+                    initializer.initialize_2d((self.vocabulary_size, self.embedding_size), self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "embeddings",
+                ));
+            }
+        }
+
+        self.embeddings_buffer = Some(
+            self.embeddings
+                .par_iter()
+                .flatten()
+                .map(|x| *x)
+                .collect::<Vec<f32>>()
+                .to_buffer(false, opencl_state)?,
+        );
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let indices_amount = inputs_size / mem::size_of::<cl_float>();
+
+        if indices_amount % self.sequence_length != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let outputs = empty_buffer(
+            indices_amount * self.embedding_size,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(EMBEDDING_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(PROPAGATE_KERNEL_NAME)?)
+            .set_arg(inputs)
+            .set_arg(self.embeddings_buffer.as_ref().unwrap())
+            .set_arg(&outputs)
+            .set_arg(&(self.vocabulary_size as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(indices_amount as cl_int))
+            .set_global_work_sizes(&[indices_amount, self.embedding_size])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_outputs_buffer = Some(outputs);
+
+        self.last_used_embeddings = if self.is_training {
+            let indices = Vec::<f32>::from_buffer(inputs, false, state)?;
+
+            Some(UsedEmbeddingsBuffers::new(
+                group_tokens_by_embedding(&indices, self.vocabulary_size),
+                state,
+            )?)
+        } else {
+            None
+        };
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivatives: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        if self.last_used_embeddings.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivatives.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let used_embeddings = self.last_used_embeddings.as_ref().unwrap();
+
+        // the gradients are still for the whole vocabulary since the optimizers keep their state
+        // with the same shape as the parameters
+        let embeddings_gradients = empty_buffer(
+            self.vocabulary_size * self.embedding_size,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(EMBEDDING_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_EMBEDDINGS_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(&used_embeddings.tokens_offsets)
+            .set_arg(&used_embeddings.grouped_token_indices)
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&embeddings_gradients)
+            .set_arg(&(self.vocabulary_size as cl_int))
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[self.vocabulary_size, self.embedding_size])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(vec![Gradient {
+            optimizable: true,
+            parameter_id: "embeddings".to_string(),
+            value: embeddings_gradients,
+        }])
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.embeddings_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "embeddings".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.embeddings_buffer.as_mut().unwrap(),
+            "embeddings".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_model_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientApplicationError::NoCommandQueueFound);
+        }
+
+        if per_parameter_type_gradients.len() != 1 || self.last_used_embeddings.is_none() {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_model_index,
+            timestep,
+            state,
+        )?;
+
+        let used_embeddings = self.last_used_embeddings.as_ref().unwrap();
+        let used_embeddings_amount = used_embeddings.used_embeddings_amount;
+
+        if used_embeddings_amount == 0 {
+            return Ok(());
+        }
+
+        let program = state.get_prgm(EMBEDDING_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(APPLY_UPDATES_TO_USED_EMBEDDINGS_KERNEL_NAME)?)
+            .set_arg(&used_embeddings.vocabulary_indices)
+            .set_arg(&update_vectors[0])
+            .set_arg(self.embeddings_buffer.as_ref().unwrap())
+            .set_arg(&(self.embedding_size as cl_int))
+            .set_arg(&(used_embeddings_amount as cl_int))
+            .set_global_work_sizes(&[used_embeddings_amount, self.embedding_size])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+        let indices_amount = samples_amount * self.sequence_length;
+
+        let loss_to_input_derivatives_buffer =
+            empty_buffer(indices_amount, CL_MEM_READ_WRITE, state)?;
+
+        let program = state.get_prgm(EMBEDDING_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME)?)
+            .set_arg(&loss_to_input_derivatives_buffer)
+            .set_arg(&(indices_amount as cl_int))
+            .set_global_work_size(indices_amount)
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod embedding_tests {
+    use crate::{
+        layers::Layer,
+        optimizers::{Basic, Momentum, Optimizer},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::{group_tokens_by_embedding, Embedding, UsedEmbeddings};
+
+    #[test]
+    fn should_look_up_and_update_only_the_used_embeddings() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        // the 5 is outside of the vocabulary so it just looks up zeros
+        let inputs = vec![
+            1.0, 3.0, 1.0,
+            3.0, 5.0, 1.0,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.1, 0.2, 0.3, 0.4, 0.5, 0.6,
+            0.7, 0.8, 0.9, 1.0, 1.1, 1.2,
+        ];
+
+        let expected_outputs = vec![
+            0.3, 0.4, 0.7, 0.8, 0.3, 0.4,
+            0.7, 0.8, 0.0, 0.0, 0.3, 0.4,
+        ];
+        let expected_embeddings_gradients = vec![
+            0.0, 0.0,
+            0.85, 1.0,
+            0.0, 0.0,
+            0.5, 0.6,
+        ];
+        // with a learning rate of 1 the used embeddings just go down by their gradients
+        let expected_embeddings = vec![
+            0.1, 0.2,
+            -0.55, -0.6,
+            0.5, 0.6,
+            0.2, 0.2,
+        ];
+
+        let mut layer = Embedding::new_raw(3, 4, 2);
+        layer.embeddings = vec![
+            vec![0.1, 0.2],
+            vec![0.3, 0.4],
+            vec![0.5, 0.6],
+            vec![0.7, 0.8],
+        ];
+        layer.init(&opencl_state).expect("unable to init Embedding");
+        layer.set_training_mode(true);
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the Embedding");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let gradients = layer
+            .compute_gradients(
+                &loss_to_output_derivatives
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the loss to output derivatives buffer"),
+            )
+            .expect("unable to compute the gradients of the Embedding");
+        let embeddings_gradients =
+            Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+                .expect("unable to read the embeddings gradients buffer");
+
+        assert_approx_equal_distance(&embeddings_gradients, &expected_embeddings_gradients, 0.01);
+
+        let mut optimizer = Basic::new(1.0);
+        optimizer.init(&opencl_state).expect("unable to init the optimizer");
+
+        layer
+            .apply_gradients(gradients.as_slice(), &mut optimizer, 0, 1)
+            .expect("unable to apply the gradients of the Embedding");
+        layer
+            .sync_data_from_buffers_to_host()
+            .expect("unable to sync the embeddings");
+
+        assert_approx_equal_distance(
+            &layer.embeddings.iter().flatten().map(|x| *x).collect(),
+            &expected_embeddings,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_group_the_tokens_by_the_embedding_they_look_up() {
+        let indices = vec![1.0, 3.0, 1.0, 3.0, 5.0, 1.0];
+
+        assert_eq!(
+            group_tokens_by_embedding(&indices, 4),
+            UsedEmbeddings {
+                tokens_offsets: vec![0, 0, 3, 3, 5],
+                grouped_token_indices: vec![0, 2, 5, 1, 3],
+                vocabulary_indices: vec![1, 3],
+            }
+        );
+    }
+
+    #[test]
+    fn should_only_update_the_used_embeddings_with_momentum() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let mut layer = Embedding::new_raw(1, 4, 2);
+        layer.embeddings = vec![
+            vec![0.1, 0.2],
+            vec![0.3, 0.4],
+            vec![0.5, 0.6],
+            vec![0.7, 0.8],
+        ];
+        layer.init(&opencl_state).expect("unable to init Embedding");
+
+        layer.set_training_mode(true);
+
+        let mut optimizer = Momentum::new(1.0, 0.5);
+        optimizer.init(&opencl_state).expect("unable to init the optimizer");
+
+        // the first step looks up the token 1 and the second one the token 2, so the update
+        // vector of the second step still has momentum for the token 1
+        let steps = vec![(1.0, vec![0.1, 0.2]), (2.0, vec![0.3, 0.4])];
+        for (timestep, (index, loss_to_output_derivatives)) in steps.iter().enumerate() {
+            layer
+                .propagate(
+                    &vec![*index]
+                        .to_buffer(false, &opencl_state)
+                        .expect("unable to get the inputs buffer"),
+                )
+                .expect("unable to propagate the Embedding");
+            let gradients = layer
+                .compute_gradients(
+                    &loss_to_output_derivatives
+                        .to_buffer(false, &opencl_state)
+                        .expect("unable to get the loss to output derivatives buffer"),
+                )
+                .expect("unable to compute the gradients of the Embedding");
+            layer
+                .apply_gradients(gradients.as_slice(), &mut optimizer, 0, timestep)
+                .expect("unable to apply the gradients of the Embedding");
+        }
+
+        layer
+            .sync_data_from_buffers_to_host()
+            .expect("unable to sync the embeddings");
+
+        // the token 1 only moved in the first step
+        let expected_embeddings = vec![
+            0.1, 0.2,
+            0.2, 0.2,
+            0.2, 0.2,
+            0.7, 0.8,
+        ];
+
+        assert_approx_equal_distance(
+            &layer.embeddings.iter().flatten().map(|x| *x).collect(),
+            &expected_embeddings,
+            0.01,
+        );
+    }
+}
@@ -0,0 +1,744 @@
+//! The module that defines the GroupNorm layer, as well as the normalization in groups that is
+//! shared with the LayerNorm.
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    initializers::{ConstantInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+const GROUP_NORM_PROGRAM_NAME: &str = "GROUP_NORM";
+const PROGRAM_SOURCE: &str = include_str!("kernels/group_norm.cl");
+
+const COMPUTE_GROUP_STATISTICS_KERNEL_NAME: &str = "compute_group_statistics";
+const NORMALIZE_KERNEL_NAME: &str = "normalize";
+const COMPUTE_GRADIENTS_KERNEL_NAME: &str = "compute_gradients";
+const LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_group_norm(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        COMPUTE_GROUP_STATISTICS_KERNEL_NAME.to_string(),
+        NORMALIZE_KERNEL_NAME.to_string(),
+        COMPUTE_GRADIENTS_KERNEL_NAME.to_string(),
+        LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        GROUP_NORM_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Describes how the inputs of each sample are separated into groups that are normalized
+/// together and how many contiguous inputs share the same gamma and beta.
+pub(crate) struct GroupNormalizationShape {
+    pub(crate) inputs_amount: usize,
+    pub(crate) groups_amount: usize,
+    pub(crate) parameter_volume: usize,
+}
+
+impl GroupNormalizationShape {
+    fn group_volume(&self) -> usize {
+        self.inputs_amount / self.groups_amount
+    }
+
+    fn parameters_amount(&self) -> usize {
+        self.inputs_amount / self.parameter_volume
+    }
+}
+
+/// Normalizes the inputs in groups and then scales and shifts them with gamma and beta,
+/// returning the normalized inputs, the variances of each group and the outputs respectively.
+pub(crate) fn propagate_in_groups(
+    inputs: &Buffer<cl_float>,
+    gamma_buffer: &Buffer<cl_float>,
+    beta_buffer: &Buffer<cl_float>,
+    epsilon: f32,
+    shape: GroupNormalizationShape,
+    state: &OpenCLState,
+) -> Result<(Buffer<cl_float>, Buffer<cl_float>, Buffer<cl_float>), LayerPropagationError> {
+    if state.queues.is_empty() {
+        return Err(LayerPropagationError::NoCommandQueueFound);
+    }
+
+    let queue = state.queues.first().unwrap();
+
+    let inputs_total_count = inputs.size()? / mem::size_of::<cl_float>();
+
+    if inputs_total_count % shape.inputs_amount != 0 {
+        return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+    }
+
+    let samples_amount = inputs_total_count / shape.inputs_amount;
+
+    let means_buffer = empty_buffer(
+        samples_amount * shape.groups_amount,
+        CL_MEM_READ_WRITE,
+        state,
+    )?;
+    let variances_buffer = empty_buffer(
+        samples_amount * shape.groups_amount,
+        CL_MEM_READ_WRITE,
+        state,
+    )?;
+
+    let program = state.get_prgm(GROUP_NORM_PROGRAM_NAME)?;
+    let statistics_kernel = program.get_krnl(COMPUTE_GROUP_STATISTICS_KERNEL_NAME)?;
+
+    ExecuteKernel::new(statistics_kernel)
+        .set_arg(inputs)
+        .set_arg(&means_buffer)
+        .set_arg(&variances_buffer)
+        .set_arg(&(shape.group_volume() as cl_int))
+        .set_arg(&(shape.groups_amount as cl_int))
+        .set_arg(&(samples_amount as cl_int))
+        .set_global_work_sizes(&[samples_amount, shape.groups_amount])
+        .enqueue_nd_range(queue)?;
+
+    queue.finish()?;
+
+    let normalized_inputs_buffer = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+    let outputs_buffer = empty_buffer(inputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+    let normalize_kernel = program.get_krnl(NORMALIZE_KERNEL_NAME)?;
+
+    ExecuteKernel::new(normalize_kernel)
+        .set_arg(inputs)
+        .set_arg(&means_buffer)
+        .set_arg(&variances_buffer)
+        .set_arg(gamma_buffer)
+        .set_arg(beta_buffer)
+        .set_arg(&normalized_inputs_buffer)
+        .set_arg(&outputs_buffer)
+        .set_arg(&(epsilon as cl_float))
+        .set_arg(&(shape.group_volume() as cl_int))
+        .set_arg(&(shape.groups_amount as cl_int))
+        .set_arg(&(shape.parameter_volume as cl_int))
+        .set_arg(&(shape.inputs_amount as cl_int))
+        .set_arg(&(samples_amount as cl_int))
+        .set_global_work_sizes(&[samples_amount, shape.inputs_amount])
+        .enqueue_nd_range(queue)?;
+
+    queue.finish()?;
+
+    Ok((normalized_inputs_buffer, variances_buffer, outputs_buffer))
+}
+
+/// Computes the gradients of gamma and beta respectively for a normalization in groups.
+pub(crate) fn compute_gradients_in_groups(
+    layer_output_to_error_derivative: &Buffer<cl_float>,
+    normalized_inputs_buffer: &Buffer<cl_float>,
+    shape: GroupNormalizationShape,
+    state: &OpenCLState,
+) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+    if state.queues.is_empty() {
+        return Err(LayerGradientComputationError::NoCommandQueueFound);
+    }
+
+    let queue = state.queues.first().unwrap();
+
+    let derivatives_total_count =
+        layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+    if derivatives_total_count % shape.inputs_amount != 0 {
+        return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+    }
+
+    let samples_amount = derivatives_total_count / shape.inputs_amount;
+
+    let gamma_gradients = empty_buffer(shape.parameters_amount(), CL_MEM_READ_WRITE, state)?;
+    let beta_gradients = empty_buffer(shape.parameters_amount(), CL_MEM_READ_WRITE, state)?;
+
+    let program = state.get_prgm(GROUP_NORM_PROGRAM_NAME)?;
+    let gradients_kernel = program.get_krnl(COMPUTE_GRADIENTS_KERNEL_NAME)?;
+
+    ExecuteKernel::new(gradients_kernel)
+        .set_arg(layer_output_to_error_derivative)
+        .set_arg(normalized_inputs_buffer)
+        .set_arg(&gamma_gradients)
+        .set_arg(&beta_gradients)
+        .set_arg(&(shape.parameter_volume as cl_int))
+        .set_arg(&(shape.parameters_amount() as cl_int))
+        .set_arg(&(shape.inputs_amount as cl_int))
+        .set_arg(&(samples_amount as cl_int))
+        .set_global_work_size(shape.parameters_amount())
+        .enqueue_nd_range(queue)?;
+
+    queue.finish()?;
+
+    Ok(vec![
+        Gradient {
+            parameter_id: "gamma".to_string(),
+            value: gamma_gradients,
+            optimizable: true,
+        },
+        Gradient {
+            parameter_id: "beta".to_string(),
+            value: beta_gradients,
+            optimizable: true,
+        },
+    ])
+}
+
+/// Computes the derivatives of the loss with respect to the inputs of a normalization in groups.
+pub(crate) fn compute_loss_to_input_derivatives_in_groups(
+    layer_output_to_error_derivative: &Buffer<cl_float>,
+    normalized_inputs_buffer: &Buffer<cl_float>,
+    variances_buffer: &Buffer<cl_float>,
+    gamma_buffer: &Buffer<cl_float>,
+    epsilon: f32,
+    shape: GroupNormalizationShape,
+    state: &OpenCLState,
+) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+    if state.queues.is_empty() {
+        return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+    }
+
+    let queue = state.queues.first().unwrap();
+
+    let derivatives_total_count =
+        layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+    if derivatives_total_count % shape.inputs_amount != 0 {
+        return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+    }
+
+    let samples_amount = derivatives_total_count / shape.inputs_amount;
+
+    let loss_to_input_derivatives =
+        empty_buffer(derivatives_total_count, CL_MEM_READ_WRITE, state)?;
+
+    let program = state.get_prgm(GROUP_NORM_PROGRAM_NAME)?;
+    let kernel = program.get_krnl(LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME)?;
+
+    ExecuteKernel::new(kernel)
+        .set_arg(layer_output_to_error_derivative)
+        .set_arg(normalized_inputs_buffer)
+        .set_arg(variances_buffer)
+        .set_arg(gamma_buffer)
+        .set_arg(&loss_to_input_derivatives)
+        .set_arg(&(epsilon as cl_float))
+        .set_arg(&(shape.group_volume() as cl_int))
+        .set_arg(&(shape.groups_amount as cl_int))
+        .set_arg(&(shape.parameter_volume as cl_int))
+        .set_arg(&(shape.inputs_amount as cl_int))
+        .set_arg(&(samples_amount as cl_int))
+        .set_global_work_sizes(&[samples_amount, shape.inputs_amount])
+        .enqueue_nd_range(queue)?;
+
+    queue.finish()?;
+
+    Ok(loss_to_input_derivatives)
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that separates the channels of its input images into groups and normalizes each
+/// group of each sample using its own mean and variance, then scales and shifts every channel
+/// with the trainable **gamma** and **beta** parameters.
+///
+/// Since the statistics are computed per sample, this works the same way while training and
+/// predicting and does not depend on the batch size, which is good for a Conv2D's outputs.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::GroupNorm;
+///
+/// // this will normalize 26x26 images with 8 channels in 4 groups of 2 channels
+/// let my_layer: GroupNorm = GroupNorm::new_raw((26, 26), 8, 4);
+/// ```
+pub struct GroupNorm<'a> {
+    /// The size of the inputs, width and height respectively.
+    pub inputs_size: (usize, usize),
+    /// The amount of channels that each input image has.
+    pub channels: usize,
+    /// The amount of groups the channels are separated into.
+    pub groups: usize,
+
+    /// A small number added to the variance to avoid dividing by zero.
+    pub epsilon: f32,
+
+    /// The factors by which each channel is multiplied after being normalized, stored in the CPU.
+    pub gamma: Vec<f32>,
+    /// The numbers added to each channel after scaling them, stored in the CPU.
+    pub beta: Vec<f32>,
+
+    /// The initializers that will generate the initial parameters for the GroupNorm (gamma and
+    /// beta).
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the gamma of this GroupNorm.
+    pub gamma_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the beta of this GroupNorm.
+    pub beta_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this GroupNorm.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The inputs after being normalized in the last forward pass, before gamma and beta.
+    pub last_normalized_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The variance of each group of each sample in the last forward pass.
+    pub last_variances_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this GroupNorm.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> GroupNorm<'a> {
+    /// Creates a raw version of the GroupNorm layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount of channels is not divisible by the amount of groups.
+    pub fn new_raw(inputs_size: (usize, usize), channels: usize, groups: usize) -> GroupNorm<'a> {
+        assert!(
+            groups > 0 && channels % groups == 0,
+            "the channels of a GroupNorm must be divisible by its amount of groups"
+        );
+
+        let mut initializers = HashMap::with_capacity(2);
+        initializers.insert("gamma".to_string(), ConstantInitializer::new(1.0).into());
+        initializers.insert("beta".to_string(), ConstantInitializer::new(0.0).into());
+
+        GroupNorm {
+            inputs_size,
+            channels,
+            groups,
+
+            epsilon: 0.001,
+
+            gamma: Vec::default(),
+            beta: Vec::default(),
+
+            initializers,
+
+            gamma_buffer: None,
+            beta_buffer: None,
+
+            last_inputs_buffer: None,
+            last_normalized_inputs_buffer: None,
+            last_variances_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the GroupNorm layer, to be used with a Model.
+    pub fn new(inputs_size: (usize, usize), channels: usize, groups: usize) -> ModelLayer<'a> {
+        Self::new_raw(inputs_size, channels, groups).into()
+    }
+
+    /// Sets the small number that is added to the variance to avoid divisions by zero, which
+    /// is **0.001** by default.
+    pub fn set_epsilon(mut self, epsilon: f32) -> Self {
+        self.epsilon = epsilon;
+
+        self
+    }
+
+    fn get_shape(&self) -> GroupNormalizationShape {
+        GroupNormalizationShape {
+            inputs_amount: self.get_inputs_amount(),
+            groups_amount: self.groups,
+            parameter_volume: self.inputs_size.0 * self.inputs_size.1,
+        }
+    }
+}
+
+impl<'a> Layer<'a> for GroupNorm<'a> {
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "gamma" => Some(self.gamma.to_vec()),
+            "beta" => Some(self.beta.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_size.0 * self.inputs_size.1 * self.channels
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.get_inputs_amount()
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.gamma_buffer.is_some() {
+            drop(self.gamma_buffer.as_ref().unwrap());
+        }
+
+        if self.beta_buffer.is_some() {
+            drop(self.beta_buffer.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_normalized_inputs_buffer.is_some() {
+            drop(self.last_normalized_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_variances_buffer.is_some() {
+            drop(self.last_variances_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.gamma_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "gamma_buffer".to_string(),
+            });
+        }
+
+        if self.beta_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "beta_buffer".to_string(),
+            });
+        }
+
+        self.gamma = Vec::<f32>::from_buffer(self.gamma_buffer.as_ref().unwrap(), false, state)?;
+        self.beta = Vec::<f32>::from_buffer(self.beta_buffer.as_ref().unwrap(), false, state)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.gamma.is_empty() {
+            if let Some(initializer) = self.initializers.get("gamma") {
+                self.gamma = initializer.initialize_1d(self.channels, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer("gamma"));
+            }
+        }
+
+        if self.beta.is_empty() {
+            if let Some(initializer) = self.initializers.get("beta") {
+                self.beta = initializer.initialize_1d(self.channels, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer("beta"));
+            }
+        }
+
+        self.gamma_buffer = Some(self.gamma.to_buffer(false, opencl_state)?);
+        self.beta_buffer = Some(self.beta.to_buffer(false, opencl_state)?);
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        let (normalized_inputs_buffer, variances_buffer, outputs_buffer) = propagate_in_groups(
+            inputs,
+            self.gamma_buffer.as_ref().unwrap(),
+            self.beta_buffer.as_ref().unwrap(),
+            self.epsilon,
+            self.get_shape(),
+            state,
+        )?;
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+        self.last_normalized_inputs_buffer = Some(normalized_inputs_buffer);
+        self.last_variances_buffer = Some(variances_buffer);
+        self.last_outputs_buffer = Some(outputs_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        if self.last_normalized_inputs_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        compute_gradients_in_groups(
+            layer_output_to_error_derivative,
+            self.last_normalized_inputs_buffer.as_ref().unwrap(),
+            self.get_shape(),
+            self.opencl_state.unwrap(),
+        )
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.gamma_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "gamma".to_string(),
+            ));
+        }
+
+        if self.beta_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "beta".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.gamma_buffer.as_mut().unwrap(),
+            "gamma".to_string(),
+            timestep,
+            layer_index,
+        )?;
+        optimizer.optimize_parameters(
+            self.beta_buffer.as_mut().unwrap(),
+            "beta".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != 2 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_index,
+            timestep,
+            state,
+        )?;
+
+        let gamma_buffer = self.gamma_buffer.as_mut().unwrap();
+        let beta_buffer = self.beta_buffer.as_mut().unwrap();
+        gamma_buffer.subtract_inplc(&update_vectors[0], state)?;
+        beta_buffer.subtract_inplc(&update_vectors[1], state)?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        if self.last_normalized_inputs_buffer.is_none() || self.last_variances_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        compute_loss_to_input_derivatives_in_groups(
+            layer_output_to_error_derivative,
+            self.last_normalized_inputs_buffer.as_ref().unwrap(),
+            self.last_variances_buffer.as_ref().unwrap(),
+            self.gamma_buffer.as_ref().unwrap(),
+            self.epsilon,
+            self.get_shape(),
+            self.opencl_state.unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod group_norm_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::GroupNorm;
+
+    #[test]
+    fn should_normalize_groups_of_channels_and_compute_derivatives_correctly() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5,
+            0.7, -0.3,
+
+            0.4, 0.9,
+            0.2, 0.6,
+
+
+            -0.5, 0.3,
+            0.8, 0.1,
+
+            0.0, 0.4,
+            1.0, -0.2,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.2, -0.4, 0.6, 0.1, -0.3, 0.5, 0.1, 0.3,
+            0.4, 0.2, -0.1, 0.3, 0.5, -0.2, 0.2, 0.1,
+        ];
+
+        let expected_outputs = vec![
+            -0.3892, 0.6487, 2.4355, -2.7545, -0.3399, 0.6197, 1.4475, -0.0879,
+            -1.4461, 0.2678, 2.778, -0.2214, -0.4266, 0.0089, -1.3239, 1.2885,
+        ];
+        let expected_gamma_gradients = vec![-0.4311, 0.1879, 0.2468, 0.0788];
+        let expected_beta_gradients = vec![0.2, 0.45, 0.25, 0.35];
+        let expected_loss_to_input_derivatives = vec![
+            -0.0629, -2.1441, 1.7457, 0.4613, -0.0714, 0.598, 0.4669, -0.9935,
+            -0.3673, 0.0204, -0.3265, 0.6733, 0.4905, -0.0909, -0.0378, -0.3618,
+        ];
+
+        let mut layer = GroupNorm::new_raw((2, 1), 4, 2);
+        layer.gamma = vec![1.0, 2.0, 0.5, -1.0];
+        layer.beta = vec![0.0, 0.1, -0.1, 0.2];
+        layer.init(&opencl_state).expect("unable to init GroupNorm");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the GroupNorm");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the GroupNorm");
+        let gamma_gradients = Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+            .expect("unable to read the gamma gradients buffer");
+        let beta_gradients = Vec::<f32>::from_buffer(&gradients[1].value, false, &opencl_state)
+            .expect("unable to read the beta gradients buffer");
+
+        assert_approx_equal_distance(&gamma_gradients, &expected_gamma_gradients, 0.01);
+        assert_approx_equal_distance(&beta_gradients, &expected_beta_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the GroupNorm");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
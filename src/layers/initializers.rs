@@ -39,6 +39,17 @@ where
             .map(|_| self.initialize_2d((shape.1, shape.2), layer))
             .collect()
     }
+
+    /// Generates a 4D Matrix of Vec of numbers initialized based on the Initializer's implementation
+    fn initialize_4d<'a>(
+        &self,
+        shape: (usize, usize, usize, usize),
+        layer: &dyn Layer<'a>,
+    ) -> Vec<Vec<Vec<Vec<f32>>>> {
+        (0..shape.0)
+            .map(|_| self.initialize_3d((shape.1, shape.2, shape.3), layer))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Savefile)]
@@ -0,0 +1,284 @@
+// the queries, keys, values and contexts all have the shape (samples, timesteps, embedding_size)
+// where each head uses `head_size` contiguous numbers of the embedding, and the attention
+// scores and weights have the shape (samples, heads, timesteps, timesteps)
+
+kernel void add_positional_encoding(
+    global float* inputs,
+
+    global float* encoded_inputs,
+
+    int samples_amount,
+    int timesteps,
+    int embedding_size
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= timesteps * embedding_size) {
+        return;
+    }
+
+    int timestep = input_index / embedding_size;
+    int embedding_index = input_index % embedding_size;
+
+    float angle = (float)timestep
+        / pow(10000.0f, (float)(embedding_index - embedding_index % 2) / (float)embedding_size);
+
+    float encoding;
+    if (embedding_index % 2 == 0) {
+        encoding = sin(angle);
+    } else {
+        encoding = cos(angle);
+    }
+
+    int flat_input_index = sample_index * timesteps * embedding_size + input_index;
+
+    encoded_inputs[flat_input_index] = (float)inputs[flat_input_index] + encoding;
+}
+
+kernel void compute_attention_scores(
+    global float* queries,
+    global float* keys,
+
+    global float* scores,
+
+    int causal,
+
+    int samples_amount,
+    int timesteps,
+    int embedding_size,
+    int heads_amount
+) {
+    int sample_head_index = get_global_id(0);
+
+    if (sample_head_index >= samples_amount * heads_amount) {
+        return;
+    }
+
+    int query_timestep = get_global_id(1);
+
+    if (query_timestep >= timesteps) {
+        return;
+    }
+
+    int key_timestep = get_global_id(2);
+
+    if (key_timestep >= timesteps) {
+        return;
+    }
+
+    int sample_index = sample_head_index / heads_amount;
+    int head_index = sample_head_index % heads_amount;
+    int head_size = embedding_size / heads_amount;
+
+    int score_index = (sample_head_index * timesteps + query_timestep) * timesteps + key_timestep;
+
+    // the softmax will make the masked scores have a weight of zero
+    if (causal != 0 && key_timestep > query_timestep) {
+        scores[score_index] = -INFINITY;
+        return;
+    }
+
+    int query_start = (sample_index * timesteps + query_timestep) * embedding_size + head_index * head_size;
+    int key_start = (sample_index * timesteps + key_timestep) * embedding_size + head_index * head_size;
+
+    float score = 0.0f;
+
+    for (int i = 0; i < head_size; i++) {
+        score += (float)queries[query_start + i] * (float)keys[key_start + i];
+    }
+
+    scores[score_index] = score * rsqrt((float)head_size);
+}
+
+kernel void compute_context(
+    global float* attention_weights,
+    global float* values,
+
+    global float* context,
+
+    int samples_amount,
+    int timesteps,
+    int embedding_size,
+    int heads_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int query_timestep = get_global_id(1);
+
+    if (query_timestep >= timesteps) {
+        return;
+    }
+
+    int embedding_index = get_global_id(2);
+
+    if (embedding_index >= embedding_size) {
+        return;
+    }
+
+    int head_index = embedding_index / (embedding_size / heads_amount);
+    int weights_start = ((sample_index * heads_amount + head_index) * timesteps + query_timestep) * timesteps;
+
+    float value = 0.0f;
+
+    for (int key_timestep = 0; key_timestep < timesteps; key_timestep++) {
+        value += (float)attention_weights[weights_start + key_timestep]
+            * (float)values[(sample_index * timesteps + key_timestep) * embedding_size + embedding_index];
+    }
+
+    context[(sample_index * timesteps + query_timestep) * embedding_size + embedding_index] = value;
+}
+
+kernel void compute_attention_weights_derivatives(
+    global float* loss_to_context_derivatives,
+    global float* values,
+
+    global float* loss_to_attention_weights_derivatives,
+
+    int samples_amount,
+    int timesteps,
+    int embedding_size,
+    int heads_amount
+) {
+    int sample_head_index = get_global_id(0);
+
+    if (sample_head_index >= samples_amount * heads_amount) {
+        return;
+    }
+
+    int query_timestep = get_global_id(1);
+
+    if (query_timestep >= timesteps) {
+        return;
+    }
+
+    int key_timestep = get_global_id(2);
+
+    if (key_timestep >= timesteps) {
+        return;
+    }
+
+    int sample_index = sample_head_index / heads_amount;
+    int head_index = sample_head_index % heads_amount;
+    int head_size = embedding_size / heads_amount;
+
+    int context_start = (sample_index * timesteps + query_timestep) * embedding_size + head_index * head_size;
+    int value_start = (sample_index * timesteps + key_timestep) * embedding_size + head_index * head_size;
+
+    float derivative = 0.0f;
+
+    for (int i = 0; i < head_size; i++) {
+        derivative += (float)loss_to_context_derivatives[context_start + i] * (float)values[value_start + i];
+    }
+
+    loss_to_attention_weights_derivatives[
+        (sample_head_index * timesteps + query_timestep) * timesteps + key_timestep
+    ] = derivative;
+}
+
+kernel void compute_values_derivatives(
+    global float* attention_weights,
+    global float* loss_to_context_derivatives,
+
+    global float* loss_to_values_derivatives,
+
+    int samples_amount,
+    int timesteps,
+    int embedding_size,
+    int heads_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int key_timestep = get_global_id(1);
+
+    if (key_timestep >= timesteps) {
+        return;
+    }
+
+    int embedding_index = get_global_id(2);
+
+    if (embedding_index >= embedding_size) {
+        return;
+    }
+
+    int head_index = embedding_index / (embedding_size / heads_amount);
+    int weights_start = (sample_index * heads_amount + head_index) * timesteps * timesteps;
+
+    float derivative = 0.0f;
+
+    for (int query_timestep = 0; query_timestep < timesteps; query_timestep++) {
+        derivative += (float)attention_weights[weights_start + query_timestep * timesteps + key_timestep]
+            * (float)loss_to_context_derivatives[(sample_index * timesteps + query_timestep) * embedding_size + embedding_index];
+    }
+
+    loss_to_values_derivatives[(sample_index * timesteps + key_timestep) * embedding_size + embedding_index] = derivative;
+}
+
+// computes the derivatives of the loss with respect to either the queries or the keys, since
+// they are symmetrical the only difference is which of the timesteps of the scores is summed over
+kernel void compute_queries_or_keys_derivatives(
+    global float* loss_to_scores_derivatives,
+    // the keys when computing the derivatives of the queries and vice versa
+    global float* others,
+
+    global float* derivatives,
+
+    int is_keys,
+
+    int samples_amount,
+    int timesteps,
+    int embedding_size,
+    int heads_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int timestep = get_global_id(1);
+
+    if (timestep >= timesteps) {
+        return;
+    }
+
+    int embedding_index = get_global_id(2);
+
+    if (embedding_index >= embedding_size) {
+        return;
+    }
+
+    int head_size = embedding_size / heads_amount;
+    int head_index = embedding_index / head_size;
+    int scores_start = (sample_index * heads_amount + head_index) * timesteps * timesteps;
+
+    float derivative = 0.0f;
+
+    for (int other_timestep = 0; other_timestep < timesteps; other_timestep++) {
+        int score_index;
+        if (is_keys != 0) {
+            score_index = scores_start + other_timestep * timesteps + timestep;
+        } else {
+            score_index = scores_start + timestep * timesteps + other_timestep;
+        }
+
+        derivative += (float)loss_to_scores_derivatives[score_index]
+            * (float)others[(sample_index * timesteps + other_timestep) * embedding_size + embedding_index];
+    }
+
+    derivatives[(sample_index * timesteps + timestep) * embedding_size + embedding_index] =
+        derivative * rsqrt((float)head_size);
+}
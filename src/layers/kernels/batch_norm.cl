@@ -0,0 +1,184 @@
+kernel void compute_batch_statistics(
+    global float* inputs,
+
+    global float* means,
+    global float* variances,
+
+    global float* running_means,
+    global float* running_variances,
+
+    float momentum,
+    int update_running_statistics,
+
+    int samples_amount,
+    int inputs_amount
+) {
+    int input_index = get_global_id(0);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    float mean = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        mean += (float)inputs[sample_index * inputs_amount + input_index];
+    }
+
+    mean /= (float)samples_amount;
+
+    float variance = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        float deviation = (float)inputs[sample_index * inputs_amount + input_index] - mean;
+
+        variance += deviation * deviation;
+    }
+
+    variance /= (float)samples_amount;
+
+    means[input_index] = mean;
+    variances[input_index] = variance;
+
+    if (!update_running_statistics) {
+        return;
+    }
+
+    running_means[input_index] = momentum * (float)running_means[input_index]
+        + (1.0f - momentum) * mean;
+    running_variances[input_index] = momentum * (float)running_variances[input_index]
+        + (1.0f - momentum) * variance;
+}
+
+kernel void normalize(
+    global float* inputs,
+
+    global float* means,
+    global float* variances,
+
+    global float* gammas,
+    global float* betas,
+
+    global float* normalized_inputs,
+    global float* outputs,
+
+    float epsilon,
+
+    int samples_amount,
+    int inputs_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_index = sample_index * inputs_amount + input_index;
+
+    float normalized_input = ((float)inputs[flat_input_index] - (float)means[input_index])
+        * rsqrt((float)variances[input_index] + epsilon);
+
+    normalized_inputs[flat_input_index] = normalized_input;
+    outputs[flat_input_index] = (float)gammas[input_index] * normalized_input
+        + (float)betas[input_index];
+}
+
+kernel void compute_gradients(
+    global float* loss_to_output_derivatives,
+    global float* normalized_inputs,
+
+    global float* gamma_gradients,
+    global float* beta_gradients,
+
+    int samples_amount,
+    int inputs_amount
+) {
+    int input_index = get_global_id(0);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    float gamma_gradient = 0.0f;
+    float beta_gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int flat_input_index = sample_index * inputs_amount + input_index;
+
+        float loss_to_output_derivative = (float)loss_to_output_derivatives[flat_input_index];
+
+        gamma_gradient += loss_to_output_derivative * (float)normalized_inputs[flat_input_index];
+        beta_gradient += loss_to_output_derivative;
+    }
+
+    gamma_gradients[input_index] = gamma_gradient / (float)samples_amount;
+    beta_gradients[input_index] = beta_gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_input_derivatives(
+    global float* loss_to_output_derivatives,
+    global float* normalized_inputs,
+
+    global float* variances,
+    global float* gammas,
+
+    global float* loss_to_input_derivatives,
+
+    float epsilon,
+    // weather or not the variances are the ones from the batch itself, in which case
+    // the means and variances also depend on the inputs
+    int used_batch_statistics,
+
+    int samples_amount,
+    int inputs_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_index = sample_index * inputs_amount + input_index;
+
+    float scaler = (float)gammas[input_index] * rsqrt((float)variances[input_index] + epsilon);
+    float loss_to_output_derivative = (float)loss_to_output_derivatives[flat_input_index];
+
+    if (used_batch_statistics == 0) {
+        loss_to_input_derivatives[flat_input_index] = scaler * loss_to_output_derivative;
+        return;
+    }
+
+    float mean_derivative = 0.0f;
+    float mean_derivative_times_normalized_input = 0.0f;
+
+    for (int other_sample_index = 0; other_sample_index < samples_amount; other_sample_index++) {
+        int other_flat_input_index = other_sample_index * inputs_amount + input_index;
+
+        float other_derivative = (float)loss_to_output_derivatives[other_flat_input_index];
+
+        mean_derivative += other_derivative;
+        mean_derivative_times_normalized_input += other_derivative
+            * (float)normalized_inputs[other_flat_input_index];
+    }
+
+    mean_derivative /= (float)samples_amount;
+    mean_derivative_times_normalized_input /= (float)samples_amount;
+
+    loss_to_input_derivatives[flat_input_index] = scaler * (
+        loss_to_output_derivative
+        - mean_derivative
+        - (float)normalized_inputs[flat_input_index] * mean_derivative_times_normalized_input
+    );
+}
@@ -0,0 +1,247 @@
+// gets the position of the input that a certain weight of the filter is on top of for a
+// certain output considering the stride, padding and dilation of the convolution, which can be
+// outside of the sequence in case of padding
+int get_input_position(
+    int output_position,
+    int filter_position,
+
+    int stride,
+    int padding,
+    int dilation
+) {
+    return output_position * stride - padding + filter_position * dilation;
+}
+
+// gets the position of the output for which a certain weight of the filter was on top of the
+// input, or -1 in case there is no such output
+int get_output_position(
+    int input_position,
+    int filter_position,
+
+    int stride,
+    int padding,
+    int dilation,
+
+    int outputs_length
+) {
+    int strided_position = input_position + padding - filter_position * dilation;
+
+    if (strided_position < 0 || strided_position % stride != 0) {
+        return -1;
+    }
+
+    int output_position = strided_position / stride;
+
+    if (output_position >= outputs_length) {
+        return -1;
+    }
+
+    return output_position;
+}
+
+kernel void convolute(
+    global float* inputs,
+    global float* filters,
+    global float* biases,
+
+    global float* outputs,
+
+    int inputs_length,
+    int channels,
+
+    int outputs_length,
+
+    int filter_size,
+    int filters_amount,
+
+    int stride,
+    int padding,
+    int dilation,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the output considering all of the filters
+    int output_index = get_global_id(1);
+
+    if (output_index >= outputs_length * filters_amount) {
+        return;
+    }
+
+    int filter_index = output_index / outputs_length;
+    int output_position = output_index % outputs_length;
+
+    float output = (float)biases[filter_index];
+
+    for (int filter_position = 0; filter_position < filter_size; filter_position++) {
+        int input_position = get_input_position(output_position, filter_position, stride, padding, dilation);
+
+        // the inputs that fall into the padding are just zeros
+        if (input_position < 0 || input_position >= inputs_length) {
+            continue;
+        }
+
+        for (int channel_index = 0; channel_index < channels; channel_index++) {
+            int input_index = (sample_index * channels + channel_index) * inputs_length + input_position;
+            int weight_index = (filter_index * channels + channel_index) * filter_size + filter_position;
+
+            output += (float)inputs[input_index] * (float)filters[weight_index];
+        }
+    }
+
+    outputs[sample_index * outputs_length * filters_amount + output_index] = output;
+}
+
+kernel void compute_weights_gradients(
+    global float* inputs,
+    global float* loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int inputs_length,
+    int channels,
+
+    int outputs_length,
+
+    int filter_size,
+    int filters_amount,
+
+    int stride,
+    int padding,
+    int dilation,
+
+    int samples_amount
+) {
+    int filter_index = get_global_id(0);
+
+    if (filter_index >= filters_amount) {
+        return;
+    }
+
+    int channel_index = get_global_id(1);
+
+    if (channel_index >= channels) {
+        return;
+    }
+
+    int filter_position = get_global_id(2);
+
+    if (filter_position >= filter_size) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int inputs_start = (sample_index * channels + channel_index) * inputs_length;
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_length;
+
+        for (int output_position = 0; output_position < outputs_length; output_position++) {
+            int input_position = get_input_position(output_position, filter_position, stride, padding, dilation);
+
+            if (input_position < 0 || input_position >= inputs_length) {
+                continue;
+            }
+
+            gradient += (float)inputs[inputs_start + input_position]
+                * (float)loss_to_output_derivatives[outputs_start + output_position];
+        }
+    }
+
+    gradients[(filter_index * channels + channel_index) * filter_size + filter_position] =
+        gradient / (float)samples_amount;
+}
+
+kernel void compute_biases_gradients(
+    global float* loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int outputs_length,
+    int filters_amount,
+
+    int samples_amount
+) {
+    int filter_index = get_global_id(0);
+
+    if (filter_index >= filters_amount) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_length;
+
+        for (int output_position = 0; output_position < outputs_length; output_position++) {
+            gradient += (float)loss_to_output_derivatives[outputs_start + output_position];
+        }
+    }
+
+    gradients[filter_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_input_derivatives(
+    global float* filters,
+    global float* loss_to_output_derivatives,
+
+    global float* loss_to_input_derivatives,
+
+    int inputs_length,
+    int channels,
+
+    int outputs_length,
+
+    int filter_size,
+    int filters_amount,
+
+    int stride,
+    int padding,
+    int dilation,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the input considering all of the channels
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_length * channels) {
+        return;
+    }
+
+    int channel_index = input_index / inputs_length;
+    int input_position = input_index % inputs_length;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int filter_index = 0; filter_index < filters_amount; filter_index++) {
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_length;
+        int filter_start = (filter_index * channels + channel_index) * filter_size;
+
+        for (int filter_position = 0; filter_position < filter_size; filter_position++) {
+            int output_position = get_output_position(
+                input_position, filter_position, stride, padding, dilation, outputs_length
+            );
+
+            if (output_position < 0) {
+                continue;
+            }
+
+            loss_to_input_derivative += (float)filters[filter_start + filter_position]
+                * (float)loss_to_output_derivatives[outputs_start + output_position];
+        }
+    }
+
+    loss_to_input_derivatives[sample_index * inputs_length * channels + input_index] =
+        loss_to_input_derivative;
+}
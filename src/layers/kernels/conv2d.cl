@@ -1,51 +1,29 @@
-int get_pixel_x_from_id(
-    int id,
-
-    int width
-) {
-    return id % width;
-}
-
-int get_pixel_y_from_id(
-    int id,
-    int x,
-
-    int width
-) {
-    return (id  - x) / width;
-}
-
-int get_image_pixel_id(
-    int local_id, 
-
-    int filter_id,
-    int filter_starting_pixel_index,
-
-    int image_width,
-    int filter_width
+// gets the position of the pixel of the image that a certain pixel of the filter
+// is on top of for a certain output pixel considering the stride, padding and dilation
+// of the convolution, which can be outside of the image in case of padding
+int get_input_position(
+    int output_position,
+    int filter_position,
+
+    int stride,
+    int padding,
+    int dilation
 ) {
-    int local_pixel_x = get_pixel_x_from_id(local_id, filter_width);
-    int local_pixel_y = get_pixel_y_from_id(local_id, local_pixel_x, filter_width);
-
-    int filter_starting_global_pixel_x = get_pixel_x_from_id(filter_starting_pixel_index, image_width);
-    int filter_starting_global_pixel_y = get_pixel_y_from_id(filter_starting_pixel_index, filter_starting_global_pixel_x, image_width);
-
-    int pixel_id = (filter_starting_global_pixel_y + local_pixel_y) * image_width
-        + filter_starting_global_pixel_x + local_pixel_x;
-
-    return pixel_id;
+    return output_position * stride - padding + filter_position * dilation;
 }
 
 kernel void convolute(
     global float* image,
-    constant float* filter,
+    global float* filters,
     global float* biases,
     global float* output,
 
     local float* filtered,
 
     int image_width,
+    int image_height,
     int image_volume,
+    int channels,
 
     int output_width,
     int output_image_volume,
@@ -53,6 +31,14 @@ kernel void convolute(
     int filter_width,
     int filter_height,
     int filter_volume,
+    int filters_amount,
+
+    int stride_x,
+    int stride_y,
+    int padding_x,
+    int padding_y,
+    int dilation_x,
+    int dilation_y,
 
     int samples_amount
 ) {
@@ -64,25 +50,37 @@ kernel void convolute(
 
     int local_sample_index = get_local_id(0);
 
-    int output_index = get_group_id(1);
+    // each work group computes one output pixel of one of the filters
+    int group_index = get_group_id(1);
+    int filter_index = group_index / output_image_volume;
+    int output_index = group_index % output_image_volume;
     int filter_pixel_index = get_local_id(1);
 
-    int filter_starting_global_pixel_id = output_index  
-        + (filter_width - 1) * (int)floor((float)output_index / (float)output_width);
+    int output_x = output_index % output_width;
+    int output_y = output_index / output_width;
 
-    int pixel_index = get_image_pixel_id(
-        filter_pixel_index,
+    int filter_pixel_x = filter_pixel_index % filter_width;
+    int filter_pixel_y = filter_pixel_index / filter_width;
 
-        output_index,
-        filter_starting_global_pixel_id,
+    int pixel_x = get_input_position(output_x, filter_pixel_x, stride_x, padding_x, dilation_x);
+    int pixel_y = get_input_position(output_y, filter_pixel_y, stride_y, padding_y, dilation_y);
 
-        image_width,
-        filter_width
-    );
+    float filtered_pixel = 0.0f;
+
+    // the pixels that fall into the padding are just zeros
+    if (pixel_x >= 0 && pixel_x < image_width && pixel_y >= 0 && pixel_y < image_height) {
+        int pixel_index = pixel_y * image_width + pixel_x;
+
+        for (int channel_index = 0; channel_index < channels; channel_index++) {
+            int global_pixel_index = (sample_index * channels + channel_index) * image_volume + pixel_index;
+            int weight_index = (filter_index * channels + channel_index) * filter_volume + filter_pixel_index;
+
+            filtered_pixel += image[global_pixel_index] // the pixel
+                * filters[weight_index]; // multiplied by the respective filter weight
+        }
+    }
 
-    filtered[local_sample_index * filter_volume + filter_pixel_index] = 
-        image[sample_index * image_volume + pixel_index] // the pixel
-      * filter[filter_pixel_index]; // multiplied by the respective filter weight
+    filtered[local_sample_index * filter_volume + filter_pixel_index] = filtered_pixel;
     barrier(CLK_LOCAL_MEM_FENCE);
 
     if (filter_pixel_index == 0) {
@@ -92,7 +90,9 @@ kernel void convolute(
             result += filtered[local_sample_index * filter_volume + i];
         }
 
-        output[sample_index * output_image_volume + output_index] = result + (float)biases[0];
+        int global_output_index = (sample_index * filters_amount + filter_index) * output_image_volume + output_index;
+
+        output[global_output_index] = result + (float)biases[filter_index];
     }
 }
 
@@ -102,85 +102,155 @@ kernel void compute_gradients_for_one_filter_pixel(
     global float* filter_pixel_gradients,
 
     int image_width,
+    int image_height,
     int image_volume,
+    int channels,
 
     int filter_width,
     int filter_volume,
-    
+    int filters_amount,
+
+    int stride_x,
+    int stride_y,
+    int padding_x,
+    int padding_y,
+    int dilation_x,
+    int dilation_y,
+
     int samples_amount,
 
     int output_width,
     int output_height,
-    int output_volume,
-
-    int pixel_index,
-    int pixel_y,
-    int pixel_x
+    int output_volume
 ) {
-    int sample_index = get_global_id(0);
+    int filter_index = get_global_id(0);
 
-    if (sample_index >= samples_amount) {
+    if (filter_index >= filters_amount) {
         return;
     }
 
-    int output_y = get_global_id(1);
+    int channel_index = get_global_id(1);
 
-    if (output_y >= output_width) {
+    if (channel_index >= channels) {
         return;
     }
 
-    int output_x = get_global_id(2);
+    int pixel_index = get_global_id(2);
 
-    if (output_x >= output_height) {
+    if (pixel_index >= filter_volume) {
         return;
     }
 
-    int input_y = output_y + pixel_y;
-    int input_x = output_x + pixel_x;
+    int pixel_x = pixel_index % filter_width;
+    int pixel_y = pixel_index / filter_width;
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int image_start = (sample_index * channels + channel_index) * image_volume;
+        int output_start = (sample_index * filters_amount + filter_index) * output_volume;
 
-    int input_index = input_y * image_width + input_x;
-    int global_input_index = sample_index * image_volume + input_index;
+        for (int output_y = 0; output_y < output_height; output_y++) {
+            int input_y = get_input_position(output_y, pixel_y, stride_y, padding_y, dilation_y);
+
+            if (input_y < 0 || input_y >= image_height) {
+                continue;
+            }
+
+            for (int output_x = 0; output_x < output_width; output_x++) {
+                int input_x = get_input_position(output_x, pixel_x, stride_x, padding_x, dilation_x);
+
+                if (input_x < 0 || input_x >= image_width) {
+                    continue;
+                }
+
+                int global_input_index = image_start + input_y * image_width + input_x;
+                int global_output_index = output_start + output_y * output_width + output_x;
+
+                gradient += (float)image[global_input_index]
+                    * (float)error_to_output_derivatives[global_output_index];
+            }
+        }
+    }
 
-    int output_index = output_y * output_width + output_x;
-    int global_output_index = sample_index * output_volume + output_index;
+    int weight_index = (filter_index * channels + channel_index) * filter_volume + pixel_index;
 
-    filter_pixel_gradients[global_output_index] 
-        = (float)image[global_input_index] * (float)error_to_output_derivatives[global_output_index];
+    filter_pixel_gradients[weight_index] = gradient / (float)samples_amount;
 }
 
-// kernel void compute_gradients_for_biases(
-//    global float* loss_to_output_derivatives,
-//    global float* gradients,
-// 
-//    int samples_amount,
-//    int outputs_amount
-//  {
-//    int output_index = get_global_id(0);
-//    if (output_index >= outputs_amount) {
-//        return;
-//    }
-// 
-//    float bias_gradient = 0.0f;
-// 
-//    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
-//        int flat_output_i = sample_index * outputs_amount + output_index;
-// 
-//        bias_gradient += (float)loss_to_output_derivatives[flat_output_i];
-//    }
-// 
-//    gradients[output_index] = bias_gradient / (float)samples_amount;
-// 
+kernel void compute_gradients_for_biases(
+    global float* loss_to_output_derivatives,
+    global float* gradients,
+
+    int samples_amount,
+    int filters_amount,
+    int output_volume
+) {
+    int filter_index = get_global_id(0);
+
+    if (filter_index >= filters_amount) {
+        return;
+    }
+
+    float bias_gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int output_start = (sample_index * filters_amount + filter_index) * output_volume;
+
+        for (int output_index = 0; output_index < output_volume; output_index++) {
+            bias_gradient += (float)loss_to_output_derivatives[output_start + output_index];
+        }
+    }
+
+    gradients[filter_index] = bias_gradient / (float)samples_amount;
+}
+
+// gets the position of the output pixel for which a certain pixel of the filter was on top
+// of the input pixel, or -1 in case there is no such output pixel
+int get_output_position(
+    int input_position,
+    int filter_position,
+
+    int stride,
+    int padding,
+    int dilation,
+
+    int output_size
+) {
+    int strided_position = input_position + padding - filter_position * dilation;
+
+    if (strided_position < 0 || strided_position % stride != 0) {
+        return -1;
+    }
+
+    int output_position = strided_position / stride;
+
+    if (output_position >= output_size) {
+        return -1;
+    }
+
+    return output_position;
+}
 
 kernel void compute_loss_to_input_derivatives(
-    constant float* filter,
+    global float* filters,
     global float* loss_to_output_derivatives,
     global float* loss_to_input_derivatives,
 
     int samples_amount,
+    int channels,
+    int filters_amount,
 
     int filter_width,
     int filter_height,
 
+    int stride_x,
+    int stride_y,
+    int padding_x,
+    int padding_y,
+    int dilation_x,
+    int dilation_y,
+
     int output_height,
     int output_width,
 
@@ -193,31 +263,50 @@ kernel void compute_loss_to_input_derivatives(
         return;
     }
 
+    // the index of the pixel considering all of the channels of the image
     int input_index = get_global_id(1);
-    
-    if (input_index >= inputs_amount) {
+
+    if (input_index >= inputs_amount * channels) {
         return;
     }
 
-    float loss_to_input_derivative = 0;
+    int channel_index = input_index / inputs_amount;
+    int pixel_index = input_index % inputs_amount;
 
-    int input_y = (int)floor((float)input_index / (float)inputs_width);
-    int input_x = input_index % inputs_width;
-    
-    for (int output_y = 0; output_y < output_height; output_y++) {
-        int filter_y = input_y - output_y + 1;
-        if (filter_y >= 0) {
-            for (int output_x = 0; output_x < output_width; output_x++) {
-                int filter_x = input_x - output_x + 1;
-                if (filter_x >= 0) {
-                    int filter_index = filter_y * filter_width + filter_x;
-                    loss_to_input_derivative += (float)filter[filter_index];
+    int input_y = pixel_index / inputs_width;
+    int input_x = pixel_index % inputs_width;
+
+    int output_volume = output_width * output_height;
+    int filter_volume = filter_width * filter_height;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int filter_index = 0; filter_index < filters_amount; filter_index++) {
+        int output_start = (sample_index * filters_amount + filter_index) * output_volume;
+        int filter_start = (filter_index * channels + channel_index) * filter_volume;
+
+        for (int filter_y = 0; filter_y < filter_height; filter_y++) {
+            int output_y = get_output_position(input_y, filter_y, stride_y, padding_y, dilation_y, output_height);
+            if (output_y < 0) {
+                continue;
+            }
+
+            for (int filter_x = 0; filter_x < filter_width; filter_x++) {
+                int output_x = get_output_position(input_x, filter_x, stride_x, padding_x, dilation_x, output_width);
+                if (output_x < 0) {
+                    continue;
                 }
+
+                int filter_pixel_index = filter_start + filter_y * filter_width + filter_x;
+                int output_index = output_start + output_y * output_width + output_x;
+
+                loss_to_input_derivative += (float)filters[filter_pixel_index]
+                    * (float)loss_to_output_derivatives[output_index];
             }
         }
     }
-    
-    int input_derivative_index = sample_index * inputs_amount + input_index;
+
+    int input_derivative_index = sample_index * inputs_amount * channels + input_index;
 
     loss_to_input_derivatives[input_derivative_index] = loss_to_input_derivative;
-}
\ No newline at end of file
+}
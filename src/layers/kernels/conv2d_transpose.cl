@@ -0,0 +1,267 @@
+// gets the position of the input pixel that a certain pixel of the filter puts on top of a
+// certain output pixel, or -1 in case there is no such input pixel
+int get_input_position(
+    int output_position,
+    int filter_position,
+
+    int stride,
+
+    int inputs_size
+) {
+    int strided_position = output_position - filter_position;
+
+    if (strided_position < 0 || strided_position % stride != 0) {
+        return -1;
+    }
+
+    int input_position = strided_position / stride;
+
+    if (input_position >= inputs_size) {
+        return -1;
+    }
+
+    return input_position;
+}
+
+kernel void propagate(
+    global float* inputs,
+    global float* filters,
+    global float* biases,
+
+    global float* outputs,
+
+    int inputs_width,
+    int inputs_height,
+    int channels,
+
+    int outputs_width,
+    int outputs_height,
+
+    int filter_width,
+    int filter_height,
+    int filters_amount,
+
+    int stride_x,
+    int stride_y,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int outputs_volume = outputs_width * outputs_height;
+
+    // the index of the output pixel considering all of the filters
+    int output_index = get_global_id(1);
+
+    if (output_index >= outputs_volume * filters_amount) {
+        return;
+    }
+
+    int filter_index = output_index / outputs_volume;
+    int pixel_index = output_index % outputs_volume;
+
+    int output_x = pixel_index % outputs_width;
+    int output_y = pixel_index / outputs_width;
+
+    int inputs_volume = inputs_width * inputs_height;
+    int filter_volume = filter_width * filter_height;
+
+    float output = (float)biases[filter_index];
+
+    for (int filter_y = 0; filter_y < filter_height; filter_y++) {
+        int input_y = get_input_position(output_y, filter_y, stride_y, inputs_height);
+        if (input_y < 0) {
+            continue;
+        }
+
+        for (int filter_x = 0; filter_x < filter_width; filter_x++) {
+            int input_x = get_input_position(output_x, filter_x, stride_x, inputs_width);
+            if (input_x < 0) {
+                continue;
+            }
+
+            for (int channel_index = 0; channel_index < channels; channel_index++) {
+                int input_index = (sample_index * channels + channel_index) * inputs_volume
+                    + input_y * inputs_width + input_x;
+                int weight_index = (filter_index * channels + channel_index) * filter_volume
+                    + filter_y * filter_width + filter_x;
+
+                output += (float)inputs[input_index] * (float)filters[weight_index];
+            }
+        }
+    }
+
+    outputs[sample_index * outputs_volume * filters_amount + output_index] = output;
+}
+
+kernel void compute_weights_gradients(
+    global float* inputs,
+    global float* loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int inputs_width,
+    int inputs_height,
+    int channels,
+
+    int outputs_width,
+    int outputs_height,
+
+    int filter_width,
+    int filter_height,
+    int filters_amount,
+
+    int stride_x,
+    int stride_y,
+
+    int samples_amount
+) {
+    int filter_index = get_global_id(0);
+
+    if (filter_index >= filters_amount) {
+        return;
+    }
+
+    int channel_index = get_global_id(1);
+
+    if (channel_index >= channels) {
+        return;
+    }
+
+    int filter_volume = filter_width * filter_height;
+
+    int filter_pixel_index = get_global_id(2);
+
+    if (filter_pixel_index >= filter_volume) {
+        return;
+    }
+
+    int filter_x = filter_pixel_index % filter_width;
+    int filter_y = filter_pixel_index / filter_width;
+
+    int inputs_volume = inputs_width * inputs_height;
+    int outputs_volume = outputs_width * outputs_height;
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int inputs_start = (sample_index * channels + channel_index) * inputs_volume;
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_volume;
+
+        // every input pixel is put on top of exactly one output pixel by each filter pixel
+        for (int input_y = 0; input_y < inputs_height; input_y++) {
+            int output_y = input_y * stride_y + filter_y;
+
+            for (int input_x = 0; input_x < inputs_width; input_x++) {
+                int output_x = input_x * stride_x + filter_x;
+
+                gradient += (float)inputs[inputs_start + input_y * inputs_width + input_x]
+                    * (float)loss_to_output_derivatives[outputs_start + output_y * outputs_width + output_x];
+            }
+        }
+    }
+
+    gradients[(filter_index * channels + channel_index) * filter_volume + filter_pixel_index] =
+        gradient / (float)samples_amount;
+}
+
+kernel void compute_biases_gradients(
+    global float* loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int outputs_volume,
+    int filters_amount,
+
+    int samples_amount
+) {
+    int filter_index = get_global_id(0);
+
+    if (filter_index >= filters_amount) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_volume;
+
+        for (int output_index = 0; output_index < outputs_volume; output_index++) {
+            gradient += (float)loss_to_output_derivatives[outputs_start + output_index];
+        }
+    }
+
+    gradients[filter_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_input_derivatives(
+    global float* filters,
+    global float* loss_to_output_derivatives,
+
+    global float* loss_to_input_derivatives,
+
+    int inputs_width,
+    int inputs_height,
+    int channels,
+
+    int outputs_width,
+    int outputs_height,
+
+    int filter_width,
+    int filter_height,
+    int filters_amount,
+
+    int stride_x,
+    int stride_y,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int inputs_volume = inputs_width * inputs_height;
+
+    // the index of the input pixel considering all of the channels
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = input_index / inputs_volume;
+    int pixel_index = input_index % inputs_volume;
+
+    int input_x = pixel_index % inputs_width;
+    int input_y = pixel_index / inputs_width;
+
+    int outputs_volume = outputs_width * outputs_height;
+    int filter_volume = filter_width * filter_height;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int filter_index = 0; filter_index < filters_amount; filter_index++) {
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_volume;
+        int filter_start = (filter_index * channels + channel_index) * filter_volume;
+
+        for (int filter_y = 0; filter_y < filter_height; filter_y++) {
+            int output_y = input_y * stride_y + filter_y;
+
+            for (int filter_x = 0; filter_x < filter_width; filter_x++) {
+                int output_x = input_x * stride_x + filter_x;
+
+                loss_to_input_derivative += (float)filters[filter_start + filter_y * filter_width + filter_x]
+                    * (float)loss_to_output_derivatives[outputs_start + output_y * outputs_width + output_x];
+            }
+        }
+    }
+
+    loss_to_input_derivatives[sample_index * inputs_volume * channels + input_index] =
+        loss_to_input_derivative;
+}
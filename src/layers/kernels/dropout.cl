@@ -0,0 +1,32 @@
+// a simple hash based generator of uniformly distributed numbers in the range [0, 1)
+// that gives out a different number for each index with the same seed
+float random_uniform(uint seed, uint index) {
+    uint state = seed ^ (index * 747796405u + 2891336453u);
+    state = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    state = (state >> 22u) ^ state;
+
+    return (float)state / 4294967296.0f;
+}
+
+kernel void generate_mask(
+    global float* mask,
+
+    float rate,
+    uint seed,
+
+    int count
+) {
+    int index = get_global_id(0);
+
+    if (index >= count) {
+        return;
+    }
+
+    // the kept inputs are scaled up so that the expected sum of
+    // the inputs stays the same as when predicting
+    if (random_uniform(seed, (uint)index) < rate) {
+        mask[index] = 0.0f;
+    } else {
+        mask[index] = 1.0f / (1.0f - rate);
+    }
+}
@@ -0,0 +1,130 @@
+// the indices come in as floats, and the ones that are not in the vocabulary just look up zeros
+
+int get_vocabulary_index(float index, int vocabulary_size) {
+    int vocabulary_index = (int)round(index);
+
+    if (vocabulary_index < 0 || vocabulary_index >= vocabulary_size) {
+        return -1;
+    }
+
+    return vocabulary_index;
+}
+
+kernel void propagate(
+    global float* indices,
+    global float* embeddings,
+
+    global float* outputs,
+
+    int vocabulary_size,
+    int embedding_size,
+
+    int indices_amount
+) {
+    // the index of the token considering all of the samples
+    int token_index = get_global_id(0);
+
+    if (token_index >= indices_amount) {
+        return;
+    }
+
+    int embedding_index = get_global_id(1);
+
+    if (embedding_index >= embedding_size) {
+        return;
+    }
+
+    int vocabulary_index = get_vocabulary_index(indices[token_index], vocabulary_size);
+
+    float output = 0.0f;
+    if (vocabulary_index >= 0) {
+        output = (float)embeddings[vocabulary_index * embedding_size + embedding_index];
+    }
+
+    outputs[token_index * embedding_size + embedding_index] = output;
+}
+
+// the tokens come grouped by the embedding they looked up, so that each embedding only goes
+// through the tokens that looked it up from tokens_offsets[vocabulary_index] up to
+// tokens_offsets[vocabulary_index + 1]
+kernel void compute_embeddings_gradients(
+    global int* tokens_offsets,
+    global int* grouped_token_indices,
+    global float* loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int vocabulary_size,
+    int embedding_size,
+
+    int samples_amount
+) {
+    int vocabulary_index = get_global_id(0);
+
+    if (vocabulary_index >= vocabulary_size) {
+        return;
+    }
+
+    int embedding_index = get_global_id(1);
+
+    if (embedding_index >= embedding_size) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    int tokens_end = tokens_offsets[vocabulary_index + 1];
+    for (int i = tokens_offsets[vocabulary_index]; i < tokens_end; i++) {
+        int token_index = grouped_token_indices[i];
+        gradient += (float)loss_to_output_derivatives[token_index * embedding_size + embedding_index];
+    }
+
+    gradients[vocabulary_index * embedding_size + embedding_index] = gradient / (float)samples_amount;
+}
+
+// only the embeddings that were looked up are updated, so that optimizers that keep going in
+// the same direction for some time, such as with momentum, don't keep changing embeddings of
+// tokens that have not been seen in a while
+kernel void apply_updates_to_used_embeddings(
+    global int* used_vocabulary_indices,
+    global float* update_vectors,
+
+    global float* embeddings,
+
+    int embedding_size,
+
+    int used_embeddings_amount
+) {
+    int used_embedding_index = get_global_id(0);
+
+    if (used_embedding_index >= used_embeddings_amount) {
+        return;
+    }
+
+    int embedding_index = get_global_id(1);
+
+    if (embedding_index >= embedding_size) {
+        return;
+    }
+
+    int vocabulary_index = used_vocabulary_indices[used_embedding_index];
+    int flat_i = vocabulary_index * embedding_size + embedding_index;
+
+    embeddings[flat_i] = (float)embeddings[flat_i] - (float)update_vectors[flat_i];
+}
+
+// the loss can't be differentiated with respect to the indices, but the layers before the
+// Embedding still expect some derivatives, so they are just zeros
+kernel void compute_loss_to_input_derivatives(
+    global float* loss_to_input_derivatives,
+
+    int indices_amount
+) {
+    int token_index = get_global_id(0);
+
+    if (token_index >= indices_amount) {
+        return;
+    }
+
+    loss_to_input_derivatives[token_index] = 0.0f;
+}
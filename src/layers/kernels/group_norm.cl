@@ -0,0 +1,195 @@
+// the inputs of each sample are separated into groups of `group_volume` contiguous inputs that are
+// normalized together, and each `parameter_volume` contiguous inputs share the same gamma and beta
+
+kernel void compute_group_statistics(
+    global float* inputs,
+
+    global float* means,
+    global float* variances,
+
+    int group_volume,
+    int groups_amount,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int group_index = get_global_id(1);
+
+    if (group_index >= groups_amount) {
+        return;
+    }
+
+    int group_start = (sample_index * groups_amount + group_index) * group_volume;
+
+    float mean = 0.0f;
+
+    for (int i = 0; i < group_volume; i++) {
+        mean += (float)inputs[group_start + i];
+    }
+
+    mean /= (float)group_volume;
+
+    float variance = 0.0f;
+
+    for (int i = 0; i < group_volume; i++) {
+        float deviation = (float)inputs[group_start + i] - mean;
+
+        variance += deviation * deviation;
+    }
+
+    variance /= (float)group_volume;
+
+    means[sample_index * groups_amount + group_index] = mean;
+    variances[sample_index * groups_amount + group_index] = variance;
+}
+
+kernel void normalize(
+    global float* inputs,
+
+    global float* means,
+    global float* variances,
+
+    global float* gammas,
+    global float* betas,
+
+    global float* normalized_inputs,
+    global float* outputs,
+
+    float epsilon,
+
+    int group_volume,
+    int groups_amount,
+    int parameter_volume,
+
+    int inputs_amount,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int flat_input_index = sample_index * inputs_amount + input_index;
+    int statistics_index = sample_index * groups_amount + input_index / group_volume;
+    int parameter_index = input_index / parameter_volume;
+
+    float normalized_input = ((float)inputs[flat_input_index] - (float)means[statistics_index])
+        * rsqrt((float)variances[statistics_index] + epsilon);
+
+    normalized_inputs[flat_input_index] = normalized_input;
+    outputs[flat_input_index] = (float)gammas[parameter_index] * normalized_input
+        + (float)betas[parameter_index];
+}
+
+kernel void compute_gradients(
+    global float* loss_to_output_derivatives,
+    global float* normalized_inputs,
+
+    global float* gamma_gradients,
+    global float* beta_gradients,
+
+    int parameter_volume,
+    int parameters_amount,
+
+    int inputs_amount,
+    int samples_amount
+) {
+    int parameter_index = get_global_id(0);
+
+    if (parameter_index >= parameters_amount) {
+        return;
+    }
+
+    float gamma_gradient = 0.0f;
+    float beta_gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int parameter_start = sample_index * inputs_amount + parameter_index * parameter_volume;
+
+        for (int i = 0; i < parameter_volume; i++) {
+            float loss_to_output_derivative = (float)loss_to_output_derivatives[parameter_start + i];
+
+            gamma_gradient += loss_to_output_derivative * (float)normalized_inputs[parameter_start + i];
+            beta_gradient += loss_to_output_derivative;
+        }
+    }
+
+    gamma_gradients[parameter_index] = gamma_gradient / (float)samples_amount;
+    beta_gradients[parameter_index] = beta_gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_input_derivatives(
+    global float* loss_to_output_derivatives,
+    global float* normalized_inputs,
+
+    global float* variances,
+    global float* gammas,
+
+    global float* loss_to_input_derivatives,
+
+    float epsilon,
+
+    int group_volume,
+    int groups_amount,
+    int parameter_volume,
+
+    int inputs_amount,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_amount) {
+        return;
+    }
+
+    int group_index = input_index / group_volume;
+    int group_start = sample_index * inputs_amount + group_index * group_volume;
+
+    // the derivatives of the loss with respect to the normalized inputs
+    // averaged through the group, and the same but multiplied by the normalized inputs
+    float mean_derivative = 0.0f;
+    float mean_derivative_times_normalized_input = 0.0f;
+
+    for (int i = 0; i < group_volume; i++) {
+        int parameter_index = (group_index * group_volume + i) / parameter_volume;
+
+        float derivative = (float)loss_to_output_derivatives[group_start + i]
+            * (float)gammas[parameter_index];
+
+        mean_derivative += derivative;
+        mean_derivative_times_normalized_input += derivative * (float)normalized_inputs[group_start + i];
+    }
+
+    mean_derivative /= (float)group_volume;
+    mean_derivative_times_normalized_input /= (float)group_volume;
+
+    int flat_input_index = sample_index * inputs_amount + input_index;
+
+    float derivative = (float)loss_to_output_derivatives[flat_input_index]
+        * (float)gammas[input_index / parameter_volume];
+
+    loss_to_input_derivatives[flat_input_index] =
+        rsqrt((float)variances[sample_index * groups_amount + group_index] + epsilon) * (
+            derivative
+            - mean_derivative
+            - (float)normalized_inputs[flat_input_index] * mean_derivative_times_normalized_input
+        );
+}
@@ -0,0 +1,116 @@
+// the same hash based generator of uniformly distributed numbers in the range [0, 1)
+// that is used for the mask of the Dropout
+float random_uniform(uint seed, uint index) {
+    uint state = seed ^ (index * 747796405u + 2891336453u);
+    state = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    state = (state >> 22u) ^ state;
+
+    return (float)state / 4294967296.0f;
+}
+
+// turns two uniformly distributed numbers into a normally distributed one with the Box-Muller
+// transform, the first one can't be zero because of the logarithm
+float random_normal(uint seed, uint index) {
+    float u1 = max(random_uniform(seed, 2u * index), 1e-7f);
+    float u2 = random_uniform(seed, 2u * index + 1u);
+
+    return sqrt(-2.0f * log(u1)) * cos(2.0f * M_PI_F * u2);
+}
+
+kernel void generate_normal_noise(
+    global float* noise,
+
+    float std_dev,
+    uint seed,
+
+    int count
+) {
+    int index = get_global_id(0);
+
+    if (index >= count) {
+        return;
+    }
+
+    noise[index] = random_normal(seed, (uint)index) * std_dev;
+}
+
+// the inputs of each sample are all of the means followed by all of the log variances
+kernel void sample(
+    global float* inputs,
+    global float* noise,
+
+    global float* outputs,
+
+    int latent_size,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int latent_index = get_global_id(1);
+
+    if (latent_index >= latent_size) {
+        return;
+    }
+
+    int inputs_start = sample_index * latent_size * 2;
+    int output_index = sample_index * latent_size + latent_index;
+
+    float mean = (float)inputs[inputs_start + latent_index];
+    float log_variance = (float)inputs[inputs_start + latent_size + latent_index];
+
+    outputs[output_index] = mean + exp(log_variance / 2.0f) * (float)noise[output_index];
+}
+
+// the KL divergence between the sampled normal distribution and the standard normal
+// distribution is -0.5 * sum(1 + log_variance - mean^2 - exp(log_variance)), and its
+// derivatives are added with a weight as a regularization
+kernel void compute_sampling_loss_to_input_derivatives(
+    global float* inputs,
+    global float* noise,
+    global float* loss_to_output_derivatives,
+
+    global float* loss_to_input_derivatives,
+
+    float kl_weight,
+
+    int latent_size,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= latent_size * 2) {
+        return;
+    }
+
+    int latent_index = input_index % latent_size;
+
+    int inputs_start = sample_index * latent_size * 2;
+    int output_index = sample_index * latent_size + latent_index;
+
+    float loss_to_output_derivative = (float)loss_to_output_derivatives[output_index];
+
+    float derivative;
+    if (input_index < latent_size) {
+        float mean = (float)inputs[inputs_start + latent_index];
+
+        derivative = loss_to_output_derivative + kl_weight * mean;
+    } else {
+        float log_variance = (float)inputs[inputs_start + latent_size + latent_index];
+        float std_dev = exp(log_variance / 2.0f);
+
+        derivative = loss_to_output_derivative * (float)noise[output_index] * std_dev / 2.0f
+            + kl_weight * (std_dev * std_dev - 1.0f) / 2.0f;
+    }
+
+    loss_to_input_derivatives[inputs_start + input_index] = derivative;
+}
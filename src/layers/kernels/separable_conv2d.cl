@@ -0,0 +1,409 @@
+// the depthwise outputs have the same layout as the outputs, but with one channel per input
+// channel instead of one per filter, since each channel has its own filter
+
+// gets the position of the pixel of the image that a certain pixel of the filter
+// is on top of for a certain output pixel considering the stride, padding and dilation
+// of the convolution, which can be outside of the image in case of padding
+int get_input_position(
+    int output_position,
+    int filter_position,
+
+    int stride,
+    int padding,
+    int dilation
+) {
+    return output_position * stride - padding + filter_position * dilation;
+}
+
+// gets the position of the output pixel for which a certain pixel of the filter was on top
+// of the input pixel, or -1 in case there is no such output pixel
+int get_output_position(
+    int input_position,
+    int filter_position,
+
+    int stride,
+    int padding,
+    int dilation,
+
+    int output_size
+) {
+    int strided_position = input_position + padding - filter_position * dilation;
+
+    if (strided_position < 0 || strided_position % stride != 0) {
+        return -1;
+    }
+
+    int output_position = strided_position / stride;
+
+    if (output_position >= output_size) {
+        return -1;
+    }
+
+    return output_position;
+}
+
+kernel void convolute_depthwise(
+    global float* inputs,
+    global float* depthwise_weights,
+
+    global float* depthwise_outputs,
+
+    int inputs_width,
+    int inputs_height,
+    int channels,
+
+    int outputs_width,
+    int outputs_height,
+
+    int filter_width,
+    int filter_height,
+
+    int stride_x,
+    int stride_y,
+    int padding_x,
+    int padding_y,
+    int dilation_x,
+    int dilation_y,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int outputs_volume = outputs_width * outputs_height;
+
+    // the index of the output pixel considering all of the channels
+    int output_index = get_global_id(1);
+
+    if (output_index >= outputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = output_index / outputs_volume;
+    int pixel_index = output_index % outputs_volume;
+
+    int output_x = pixel_index % outputs_width;
+    int output_y = pixel_index / outputs_width;
+
+    int inputs_start = (sample_index * channels + channel_index) * inputs_width * inputs_height;
+    int filter_start = channel_index * filter_width * filter_height;
+
+    float output = 0.0f;
+
+    for (int filter_y = 0; filter_y < filter_height; filter_y++) {
+        int input_y = get_input_position(output_y, filter_y, stride_y, padding_y, dilation_y);
+
+        // the pixels that fall into the padding are just zeros
+        if (input_y < 0 || input_y >= inputs_height) {
+            continue;
+        }
+
+        for (int filter_x = 0; filter_x < filter_width; filter_x++) {
+            int input_x = get_input_position(output_x, filter_x, stride_x, padding_x, dilation_x);
+
+            if (input_x < 0 || input_x >= inputs_width) {
+                continue;
+            }
+
+            output += (float)inputs[inputs_start + input_y * inputs_width + input_x]
+                * (float)depthwise_weights[filter_start + filter_y * filter_width + filter_x];
+        }
+    }
+
+    depthwise_outputs[sample_index * outputs_volume * channels + output_index] = output;
+}
+
+kernel void convolute_pointwise(
+    global float* depthwise_outputs,
+    global float* pointwise_weights,
+    global float* biases,
+
+    global float* outputs,
+
+    int channels,
+    int outputs_volume,
+    int filters_amount,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the output pixel considering all of the filters
+    int output_index = get_global_id(1);
+
+    if (output_index >= outputs_volume * filters_amount) {
+        return;
+    }
+
+    int filter_index = output_index / outputs_volume;
+    int pixel_index = output_index % outputs_volume;
+
+    float output = (float)biases[filter_index];
+
+    for (int channel_index = 0; channel_index < channels; channel_index++) {
+        output += (float)pointwise_weights[filter_index * channels + channel_index]
+            * (float)depthwise_outputs[(sample_index * channels + channel_index) * outputs_volume + pixel_index];
+    }
+
+    outputs[sample_index * outputs_volume * filters_amount + output_index] = output;
+}
+
+kernel void compute_pointwise_weights_gradients(
+    global float* depthwise_outputs,
+    global float* loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int channels,
+    int outputs_volume,
+    int filters_amount,
+
+    int samples_amount
+) {
+    int filter_index = get_global_id(0);
+
+    if (filter_index >= filters_amount) {
+        return;
+    }
+
+    int channel_index = get_global_id(1);
+
+    if (channel_index >= channels) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int depthwise_start = (sample_index * channels + channel_index) * outputs_volume;
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_volume;
+
+        for (int pixel_index = 0; pixel_index < outputs_volume; pixel_index++) {
+            gradient += (float)depthwise_outputs[depthwise_start + pixel_index]
+                * (float)loss_to_output_derivatives[outputs_start + pixel_index];
+        }
+    }
+
+    gradients[filter_index * channels + channel_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_biases_gradients(
+    global float* loss_to_output_derivatives,
+
+    global float* gradients,
+
+    int outputs_volume,
+    int filters_amount,
+
+    int samples_amount
+) {
+    int filter_index = get_global_id(0);
+
+    if (filter_index >= filters_amount) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int outputs_start = (sample_index * filters_amount + filter_index) * outputs_volume;
+
+        for (int pixel_index = 0; pixel_index < outputs_volume; pixel_index++) {
+            gradient += (float)loss_to_output_derivatives[outputs_start + pixel_index];
+        }
+    }
+
+    gradients[filter_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_depthwise_outputs_derivatives(
+    global float* pointwise_weights,
+    global float* loss_to_output_derivatives,
+
+    global float* loss_to_depthwise_outputs_derivatives,
+
+    int channels,
+    int outputs_volume,
+    int filters_amount,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the depthwise output pixel considering all of the channels
+    int depthwise_index = get_global_id(1);
+
+    if (depthwise_index >= outputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = depthwise_index / outputs_volume;
+    int pixel_index = depthwise_index % outputs_volume;
+
+    float derivative = 0.0f;
+
+    for (int filter_index = 0; filter_index < filters_amount; filter_index++) {
+        derivative += (float)pointwise_weights[filter_index * channels + channel_index]
+            * (float)loss_to_output_derivatives[(sample_index * filters_amount + filter_index) * outputs_volume + pixel_index];
+    }
+
+    loss_to_depthwise_outputs_derivatives[sample_index * outputs_volume * channels + depthwise_index] =
+        derivative;
+}
+
+kernel void compute_depthwise_weights_gradients(
+    global float* inputs,
+    global float* loss_to_depthwise_outputs_derivatives,
+
+    global float* gradients,
+
+    int inputs_width,
+    int inputs_height,
+    int channels,
+
+    int outputs_width,
+    int outputs_height,
+
+    int filter_width,
+    int filter_height,
+
+    int stride_x,
+    int stride_y,
+    int padding_x,
+    int padding_y,
+    int dilation_x,
+    int dilation_y,
+
+    int samples_amount
+) {
+    int channel_index = get_global_id(0);
+
+    if (channel_index >= channels) {
+        return;
+    }
+
+    int filter_volume = filter_width * filter_height;
+
+    int filter_pixel_index = get_global_id(1);
+
+    if (filter_pixel_index >= filter_volume) {
+        return;
+    }
+
+    int filter_x = filter_pixel_index % filter_width;
+    int filter_y = filter_pixel_index / filter_width;
+
+    int inputs_volume = inputs_width * inputs_height;
+    int outputs_volume = outputs_width * outputs_height;
+
+    float gradient = 0.0f;
+
+    for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+        int inputs_start = (sample_index * channels + channel_index) * inputs_volume;
+        int depthwise_start = (sample_index * channels + channel_index) * outputs_volume;
+
+        for (int output_y = 0; output_y < outputs_height; output_y++) {
+            int input_y = get_input_position(output_y, filter_y, stride_y, padding_y, dilation_y);
+
+            if (input_y < 0 || input_y >= inputs_height) {
+                continue;
+            }
+
+            for (int output_x = 0; output_x < outputs_width; output_x++) {
+                int input_x = get_input_position(output_x, filter_x, stride_x, padding_x, dilation_x);
+
+                if (input_x < 0 || input_x >= inputs_width) {
+                    continue;
+                }
+
+                gradient += (float)inputs[inputs_start + input_y * inputs_width + input_x]
+                    * (float)loss_to_depthwise_outputs_derivatives[depthwise_start + output_y * outputs_width + output_x];
+            }
+        }
+    }
+
+    gradients[channel_index * filter_volume + filter_pixel_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_input_derivatives(
+    global float* depthwise_weights,
+    global float* loss_to_depthwise_outputs_derivatives,
+
+    global float* loss_to_input_derivatives,
+
+    int inputs_width,
+    int inputs_height,
+    int channels,
+
+    int outputs_width,
+    int outputs_height,
+
+    int filter_width,
+    int filter_height,
+
+    int stride_x,
+    int stride_y,
+    int padding_x,
+    int padding_y,
+    int dilation_x,
+    int dilation_y,
+
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int inputs_volume = inputs_width * inputs_height;
+
+    // the index of the input pixel considering all of the channels
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = input_index / inputs_volume;
+    int pixel_index = input_index % inputs_volume;
+
+    int input_x = pixel_index % inputs_width;
+    int input_y = pixel_index / inputs_width;
+
+    int depthwise_start = (sample_index * channels + channel_index) * outputs_width * outputs_height;
+    int filter_start = channel_index * filter_width * filter_height;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int filter_y = 0; filter_y < filter_height; filter_y++) {
+        int output_y = get_output_position(input_y, filter_y, stride_y, padding_y, dilation_y, outputs_height);
+        if (output_y < 0) {
+            continue;
+        }
+
+        for (int filter_x = 0; filter_x < filter_width; filter_x++) {
+            int output_x = get_output_position(input_x, filter_x, stride_x, padding_x, dilation_x, outputs_width);
+            if (output_x < 0) {
+                continue;
+            }
+
+            loss_to_input_derivative += (float)depthwise_weights[filter_start + filter_y * filter_width + filter_x]
+                * (float)loss_to_depthwise_outputs_derivatives[depthwise_start + output_y * outputs_width + output_x];
+        }
+    }
+
+    loss_to_input_derivatives[sample_index * inputs_volume * channels + input_index] =
+        loss_to_input_derivative;
+}
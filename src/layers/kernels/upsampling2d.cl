@@ -0,0 +1,270 @@
+// gets the two input pixels that are interpolated into a certain output pixel along one of the
+// axes and how much of the second one goes into it, using the centers of the pixels so that the
+// upsampled images are not shifted
+void get_bilinear_neighbours(
+    int output_position,
+    int scale,
+    int inputs_size,
+
+    int* first_position,
+    int* second_position,
+    float* second_weight
+) {
+    float position = ((float)output_position + 0.5f) / (float)scale - 0.5f;
+    position = max(position, 0.0f);
+
+    *first_position = min((int)floor(position), inputs_size - 1);
+    *second_position = min(*first_position + 1, inputs_size - 1);
+    *second_weight = position - (float)(*first_position);
+}
+
+// the weight that a certain input pixel has on a certain output pixel along one of the axes
+float get_bilinear_weight(
+    int input_position,
+    int output_position,
+    int scale,
+    int inputs_size
+) {
+    int first_position;
+    int second_position;
+    float second_weight;
+
+    get_bilinear_neighbours(
+        output_position, scale, inputs_size,
+        &first_position, &second_position, &second_weight
+    );
+
+    float weight = 0.0f;
+
+    // both neighbours are the same pixel at the borders of the image
+    if (first_position == input_position) {
+        weight += 1.0f - second_weight;
+    }
+
+    if (second_position == input_position) {
+        weight += second_weight;
+    }
+
+    return weight;
+}
+
+kernel void propagate_nearest(
+    global float* inputs,
+
+    global float* outputs,
+
+    int inputs_width,
+    int inputs_volume,
+
+    int outputs_width,
+    int outputs_volume,
+
+    int scale_x,
+    int scale_y,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the output considering all of the channels
+    int output_index = get_global_id(1);
+
+    if (output_index >= outputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = output_index / outputs_volume;
+    int channel_output_index = output_index % outputs_volume;
+
+    int input_x = (channel_output_index % outputs_width) / scale_x;
+    int input_y = (channel_output_index / outputs_width) / scale_y;
+
+    int inputs_start = (sample_index * channels + channel_index) * inputs_volume;
+
+    outputs[sample_index * outputs_volume * channels + output_index] =
+        (float)inputs[inputs_start + input_y * inputs_width + input_x];
+}
+
+kernel void back_propagate_nearest(
+    global float* loss_to_output_derivatives,
+
+    global float* loss_to_input_derivatives,
+
+    int inputs_width,
+    int inputs_volume,
+
+    int outputs_width,
+    int outputs_volume,
+
+    int scale_x,
+    int scale_y,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the input considering all of the channels
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = input_index / inputs_volume;
+    int channel_input_index = input_index % inputs_volume;
+
+    int input_x = channel_input_index % inputs_width;
+    int input_y = channel_input_index / inputs_width;
+
+    int outputs_start = (sample_index * channels + channel_index) * outputs_volume;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int output_y = input_y * scale_y; output_y < (input_y + 1) * scale_y; output_y++) {
+        for (int output_x = input_x * scale_x; output_x < (input_x + 1) * scale_x; output_x++) {
+            loss_to_input_derivative += (float)loss_to_output_derivatives[outputs_start + output_y * outputs_width + output_x];
+        }
+    }
+
+    loss_to_input_derivatives[sample_index * inputs_volume * channels + input_index] =
+        loss_to_input_derivative;
+}
+
+kernel void propagate_bilinear(
+    global float* inputs,
+
+    global float* outputs,
+
+    int inputs_width,
+    int inputs_height,
+
+    int outputs_width,
+    int outputs_height,
+
+    int scale_x,
+    int scale_y,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int outputs_volume = outputs_width * outputs_height;
+
+    // the index of the output considering all of the channels
+    int output_index = get_global_id(1);
+
+    if (output_index >= outputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = output_index / outputs_volume;
+    int channel_output_index = output_index % outputs_volume;
+
+    int left_x;
+    int right_x;
+    float right_weight;
+    get_bilinear_neighbours(
+        channel_output_index % outputs_width, scale_x, inputs_width,
+        &left_x, &right_x, &right_weight
+    );
+
+    int top_y;
+    int bottom_y;
+    float bottom_weight;
+    get_bilinear_neighbours(
+        channel_output_index / outputs_width, scale_y, inputs_height,
+        &top_y, &bottom_y, &bottom_weight
+    );
+
+    int inputs_start = (sample_index * channels + channel_index) * inputs_width * inputs_height;
+
+    float top = (float)inputs[inputs_start + top_y * inputs_width + left_x] * (1.0f - right_weight)
+        + (float)inputs[inputs_start + top_y * inputs_width + right_x] * right_weight;
+    float bottom = (float)inputs[inputs_start + bottom_y * inputs_width + left_x] * (1.0f - right_weight)
+        + (float)inputs[inputs_start + bottom_y * inputs_width + right_x] * right_weight;
+
+    outputs[sample_index * outputs_volume * channels + output_index] =
+        top * (1.0f - bottom_weight) + bottom * bottom_weight;
+}
+
+kernel void back_propagate_bilinear(
+    global float* loss_to_output_derivatives,
+
+    global float* loss_to_input_derivatives,
+
+    int inputs_width,
+    int inputs_height,
+
+    int outputs_width,
+    int outputs_height,
+
+    int scale_x,
+    int scale_y,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int inputs_volume = inputs_width * inputs_height;
+
+    // the index of the input considering all of the channels
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = input_index / inputs_volume;
+    int channel_input_index = input_index % inputs_volume;
+
+    int input_x = channel_input_index % inputs_width;
+    int input_y = channel_input_index / inputs_width;
+
+    // only the outputs that are between the neighbours of this input can be interpolated from it
+    int min_output_x = max((input_x - 1) * scale_x, 0);
+    int max_output_x = min((input_x + 2) * scale_x, outputs_width);
+    int min_output_y = max((input_y - 1) * scale_y, 0);
+    int max_output_y = min((input_y + 2) * scale_y, outputs_height);
+
+    int outputs_start = (sample_index * channels + channel_index) * outputs_width * outputs_height;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int output_y = min_output_y; output_y < max_output_y; output_y++) {
+        float weight_y = get_bilinear_weight(input_y, output_y, scale_y, inputs_height);
+
+        if (weight_y == 0.0f) {
+            continue;
+        }
+
+        for (int output_x = min_output_x; output_x < max_output_x; output_x++) {
+            float weight_x = get_bilinear_weight(input_x, output_x, scale_x, inputs_width);
+
+            loss_to_input_derivative += weight_x * weight_y
+                * (float)loss_to_output_derivatives[outputs_start + output_y * outputs_width + output_x];
+        }
+    }
+
+    loss_to_input_derivatives[sample_index * inputs_volume * channels + input_index] =
+        loss_to_input_derivative;
+}
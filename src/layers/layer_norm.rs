@@ -0,0 +1,481 @@
+//! The module that defines the LayerNorm layer.
+
+use std::collections::HashMap;
+
+use opencl3::{device::cl_float, memory::Buffer};
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{BufferLike, BufferOperations, InplaceBufferOperations},
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    group_norm::{
+        compute_gradients_in_groups, compute_loss_to_input_derivatives_in_groups,
+        propagate_in_groups, GroupNormalizationShape,
+    },
+    initializers::{ConstantInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+#[derive(Debug, Savefile)]
+/// A layer that normalizes all of the inputs of each sample using the mean and variance of that
+/// same sample, then scales and shifts every input with the trainable **gamma** and **beta**
+/// parameters.
+///
+/// Since the statistics are computed per sample, this works the same way while training and
+/// predicting and does not depend on the batch size, so it works even with only one sample.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::LayerNorm;
+///
+/// let my_layer: LayerNorm = LayerNorm::new_raw(100);
+/// ```
+pub struct LayerNorm<'a> {
+    /// The amount of inputs (and outputs) this LayerNorm has.
+    pub inputs_amount: usize,
+
+    /// A small number added to the variance to avoid dividing by zero.
+    pub epsilon: f32,
+
+    /// The factors by which each input is multiplied after being normalized, stored in the CPU.
+    pub gamma: Vec<f32>,
+    /// The numbers added to each input after scaling them, stored in the CPU.
+    pub beta: Vec<f32>,
+
+    /// The initializers that will generate the initial parameters for the LayerNorm (gamma and
+    /// beta).
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the gamma of this LayerNorm.
+    pub gamma_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the beta of this LayerNorm.
+    pub beta_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this LayerNorm.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The inputs after being normalized in the last forward pass, before gamma and beta.
+    pub last_normalized_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The variance of each sample in the last forward pass.
+    pub last_variances_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this LayerNorm.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> LayerNorm<'a> {
+    /// Creates a raw version of the LayerNorm layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(inputs_amount: usize) -> LayerNorm<'a> {
+        let mut initializers = HashMap::with_capacity(2);
+        initializers.insert("gamma".to_string(), ConstantInitializer::new(1.0).into());
+        initializers.insert("beta".to_string(), ConstantInitializer::new(0.0).into());
+
+        LayerNorm {
+            inputs_amount,
+
+            epsilon: 0.001,
+
+            gamma: Vec::default(),
+            beta: Vec::default(),
+
+            initializers,
+
+            gamma_buffer: None,
+            beta_buffer: None,
+
+            last_inputs_buffer: None,
+            last_normalized_inputs_buffer: None,
+            last_variances_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the LayerNorm layer, to be used with a Model.
+    pub fn new(inputs_amount: usize) -> ModelLayer<'a> {
+        Self::new_raw(inputs_amount).into()
+    }
+
+    /// Sets the small number that is added to the variance to avoid divisions by zero, which
+    /// is **0.001** by default.
+    pub fn set_epsilon(mut self, epsilon: f32) -> Self {
+        self.epsilon = epsilon;
+
+        self
+    }
+
+    fn get_shape(&self) -> GroupNormalizationShape {
+        GroupNormalizationShape {
+            inputs_amount: self.inputs_amount,
+            groups_amount: 1,
+            parameter_volume: 1,
+        }
+    }
+}
+
+impl<'a> Layer<'a> for LayerNorm<'a> {
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "gamma" => Some(self.gamma.to_vec()),
+            "beta" => Some(self.beta.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.get_inputs_amount()
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.gamma_buffer.is_some() {
+            drop(self.gamma_buffer.as_ref().unwrap());
+        }
+
+        if self.beta_buffer.is_some() {
+            drop(self.beta_buffer.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_normalized_inputs_buffer.is_some() {
+            drop(self.last_normalized_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_variances_buffer.is_some() {
+            drop(self.last_variances_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.gamma_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "gamma_buffer".to_string(),
+            });
+        }
+
+        if self.beta_buffer.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "beta_buffer".to_string(),
+            });
+        }
+
+        self.gamma = Vec::<f32>::from_buffer(self.gamma_buffer.as_ref().unwrap(), false, state)?;
+        self.beta = Vec::<f32>::from_buffer(self.beta_buffer.as_ref().unwrap(), false, state)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.gamma.is_empty() {
+            if let Some(initializer) = self.initializers.get("gamma") {
+                self.gamma = initializer.initialize_1d(self.inputs_amount, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer("gamma"));
+            }
+        }
+
+        if self.beta.is_empty() {
+            if let Some(initializer) = self.initializers.get("beta") {
+                self.beta = initializer.initialize_1d(self.inputs_amount, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer("beta"));
+            }
+        }
+
+        self.gamma_buffer = Some(self.gamma.to_buffer(false, opencl_state)?);
+        self.beta_buffer = Some(self.beta.to_buffer(false, opencl_state)?);
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        let (normalized_inputs_buffer, variances_buffer, outputs_buffer) = propagate_in_groups(
+            inputs,
+            self.gamma_buffer.as_ref().unwrap(),
+            self.beta_buffer.as_ref().unwrap(),
+            self.epsilon,
+            self.get_shape(),
+            state,
+        )?;
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+        self.last_normalized_inputs_buffer = Some(normalized_inputs_buffer);
+        self.last_variances_buffer = Some(variances_buffer);
+        self.last_outputs_buffer = Some(outputs_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        if self.last_normalized_inputs_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        compute_gradients_in_groups(
+            layer_output_to_error_derivative,
+            self.last_normalized_inputs_buffer.as_ref().unwrap(),
+            self.get_shape(),
+            self.opencl_state.unwrap(),
+        )
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.gamma_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "gamma".to_string(),
+            ));
+        }
+
+        if self.beta_buffer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "beta".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.gamma_buffer.as_mut().unwrap(),
+            "gamma".to_string(),
+            timestep,
+            layer_index,
+        )?;
+        optimizer.optimize_parameters(
+            self.beta_buffer.as_mut().unwrap(),
+            "beta".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != 2 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_index,
+            timestep,
+            state,
+        )?;
+
+        let gamma_buffer = self.gamma_buffer.as_mut().unwrap();
+        let beta_buffer = self.beta_buffer.as_mut().unwrap();
+        gamma_buffer.subtract_inplc(&update_vectors[0], state)?;
+        beta_buffer.subtract_inplc(&update_vectors[1], state)?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        if self.last_normalized_inputs_buffer.is_none() || self.last_variances_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        compute_loss_to_input_derivatives_in_groups(
+            layer_output_to_error_derivative,
+            self.last_normalized_inputs_buffer.as_ref().unwrap(),
+            self.last_variances_buffer.as_ref().unwrap(),
+            self.gamma_buffer.as_ref().unwrap(),
+            self.epsilon,
+            self.get_shape(),
+            self.opencl_state.unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod layer_norm_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::LayerNorm;
+
+    #[test]
+    fn should_normalize_each_sample_and_compute_derivatives_correctly() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.7,
+            -0.3, 0.4, 0.9,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.2, -0.4, 0.6,
+            0.1, -0.3, 0.5,
+        ];
+
+        let expected_outputs = vec![
+            -1.8885, -0.0674, 1.0606,
+            -1.8263, -0.1324, 1.149,
+        ];
+        let expected_gamma_gradients = vec![-0.1968, -0.0733, 0.6054];
+        let expected_beta_gradients = vec![0.15, -0.35, 0.55];
+        let expected_loss_to_input_derivatives = vec![
+            0.5913, -1.7886, 1.1973,
+            0.2801, -0.6751, 0.3951,
+        ];
+
+        let mut layer = LayerNorm::new_raw(3);
+        layer.gamma = vec![1.5, 0.5, 1.0];
+        layer.beta = vec![0.1, -0.2, 0.0];
+        layer.init(&opencl_state).expect("unable to init LayerNorm");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the LayerNorm");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the LayerNorm");
+        let gamma_gradients = Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+            .expect("unable to read the gamma gradients buffer");
+        let beta_gradients = Vec::<f32>::from_buffer(&gradients[1].value, false, &opencl_state)
+            .expect("unable to read the beta gradients buffer");
+
+        assert_approx_equal_distance(&gamma_gradients, &expected_gamma_gradients, 0.01);
+        assert_approx_equal_distance(&beta_gradients, &expected_beta_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the LayerNorm");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 use intricate_macros::FromForAllUnnamedVariants;
 use opencl3::{device::cl_float, error_codes::ClError, memory::Buffer};
+use savefile::SavefileError;
 
 use crate::{
     optimizers::{OptimizationError, Optimizer},
@@ -15,21 +16,61 @@ use crate::{
 };
 
 pub mod activations;
+pub mod attention;
+pub mod batch_norm;
+pub mod conv1d;
 pub mod conv2d;
+pub mod conv2d_transpose;
 pub mod dense;
+pub mod dropout;
+pub mod embedding;
+pub mod group_norm;
 pub mod initializers;
-
+pub mod layer_norm;
+pub mod noise;
+pub mod pooling;
+pub mod recurrent;
+pub mod reshape;
+pub mod separable_conv2d;
+pub mod time_distributed;
+pub mod upsampling2d;
+
+pub use attention::MultiHeadAttention;
+pub use batch_norm::BatchNorm;
 pub use dense::Dense;
-pub use conv2d::Conv2D;
-
-use self::{activations::compile_activations, conv2d::compile_conv2d, dense::compile_dense, initializers::Initializer};
+pub use conv1d::{Conv1D, Conv1DPadding};
+pub use conv2d::{Conv2D, Conv2DPadding};
+pub use conv2d_transpose::Conv2DTranspose;
+pub use dropout::Dropout;
+pub use embedding::Embedding;
+pub use group_norm::GroupNorm;
+pub use layer_norm::LayerNorm;
+pub use noise::{GaussianNoise, Sampling};
+pub use reshape::{Flatten, Reshape};
+pub use separable_conv2d::SeparableConv2D;
+pub use time_distributed::TimeDistributed;
+pub use upsampling2d::{Upsampling2D, UpsamplingInterpolation};
+
+use self::{activations::compile_activations, attention::compile_attention, batch_norm::compile_batch_norm, conv1d::compile_conv1d, conv2d::compile_conv2d, conv2d_transpose::compile_conv2d_transpose, dense::compile_dense, dropout::compile_dropout, embedding::compile_embedding, group_norm::compile_group_norm, initializers::Initializer, noise::compile_noise, pooling::compile_pooling, recurrent::compile_recurrent, separable_conv2d::compile_separable_conv2d, upsampling2d::compile_upsampling2d};
 
 pub(crate) fn compile_layers(
     opencl_state: &mut OpenCLState,
 ) -> Result<(), EnsureKernelsAndProgramError> {
     compile_dense(opencl_state)?;
     compile_activations(opencl_state)?;
+    compile_conv1d(opencl_state)?;
     compile_conv2d(opencl_state)?;
+    compile_conv2d_transpose(opencl_state)?;
+    compile_separable_conv2d(opencl_state)?;
+    compile_upsampling2d(opencl_state)?;
+    compile_pooling(opencl_state)?;
+    compile_dropout(opencl_state)?;
+    compile_noise(opencl_state)?;
+    compile_embedding(opencl_state)?;
+    compile_batch_norm(opencl_state)?;
+    compile_group_norm(opencl_state)?;
+    compile_recurrent(opencl_state)?;
+    compile_attention(opencl_state)?;
 
     Ok(())
 }
@@ -186,6 +227,8 @@ pub enum LayerLossToInputDifferentiationError {
     ProgramNotFound(ProgramNotFoundError),
     /// Happens when a kernel could not be found inside of the program.
     KernelNotFound(KernelNotFoundError),
+    /// Happens when a buffer operation goes wrong.
+    BufferOperation(BufferOperationError),
 
     /// Happens when there is a missing required parameter for this calculation
     MissingParameter(&'static str),
@@ -224,6 +267,47 @@ pub enum LayerInitializationError {
     MissingParameterInitializer(&'static str),
     /// Happens when there is no OpenCL Command Queue when needed.
     NoCommandQueue,
+    /// Happens when the amount of outputs of the layer at the index in a Model is not the amount
+    /// of inputs that the layer right after it expects, or when both of them have a shape and
+    /// the shapes are different.
+    LayersShapesDontMatch(usize),
+    /// Happens when an edge of a GraphModel, from the first node to the second one, does not go
+    /// into a node that comes after the one it goes from.
+    InvalidEdge(usize, usize),
+    /// Happens when the node at the index of a GraphModel is not the last one but has no edges
+    /// going out of it.
+    DisconnectedNode {
+        /// The index of the node in the GraphModel.
+        node_index: usize,
+    },
+    /// Happens when the layer at the index of a GraphModel has more than one node connected into
+    /// it.
+    TooManyNodesIntoLayer {
+        /// The index of the node in the GraphModel.
+        node_index: usize,
+    },
+    /// Happens when the merge node at the index of a GraphModel has no nodes connected into it.
+    NoNodesIntoMerge {
+        /// The index of the node in the GraphModel.
+        node_index: usize,
+    },
+    /// Happens when the layer at the index of a GraphModel has no nodes connected into it, so it
+    /// receives the inputs of the GraphModel, but does not take in the same inputs as the first
+    /// layer that receives them.
+    InputNodesShapesDontMatch {
+        /// The index of the node in the GraphModel.
+        node_index: usize,
+    },
+    /// Happens when the amount of outputs of the first node of a GraphModel is not what the
+    /// second node it is connected into expects.
+    NodesShapesDontMatch(usize, usize),
+    /// Happens when the pool of a pooling layer is bigger than the images it receives.
+    PoolBiggerThanInputs,
+    /// Happens when the filter of a convolutional layer, after being dilated, is bigger than the
+    /// padded inputs it receives.
+    FilterBiggerThanInputs,
+    /// Happens when a layer that is saved inside of another layer can't be loaded back.
+    Savefile(SavefileError),
 }
 
 /// A trait implemented by Intricate that is implemented in every struct that represents a Model
@@ -277,6 +361,24 @@ pub trait Layer<'a> {
     /// inputs_amount and the outputs_amount because of its architechture.
     fn get_outputs_amount(&self) -> usize;
 
+    /// Gets the shape of the inputs of each sample, if this layer expects them to have a certain
+    /// shape, like `vec![channels, height, width]` for the layers that take in images.
+    ///
+    /// This is used by the Model to check that the shapes of layers next to each other agree
+    /// when both of them have a shape, and is `None` by default.
+    fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+        None
+    }
+
+    /// Gets the shape of the outputs of each sample, if this layer gives them out in a certain
+    /// shape, like `vec![channels, height, width]` for the layers that give out images.
+    ///
+    /// This is used by the Model to check that the shapes of layers next to each other agree
+    /// when both of them have a shape, and is `None` by default.
+    fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+        None
+    }
+
     /// Cleans up all of the buffers saved up in the Device
     /// for this layer
     fn clean_up_gpu_state(&mut self) -> ();
@@ -396,4 +498,21 @@ pub trait Layer<'a> {
         &self,
         layer_loss_to_output_derivatives: &Buffer<cl_float>,
     ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError>;
+
+    /// Sets weather or not the layer is currently being trained, this is called by the Model with
+    /// **true** before fitting and with **false** after it is done.
+    ///
+    /// Most layers behave exactly the same way in both modes so this does nothing by default,
+    /// but layers such as Dropout only change their inputs while training and just pass them
+    /// forward when predicting.
+    fn set_training_mode(&mut self, _is_training: bool) -> () {}
+
+    /// Sets weather or not the forward passes made while training should update the statistics
+    /// that the layer keeps for predicting, such as the running mean and variance of a
+    /// BatchNorm.
+    ///
+    /// This is turned off by the Model while it forward passes a batch again just to compute the
+    /// loss and accuracy of a training step, so that the statistics are only updated once per
+    /// step, and does nothing by default.
+    fn set_updating_running_statistics(&mut self, _should_update: bool) -> () {}
 }
\ No newline at end of file
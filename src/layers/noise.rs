@@ -0,0 +1,708 @@
+//! The module that defines the layers that add normally distributed noise, the GaussianNoise and
+//! the Sampling.
+
+use std::mem;
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+    types::cl_uint,
+};
+use rand::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{
+        opencl::{empty_buffer, ensure_program, BufferOperations, EnsureKernelsAndProgramError},
+        OpenCLState,
+    },
+};
+
+use super::{
+    initializers::Initializer, Gradient, Layer, LayerGradientApplicationError,
+    LayerGradientComputationError, LayerInitializationError,
+    LayerLossToInputDifferentiationError, LayerPropagationError, ParametersOptimizationError,
+};
+
+const NOISE_PROGRAM_NAME: &str = "NOISE";
+const PROGRAM_SOURCE: &str = include_str!("kernels/noise.cl");
+
+const GENERATE_NORMAL_NOISE_KERNEL_NAME: &str = "generate_normal_noise";
+const SAMPLE_KERNEL_NAME: &str = "sample";
+const COMPUTE_SAMPLING_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME: &str =
+    "compute_sampling_loss_to_input_derivatives";
+
+pub(crate) fn compile_noise(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        GENERATE_NORMAL_NOISE_KERNEL_NAME.to_string(),
+        SAMPLE_KERNEL_NAME.to_string(),
+        COMPUTE_SAMPLING_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        NOISE_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+fn generate_normal_noise(
+    count: usize,
+    std_dev: f32,
+    opencl_state: &OpenCLState,
+) -> Result<Buffer<cl_float>, LayerPropagationError> {
+    if opencl_state.queues.is_empty() {
+        return Err(LayerPropagationError::NoCommandQueueFound);
+    }
+
+    let queue = opencl_state.queues.first().unwrap();
+
+    let noise_buffer = empty_buffer(count, CL_MEM_READ_WRITE, opencl_state)?;
+
+    let program = opencl_state.get_prgm(NOISE_PROGRAM_NAME)?;
+    let generate_normal_noise_kernel = program.get_krnl(GENERATE_NORMAL_NOISE_KERNEL_NAME)?;
+
+    let seed: cl_uint = thread_rng().gen();
+
+    ExecuteKernel::new(generate_normal_noise_kernel)
+        .set_arg(&noise_buffer)
+        .set_arg(&(std_dev as cl_float))
+        .set_arg(&seed)
+        .set_arg(&(count as cl_int))
+        .set_global_work_size(count)
+        .enqueue_nd_range(queue)?;
+
+    queue.finish()?;
+
+    Ok(noise_buffer)
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that adds normally distributed noise with a mean of zero to its inputs while the Model
+/// is being trained, which works as a regularization much like the Dropout does.
+///
+/// When the Model is predicting this layer just passes its inputs forward.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::GaussianNoise;
+///
+/// // this will make a layer that adds noise with a standard deviation of 0.1
+/// // to its 100 inputs while training
+/// let my_layer: GaussianNoise = GaussianNoise::new_raw(100, 0.1);
+/// ```
+pub struct GaussianNoise<'a> {
+    /// The amount of inputs this instance of GaussianNoise expects.
+    pub inputs_amount: usize,
+    /// The standard deviation of the noise that is added to the inputs.
+    pub std_dev: f32,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// Weather or not the Model this layer is in is currently being trained.
+    pub is_training: bool,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this GaussianNoise.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this GaussianNoise.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> GaussianNoise<'a> {
+    /// Creates a raw version of the GaussianNoise layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(inputs_amount: usize, std_dev: f32) -> GaussianNoise<'a> {
+        assert!(
+            std_dev >= 0.0,
+            "the standard deviation of a GaussianNoise can't be negative"
+        );
+
+        GaussianNoise {
+            inputs_amount,
+            std_dev,
+
+            is_training: false,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the GaussianNoise layer, to be used with a Model.
+    pub fn new(inputs_amount: usize, std_dev: f32) -> ModelLayer<'a> {
+        Self::new_raw(inputs_amount, std_dev).into()
+    }
+}
+
+impl<'a> Layer<'a> for GaussianNoise<'a> {
+    fn get_flattened_parameter_data(&self, _parameter: &str) -> Option<Vec<f32>> {
+        None
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, _parameter: &str) -> Option<&'b Initializer> {
+        None
+    }
+
+    fn set_initializer_for_parameter(
+        self,
+        _initializer: Initializer,
+        _parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.into()
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.inputs_amount
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        Ok(())
+    }
+
+    fn set_training_mode(&mut self, is_training: bool) -> () {
+        self.is_training = is_training;
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.inputs_amount != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        if !self.is_training {
+            self.last_outputs_buffer = Some(inputs.clone(state)?);
+
+            return Ok(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
+        let noise_buffer = generate_normal_noise(inputs_total_count, self.std_dev, state)?;
+
+        self.last_outputs_buffer = Some(inputs.add(&noise_buffer, state)?);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        _per_parameter_type_gradients: &[Gradient],
+        _optimizer: &mut dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        Ok(())
+    }
+
+    fn compute_gradients(
+        &self,
+        _layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        Ok(Vec::default())
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        _optimizer: &dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.inputs_amount != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        // the noise is just added so the derivatives don't change
+        Ok(layer_output_to_error_derivative.clone(state)?)
+    }
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that takes the means and the log variances of normal distributions and samples from
+/// them with `mean + exp(log_variance / 2) * noise`, where the noise comes from a standard normal
+/// distribution, which is the reparameterization trick used for variational autoencoders.
+///
+/// The inputs of each sample are all of the means followed by all of the log variances, so there
+/// are two times more inputs than outputs, and the derivatives flow back into both of them.
+///
+/// The KL divergence between the sampled distributions and the standard normal distribution can
+/// also be added to the loss, with a weight set by `set_kl_weight`, but it only changes the
+/// derivatives and is not added to the loss that the Model reports.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::Sampling;
+///
+/// // this will make a layer that takes the means and log variances of 16 distributions
+/// // and gives out 16 sampled numbers
+/// let my_layer: Sampling = Sampling::new_raw(16).set_kl_weight(1.0);
+/// ```
+pub struct Sampling<'a> {
+    /// The amount of distributions this Sampling samples from, which is the amount of outputs.
+    pub latent_size: usize,
+    /// How much of the KL divergence of the distributions is added to the loss.
+    pub kl_weight: f32,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this Sampling.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this Sampling.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The standard normal noise that was used in the last forward pass.
+    pub last_noise_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> Sampling<'a> {
+    /// Creates a raw version of the Sampling layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(latent_size: usize) -> Sampling<'a> {
+        Sampling {
+            latent_size,
+            kl_weight: 0.0,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+            last_noise_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the Sampling layer, to be used with a Model.
+    pub fn new(latent_size: usize) -> ModelLayer<'a> {
+        Self::new_raw(latent_size).into()
+    }
+
+    /// Sets how much of the KL divergence of the distributions is added to the loss, which is
+    /// zero by default.
+    pub fn set_kl_weight(mut self, kl_weight: f32) -> Self {
+        assert!(kl_weight >= 0.0, "the KL weight of a Sampling can't be negative");
+
+        self.kl_weight = kl_weight;
+        self
+    }
+}
+
+impl<'a> Layer<'a> for Sampling<'a> {
+    fn get_flattened_parameter_data(&self, _parameter: &str) -> Option<Vec<f32>> {
+        None
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, _parameter: &str) -> Option<&'b Initializer> {
+        None
+    }
+
+    fn set_initializer_for_parameter(
+        self,
+        _initializer: Initializer,
+        _parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.into()
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.latent_size * 2
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.latent_size
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_noise_buffer.is_some() {
+            drop(self.last_noise_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let noise_buffer = generate_normal_noise(samples_amount * self.latent_size, 1.0, state)?;
+
+        let outputs_buffer = empty_buffer(
+            samples_amount * self.latent_size,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(NOISE_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(program.get_krnl(SAMPLE_KERNEL_NAME)?)
+            .set_arg(inputs)
+            .set_arg(&noise_buffer)
+            .set_arg(&outputs_buffer)
+            .set_arg(&(self.latent_size as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.latent_size])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_noise_buffer = Some(noise_buffer);
+        self.last_outputs_buffer = Some(outputs_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        _per_parameter_type_gradients: &[Gradient],
+        _optimizer: &mut dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        Ok(())
+    }
+
+    fn compute_gradients(
+        &self,
+        _layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        Ok(Vec::default())
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        _optimizer: &dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        if self.last_inputs_buffer.is_none() || self.last_noise_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.latent_size != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.latent_size;
+
+        let loss_to_input_derivatives_buffer = empty_buffer(
+            samples_amount * self.get_inputs_amount(),
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(NOISE_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(
+            program.get_krnl(COMPUTE_SAMPLING_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME)?,
+        )
+        .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+        .set_arg(self.last_noise_buffer.as_ref().unwrap())
+        .set_arg(layer_output_to_error_derivative)
+        .set_arg(&loss_to_input_derivatives_buffer)
+        .set_arg(&(self.kl_weight as cl_float))
+        .set_arg(&(self.latent_size as cl_int))
+        .set_arg(&(samples_amount as cl_int))
+        .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+        .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod noise_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::{GaussianNoise, Sampling};
+
+    #[test]
+    fn should_add_noise_only_while_training() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs_amount = 1000;
+        let inputs = vec![1.0; inputs_amount * 2];
+
+        let mut layer = GaussianNoise::new_raw(inputs_amount, 0.5);
+        layer.init(&opencl_state).expect("unable to init GaussianNoise");
+
+        let inputs_buffer = inputs
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the inputs buffer");
+
+        let outputs_buffer = layer
+            .propagate(&inputs_buffer)
+            .expect("unable to propagate the GaussianNoise while predicting");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &inputs, 0.0001);
+
+        layer.set_training_mode(true);
+
+        let outputs_buffer = layer
+            .propagate(&inputs_buffer)
+            .expect("unable to propagate the GaussianNoise while training");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        let count = outputs.len() as f32;
+        let mean = outputs.iter().map(|output| output - 1.0).sum::<f32>() / count;
+        let std_dev = (outputs
+            .iter()
+            .map(|output| (output - 1.0 - mean).powi(2))
+            .sum::<f32>()
+            / count)
+            .sqrt();
+
+        assert!(mean.abs() < 0.05, "the mean of the noise was {}", mean);
+        assert!((std_dev - 0.5).abs() < 0.05, "the std dev of the noise was {}", std_dev);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&inputs_buffer)
+            .expect("unable to compute the loss to input derivatives");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives");
+
+        assert_approx_equal_distance(&loss_to_input_derivatives, &inputs, 0.0001);
+    }
+
+    #[test]
+    fn should_sample_and_differentiate_both_the_means_and_the_log_variances() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let latent_size = 2;
+        let kl_weight = 0.5;
+
+        let inputs: Vec<f32> = vec![
+            0.5, -1.0, 0.0, 1.0,
+            2.0, 0.0, -2.0, 0.5,
+        ];
+        let loss_to_output_derivatives: Vec<f32> = vec![
+            1.0, -0.5,
+            0.2, 2.0,
+        ];
+
+        let mut layer = Sampling::new_raw(latent_size).set_kl_weight(kl_weight);
+        layer.init(&opencl_state).expect("unable to init Sampling");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the Sampling");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        let noise = Vec::<f32>::from_buffer(
+            layer.last_noise_buffer.as_ref().unwrap(),
+            false,
+            &opencl_state,
+        )
+        .expect("unable to read the noise buffer");
+
+        let mut expected_outputs = Vec::with_capacity(outputs.len());
+        let mut expected_loss_to_input_derivatives = vec![0.0; inputs.len()];
+
+        for sample_index in 0..2 {
+            for latent_index in 0..latent_size {
+                let inputs_start = sample_index * latent_size * 2;
+                let output_index = sample_index * latent_size + latent_index;
+
+                let mean = inputs[inputs_start + latent_index];
+                let log_variance = inputs[inputs_start + latent_size + latent_index];
+                let epsilon = noise[output_index];
+                let derivative = loss_to_output_derivatives[output_index];
+                let std_dev = (log_variance / 2.0).exp();
+
+                expected_outputs.push(mean + std_dev * epsilon);
+
+                expected_loss_to_input_derivatives[inputs_start + latent_index] =
+                    derivative + kl_weight * mean;
+                expected_loss_to_input_derivatives[inputs_start + latent_size + latent_index] =
+                    derivative * epsilon * std_dev / 2.0
+                        + kl_weight * (std_dev * std_dev - 1.0) / 2.0;
+            }
+        }
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(
+                &loss_to_output_derivatives
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the loss to output derivatives buffer"),
+            )
+            .expect("unable to compute the loss to input derivatives");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
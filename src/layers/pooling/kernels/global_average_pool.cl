@@ -0,0 +1,59 @@
+kernel void propagate(
+    global float* inputs,
+    global float* outputs,
+
+    int inputs_volume,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int channel_index = get_global_id(1);
+
+    if (channel_index >= channels) {
+        return;
+    }
+
+    int inputs_start = (sample_index * channels + channel_index) * inputs_volume;
+
+    float inputs_sum = 0.0f;
+
+    for (int input_index = 0; input_index < inputs_volume; input_index++) {
+        inputs_sum += (float)inputs[inputs_start + input_index];
+    }
+
+    outputs[sample_index * channels + channel_index] = inputs_sum / (float)inputs_volume;
+}
+
+kernel void back_propagate(
+    global float* loss_to_output_derivatives,
+    global float* loss_to_input_derivatives,
+
+    int inputs_volume,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the input considering all of the channels
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = input_index / inputs_volume;
+
+    loss_to_input_derivatives[sample_index * inputs_volume * channels + input_index] = 
+        (float)loss_to_output_derivatives[sample_index * channels + channel_index] / (float)inputs_volume;
+}
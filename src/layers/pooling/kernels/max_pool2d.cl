@@ -0,0 +1,129 @@
+kernel void propagate(
+    global float* inputs,
+    global float* outputs,
+    global int* argmax_indices,
+
+    int inputs_width,
+    int inputs_volume,
+
+    int outputs_width,
+    int outputs_volume,
+
+    int pool_width,
+    int pool_height,
+
+    int stride_x,
+    int stride_y,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the output considering all of the channels
+    int output_index = get_global_id(1);
+
+    if (output_index >= outputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = output_index / outputs_volume;
+    int channel_output_index = output_index % outputs_volume;
+
+    int output_x = channel_output_index % outputs_width;
+    int output_y = channel_output_index / outputs_width;
+
+    int inputs_start = (sample_index * channels + channel_index) * inputs_volume;
+
+    int max_index = (output_y * stride_y) * inputs_width + output_x * stride_x;
+    float max_input = (float)inputs[inputs_start + max_index];
+
+    for (int pool_y = 0; pool_y < pool_height; pool_y++) {
+        int input_y = output_y * stride_y + pool_y;
+
+        for (int pool_x = 0; pool_x < pool_width; pool_x++) {
+            int input_x = output_x * stride_x + pool_x;
+            int input_index = input_y * inputs_width + input_x;
+
+            float input = (float)inputs[inputs_start + input_index];
+
+            if (input > max_input) {
+                max_input = input;
+                max_index = input_index;
+            }
+        }
+    }
+
+    int global_output_index = sample_index * outputs_volume * channels + output_index;
+
+    outputs[global_output_index] = max_input;
+    argmax_indices[global_output_index] = max_index;
+}
+
+kernel void back_propagate(
+    global float* loss_to_output_derivatives,
+    global int* argmax_indices,
+    global float* loss_to_input_derivatives,
+
+    int inputs_width,
+    int inputs_volume,
+
+    int outputs_width,
+    int outputs_height,
+    int outputs_volume,
+
+    int pool_width,
+    int pool_height,
+
+    int stride_x,
+    int stride_y,
+
+    int channels,
+    int samples_amount
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    // the index of the input considering all of the channels
+    int input_index = get_global_id(1);
+
+    if (input_index >= inputs_volume * channels) {
+        return;
+    }
+
+    int channel_index = input_index / inputs_volume;
+    int channel_input_index = input_index % inputs_volume;
+
+    int input_x = channel_input_index % inputs_width;
+    int input_y = channel_input_index / inputs_width;
+
+    // the range of outputs whose pools contain this input
+    int min_output_x = input_x < pool_width ? 0 : (input_x - pool_width) / stride_x + 1;
+    int min_output_y = input_y < pool_height ? 0 : (input_y - pool_height) / stride_y + 1;
+    int max_output_x = min(input_x / stride_x, outputs_width - 1);
+    int max_output_y = min(input_y / stride_y, outputs_height - 1);
+
+    int outputs_start = (sample_index * channels + channel_index) * outputs_volume;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int output_y = min_output_y; output_y <= max_output_y; output_y++) {
+        for (int output_x = min_output_x; output_x <= max_output_x; output_x++) {
+            int global_output_index = outputs_start + output_y * outputs_width + output_x;
+
+            // only the input that was the max of the pool gets the derivative
+            if (argmax_indices[global_output_index] == channel_input_index) {
+                loss_to_input_derivative += (float)loss_to_output_derivatives[global_output_index];
+            }
+        }
+    }
+
+    loss_to_input_derivatives[sample_index * inputs_volume * channels + input_index] = loss_to_input_derivative;
+}
@@ -0,0 +1,482 @@
+//! The module that contains the MaxPool2D layer.
+
+use std::{mem, ptr};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+
+use savefile_derive::Savefile;
+
+use crate::{
+    layers::{
+        initializers::Initializer, Gradient, Layer, LayerGradientApplicationError,
+        LayerGradientComputationError, LayerInitializationError,
+        LayerLossToInputDifferentiationError, LayerPropagationError, ParametersOptimizationError,
+        SyncDataError,
+    },
+    optimizers::Optimizer,
+    types::ModelLayer,
+    utils::{
+        opencl::{empty_buffer, ensure_program, BufferOperations, EnsureKernelsAndProgramError},
+        OpenCLState,
+    },
+};
+
+use super::get_pooled_size;
+
+const PROGRAM_NAME: &str = "MAX_POOL2D";
+const PROGRAM_SOURCE: &str = include_str!("kernels/max_pool2d.cl");
+const PROPAGATE_KERNEL_NAME: &str = "propagate";
+const BACK_PROPAGATE_KERNEL_NAME: &str = "back_propagate";
+
+pub(crate) fn compile_max_pool2d(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        PROPAGATE_KERNEL_NAME.to_string(),
+        BACK_PROPAGATE_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that downsamples each channel of its input images by taking only the largest pixel
+/// inside of each pool.
+///
+/// The inputs are expected to be in the same layout as the outputs of a Conv2D, that is, all of
+/// the pixels of the first channel, then all of the pixels of the second channel and so on.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::pooling::MaxPool2D;
+///
+/// // this will make a max pooling layer that halves 26x26 images with 8 channels
+/// let my_layer: MaxPool2D = MaxPool2D::new_raw((26, 26), 8, (2, 2));
+/// ```
+pub struct MaxPool2D<'a> {
+    /// The size of the inputs, width and height respectively.
+    pub inputs_size: (usize, usize),
+    /// The amount of channels that each input image has.
+    pub channels: usize,
+    /// The size of the pools, width and height respectively.
+    pub pool_size: (usize, usize),
+    /// How many pixels the pool moves at each step, horizontally and vertically respectively.
+    ///
+    /// By default this is the same as the pool size, so that the pools do not overlap.
+    pub stride: (usize, usize),
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this MaxPool2D.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this MaxPool2D.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The index of the largest pixel inside of its channel for each one of the outputs of the
+    /// last forward pass, used to route the derivatives back to the inputs.
+    pub last_argmax_indices_buffer: Option<Buffer<cl_int>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> MaxPool2D<'a> {
+    /// Creates a raw version of the MaxPool2D layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(
+        inputs_size: (usize, usize),
+        channels: usize,
+        pool_size: (usize, usize),
+    ) -> MaxPool2D<'a> {
+        MaxPool2D {
+            inputs_size,
+            channels,
+            pool_size,
+            stride: pool_size,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+            last_argmax_indices_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the MaxPool2D layer, to be used with a Model.
+    pub fn new(
+        inputs_size: (usize, usize),
+        channels: usize,
+        pool_size: (usize, usize),
+    ) -> ModelLayer<'a> {
+        Self::new_raw(inputs_size, channels, pool_size).into()
+    }
+
+    /// Sets how many pixels the pool moves at each step, horizontally and vertically
+    /// respectively, and returns the mutated Self.
+    pub fn set_stride(mut self, stride: (usize, usize)) -> Self {
+        assert!(stride.0 > 0 && stride.1 > 0, "the stride of a MaxPool2D must be at least 1");
+        self.stride = stride;
+
+        self
+    }
+
+    /// Gets the size of each one of the images that come out of the pooling, width and
+    /// height respectively.
+    pub fn get_outputs_size(&self) -> (usize, usize) {
+        get_pooled_size(self.inputs_size, self.pool_size, self.stride)
+    }
+}
+
+impl<'a> Layer<'a> for MaxPool2D<'a> {
+    fn get_flattened_parameter_data(&self, _parameter: &str) -> Option<Vec<f32>> {
+        None
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, _parameter: &str) -> Option<&'b Initializer> {
+        None
+    }
+
+    fn set_initializer_for_parameter(
+        self,
+        _initializer: Initializer,
+        _parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.into()
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.pool_size.0 > self.inputs_size.0 || self.pool_size.1 > self.inputs_size.1 {
+            return Err(LayerInitializationError::PoolBiggerThanInputs);
+        }
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_size.0 * self.inputs_size.1 * self.channels
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        outputs_width * outputs_height * self.channels
+    }
+
+    fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+        Some(vec![self.channels, self.inputs_size.1, self.inputs_size.0])
+    }
+
+    fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        Some(vec![self.channels, outputs_height, outputs_width])
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_argmax_indices_buffer.is_some() {
+            drop(self.last_argmax_indices_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        let outputs_volume = outputs_width * outputs_height;
+
+        let outputs_buffer = empty_buffer(
+            self.get_outputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+        let argmax_indices_buffer = Buffer::<cl_int>::create(
+            &state.context,
+            CL_MEM_READ_WRITE,
+            self.get_outputs_amount() * samples_amount,
+            ptr::null_mut(),
+        )?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+        let propagate_kernel = program.get_krnl(PROPAGATE_KERNEL_NAME)?;
+
+        ExecuteKernel::new(propagate_kernel)
+            .set_arg(inputs)
+            .set_arg(&outputs_buffer)
+            .set_arg(&argmax_indices_buffer)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&((self.inputs_size.0 * self.inputs_size.1) as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_volume as cl_int))
+            .set_arg(&(self.pool_size.0 as cl_int))
+            .set_arg(&(self.pool_size.1 as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_outputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_argmax_indices_buffer = Some(argmax_indices_buffer);
+        self.last_outputs_buffer = Some(outputs_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        _per_parameter_type_gradients: &[Gradient],
+        _optimizer: &mut dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        Ok(())
+    }
+
+    fn compute_gradients(
+        &self,
+        _layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        Ok(Vec::default())
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        _optimizer: &dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        if self.last_argmax_indices_buffer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+
+        let loss_to_input_derivatives_buffer = empty_buffer(
+            self.get_inputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+        let backprop_kernel = program.get_krnl(BACK_PROPAGATE_KERNEL_NAME)?;
+
+        ExecuteKernel::new(backprop_kernel)
+            .set_arg(layer_output_to_error_derivative)
+            .set_arg(self.last_argmax_indices_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_input_derivatives_buffer)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&((self.inputs_size.0 * self.inputs_size.1) as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_height as cl_int))
+            .set_arg(&((outputs_width * outputs_height) as cl_int))
+            .set_arg(&(self.pool_size.0 as cl_int))
+            .set_arg(&(self.pool_size.1 as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod max_pool2d_tests {
+    use crate::{
+        layers::{Layer, LayerInitializationError},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+        Model,
+    };
+
+    use super::MaxPool2D;
+
+    #[test]
+    fn should_propagate_and_route_derivatives_to_the_max_inputs() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.3, 0.2,
+            0.7, 0.2, 0.9, 0.4,
+            0.4, 0.8, 0.6, 0.3,
+            0.5, 0.1, 0.2, 0.9,
+
+            0.3, 0.6, 0.7, 0.1,
+            0.2, 0.4, 0.5, 0.8,
+            0.9, 0.1, 0.3, 0.2,
+            0.6, 0.5, 0.4, 0.7,
+        ];
+        let expected_outputs = vec![
+            0.7, 0.9,
+            0.8, 0.9,
+
+            0.6, 0.8,
+            0.9, 0.7,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.1, 0.2,
+            0.3, 0.4,
+
+            0.5, 0.6,
+            0.7, 0.8,
+        ];
+        let expected_loss_to_input_derivatives = vec![
+            0.0, 0.0, 0.0, 0.0,
+            0.1, 0.0, 0.2, 0.0,
+            0.0, 0.3, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.4,
+
+            0.0, 0.5, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.6,
+            0.7, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.8,
+        ];
+
+        let mut layer = MaxPool2D::new_raw((4, 4), 2, (2, 2));
+        layer.init(&opencl_state).expect("unable to init MaxPool2D");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the MaxPool2D");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(
+                &loss_to_output_derivatives
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the loss to output derivatives buffer"),
+            )
+            .expect("unable to compute the loss to input derivatives of the MaxPool2D");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_not_initialize_when_the_pool_is_bigger_than_the_inputs() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let layer = MaxPool2D::new_raw((3, 1), 2, (2, 2));
+
+        assert_eq!(layer.get_outputs_amount(), 0);
+
+        let mut model = Model::new(vec![layer.into()]);
+
+        assert!(matches!(
+            model.init(&opencl_state),
+            Err(LayerInitializationError::PoolBiggerThanInputs)
+        ));
+    }
+}
@@ -0,0 +1,45 @@
+//! The module that contains all of the pooling layers currently implemented for Intricate,
+//! which are:
+//!
+//! - MaxPool2D
+//! - AvgPool2D
+//! - GlobalAveragePool
+
+pub mod avg_pool2d;
+pub mod global_average_pool;
+pub mod max_pool2d;
+
+pub use avg_pool2d::AvgPool2D;
+pub use global_average_pool::GlobalAveragePool;
+pub use max_pool2d::MaxPool2D;
+
+use crate::utils::{opencl::EnsureKernelsAndProgramError, OpenCLState};
+
+pub(crate) fn compile_pooling(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    avg_pool2d::compile_avg_pool2d(opencl_state)?;
+    global_average_pool::compile_global_average_pool(opencl_state)?;
+    max_pool2d::compile_max_pool2d(opencl_state)?;
+
+    Ok(())
+}
+
+// the amount of positions the pool fits in along each axis, which is zero on the axes in which
+// the pool is bigger than the images so that a Model can be checked without panicking
+pub(crate) fn get_pooled_size(
+    inputs_size: (usize, usize),
+    pool_size: (usize, usize),
+    stride: (usize, usize),
+) -> (usize, usize) {
+    (
+        inputs_size
+            .0
+            .checked_sub(pool_size.0)
+            .map_or(0, |remaining| remaining / stride.0 + 1),
+        inputs_size
+            .1
+            .checked_sub(pool_size.1)
+            .map_or(0, |remaining| remaining / stride.1 + 1),
+    )
+}
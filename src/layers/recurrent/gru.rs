@@ -0,0 +1,150 @@
+//! The module that contains the GRU layer.
+
+use opencl3::{device::cl_float, memory::Buffer};
+use savefile_derive::Savefile;
+
+use crate::types::ModelLayer;
+
+use super::{Recurrent, RecurrentCell};
+
+#[derive(Debug, Savefile)]
+/// A Gated Recurrent Unit layer, that uses an update and a reset gate to control how much of its
+/// last state is kept on each timestep.
+///
+/// Expects the inputs of each sample to be a flattened sequence of `timesteps` timesteps with
+/// `features` features each.
+pub struct GRU<'a> {
+    /// The Recurrent layer that does all of the work of this layer.
+    pub recurrent: Recurrent<'a>,
+}
+
+impl<'a> GRU<'a> {
+    /// Creates a new GRU raw, meaning that it is not inside of a ModelLayer enum.
+    pub fn new_raw(timesteps: usize, features: usize, units: usize) -> GRU<'a> {
+        GRU {
+            recurrent: Recurrent::new_raw(RecurrentCell::GRU, timesteps, features, units),
+        }
+    }
+
+    /// Creates a new GRU inside of a ModelLayer enum.
+    pub fn new(timesteps: usize, features: usize, units: usize) -> ModelLayer<'a> {
+        Self::new_raw(timesteps, features, units).into()
+    }
+
+    /// Sets weather or not the layer outputs its states on all of the timesteps instead of only
+    /// the last one, which is **false** by default.
+    pub fn set_return_sequences(mut self, return_sequences: bool) -> Self {
+        self.recurrent.return_sequences = return_sequences;
+
+        self
+    }
+
+    /// Sets weather or not the layer also goes through the sequences backwards, which is
+    /// **false** by default.
+    pub fn set_bidirectional(mut self, bidirectional: bool) -> Self {
+        self.recurrent.bidirectional = bidirectional;
+
+        self
+    }
+}
+
+impl_layer_for_recurrent_wrapper!(GRU);
+
+#[cfg(test)]
+mod gru_tests {
+    use crate::{
+        layers::{recurrent::RecurrentDirection, Layer},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::GRU;
+
+    #[test]
+    fn should_propagate_and_back_propagate_through_time_correctly() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.7, -0.3, 0.4, 0.9,
+            -0.5, 0.3, 0.8, 0.1, 0.0, 0.4,
+        ];
+        let loss_to_output_derivatives = vec![
+            -0.2, -0.3, 0.2, -0.4, 0.0, -0.1,
+            -0.4, 0.0, -0.5, -0.1, -0.4, -0.4,
+        ];
+
+        let expected_outputs = vec![
+            0.1371, -0.1957, 0.0622, -0.2766, 0.2283, -0.346,
+            0.0958, -0.1868, 0.1206, -0.2861, 0.1754, -0.3239,
+        ];
+        let expected_input_weights_gradients = vec![
+            -0.0039, -0.0137, -0.0002, -0.0022, -0.0491, -0.0981,
+            0.0087, -0.0177, -0.0002, -0.0007, -0.1557, -0.0833,
+        ];
+        let expected_recurrent_weights_gradients = vec![
+            0.0012, -0.0029, 0.0, -0.0006, -0.0117, -0.013,
+            -0.0025, 0.0055, 0.0001, 0.0012, 0.0274, 0.0248,
+        ];
+        let expected_biases_gradients = vec![0.0319, -0.0613, -0.0004, -0.0053, -0.484, -0.3681];
+        let expected_loss_to_input_derivatives = vec![
+            -0.0006, -0.0167, 0.0103, 0.0522, 0.0019, 0.0021,
+            -0.0106, -0.0986, 0.0026, -0.1278, 0.0096, -0.0463,
+        ];
+
+        let mut layer = GRU::new_raw(3, 2, 2).set_return_sequences(true);
+        layer.recurrent.directions.push(RecurrentDirection {
+            input_weights: vec![vec![-0.4, 0.3, 0.3, -0.2, 0.0, -0.1], vec![0.2, 0.3, -0.4, -0.5, 0.3, -0.1]],
+            recurrent_weights: vec![vec![0.5, 0.4, -0.4, -0.4, 0.3, 0.2], vec![0.2, -0.2, 0.1, 0.1, 0.1, -0.3]],
+            biases: vec![-0.3, 0.0, -0.1, 0.1, 0.1, -0.4],
+            ..Default::default()
+        });
+        layer.init(&opencl_state).expect("unable to init GRU");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the GRU");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the GRU");
+        let gradients = gradients
+            .iter()
+            .map(|gradient| {
+                Vec::<f32>::from_buffer(&gradient.value, false, &opencl_state)
+                    .expect("unable to read a gradients buffer")
+            })
+            .collect::<Vec<Vec<f32>>>();
+
+        assert_approx_equal_distance(&gradients[0], &expected_input_weights_gradients, 0.01);
+        assert_approx_equal_distance(&gradients[1], &expected_recurrent_weights_gradients, 0.01);
+        assert_approx_equal_distance(&gradients[2], &expected_biases_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the GRU");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
@@ -0,0 +1,127 @@
+// helper functions shared by all of the recurrent cells, these are prepended to the source of
+// each one of the cells' programs
+
+// computes the part of the pre activation of a gate that comes from the inputs, with the bias
+float get_input_part(
+    global float* inputs,
+    global float* input_weights,
+    global float* biases,
+
+    int sample_index,
+    int gate_index,
+    int timestep,
+
+    int timesteps,
+    int features,
+    int gates_width
+) {
+    int input_start = (sample_index * timesteps + timestep) * features;
+
+    float result = (float)biases[gate_index];
+
+    for (int feature_index = 0; feature_index < features; feature_index++) {
+        result += (float)inputs[input_start + feature_index]
+            * (float)input_weights[feature_index * gates_width + gate_index];
+    }
+
+    return result;
+}
+
+// computes the part of the pre activation of a gate that comes from the last state
+float get_recurrent_part(
+    global float* states,
+    global float* recurrent_weights,
+
+    int sample_index,
+    int gate_index,
+    int step,
+
+    int samples_amount,
+    int units,
+    int gates_width
+) {
+    if (step == 0) {
+        return 0.0f;
+    }
+
+    int state_start = ((step - 1) * samples_amount + sample_index) * units;
+
+    float result = 0.0f;
+
+    for (int unit_index = 0; unit_index < units; unit_index++) {
+        result += (float)states[state_start + unit_index]
+            * (float)recurrent_weights[unit_index * gates_width + gate_index];
+    }
+
+    return result;
+}
+
+void store_output(
+    global float* outputs,
+    float state,
+
+    int sample_index,
+    int unit_index,
+    int step,
+    int timestep,
+
+    int timesteps,
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    if (return_sequences != 0) {
+        outputs[(sample_index * timesteps + timestep) * output_width + direction_offset + unit_index] = state;
+    } else if (step == timesteps - 1) {
+        outputs[sample_index * output_width + direction_offset + unit_index] = state;
+    }
+}
+
+// gets the derivative of the loss with respect to the state of a step, both from the outputs of
+// the layer and from how the state was used in the next step
+float get_loss_to_state_derivative(
+    global float* loss_to_output_derivatives,
+    global float* recurrent_weights,
+    global float* recurrent_gate_derivatives,
+
+    int sample_index,
+    int unit_index,
+    int step,
+    int timestep,
+
+    int samples_amount,
+    int timesteps,
+    int units,
+    int gates_width,
+
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    float derivative = 0.0f;
+
+    if (return_sequences != 0) {
+        derivative = (float)loss_to_output_derivatives[
+            (sample_index * timesteps + timestep) * output_width + direction_offset + unit_index
+        ];
+    } else if (step == timesteps - 1) {
+        derivative = (float)loss_to_output_derivatives[
+            sample_index * output_width + direction_offset + unit_index
+        ];
+    }
+
+    if (step < timesteps - 1) {
+        int derivatives_start = ((step + 1) * samples_amount + sample_index) * gates_width;
+
+        for (int gate_index = 0; gate_index < gates_width; gate_index++) {
+            derivative += (float)recurrent_gate_derivatives[derivatives_start + gate_index]
+                * (float)recurrent_weights[unit_index * gates_width + gate_index];
+        }
+    }
+
+    return derivative;
+}
+
+float sigmoid(float x) {
+    return 1.0f / (1.0f + exp(-x));
+}
@@ -0,0 +1,173 @@
+// the gates are stored in the order update (z), reset (r) and candidate (n)
+//
+// n = tanh(x * W_n + b_n + r * (h_prev * U_n))
+// h = z * h_prev + (1 - z) * n
+
+kernel void propagate_timestep(
+    global float* inputs,
+    global float* input_weights,
+    global float* recurrent_weights,
+    global float* biases,
+
+    global float* states,
+    global float* cell_states, // not used by this cell
+    global float* gates,
+    global float* outputs,
+
+    int step,
+    int timestep,
+
+    int samples_amount,
+    int timesteps,
+    int features,
+    int units,
+
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int unit_index = get_global_id(1);
+
+    if (unit_index >= units) {
+        return;
+    }
+
+    int gates_width = units * 3;
+
+    float input_parts[3];
+    float recurrent_parts[3];
+
+    for (int gate = 0; gate < 3; gate++) {
+        int gate_index = gate * units + unit_index;
+
+        input_parts[gate] = get_input_part(
+            inputs, input_weights, biases,
+            sample_index, gate_index, timestep,
+            timesteps, features, gates_width
+        );
+        recurrent_parts[gate] = get_recurrent_part(
+            states, recurrent_weights,
+            sample_index, gate_index, step,
+            samples_amount, units, gates_width
+        );
+    }
+
+    float update_gate = sigmoid(input_parts[0] + recurrent_parts[0]);
+    float reset_gate = sigmoid(input_parts[1] + recurrent_parts[1]);
+    float candidate = tanh(input_parts[2] + reset_gate * recurrent_parts[2]);
+
+    int state_index = (step * samples_amount + sample_index) * units + unit_index;
+
+    float last_state = 0.0f;
+    if (step > 0) {
+        last_state = (float)states[state_index - samples_amount * units];
+    }
+
+    float state = update_gate * last_state + (1.0f - update_gate) * candidate;
+
+    int gates_start = (step * samples_amount + sample_index) * gates_width;
+
+    gates[gates_start + unit_index] = update_gate;
+    gates[gates_start + units + unit_index] = reset_gate;
+    gates[gates_start + 2 * units + unit_index] = candidate;
+
+    states[state_index] = state;
+
+    store_output(
+        outputs, state,
+        sample_index, unit_index, step, timestep,
+        timesteps, return_sequences, output_width, direction_offset
+    );
+}
+
+kernel void back_propagate_timestep(
+    global float* loss_to_output_derivatives,
+    global float* recurrent_weights,
+
+    global float* states,
+    global float* cell_states, // not used by this cell
+    global float* gates,
+
+    global float* input_gate_derivatives,
+    global float* recurrent_gate_derivatives,
+    // the derivatives of the loss with respect to the last state that go directly
+    // through the update gate without passing through the recurrent weights
+    global float* carried_derivatives,
+
+    int step,
+    int timestep,
+
+    int samples_amount,
+    int timesteps,
+    int units,
+
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int unit_index = get_global_id(1);
+
+    if (unit_index >= units) {
+        return;
+    }
+
+    int gates_width = units * 3;
+
+    int state_index = (step * samples_amount + sample_index) * units + unit_index;
+    int gates_start = (step * samples_amount + sample_index) * gates_width;
+
+    float loss_to_state_derivative = get_loss_to_state_derivative(
+        loss_to_output_derivatives, recurrent_weights, recurrent_gate_derivatives,
+        sample_index, unit_index, step, timestep,
+        samples_amount, timesteps, units, gates_width,
+        return_sequences, output_width, direction_offset
+    );
+    if (step < timesteps - 1) {
+        loss_to_state_derivative += (float)carried_derivatives[state_index + samples_amount * units];
+    }
+
+    float update_gate = (float)gates[gates_start + unit_index];
+    float reset_gate = (float)gates[gates_start + units + unit_index];
+    float candidate = (float)gates[gates_start + 2 * units + unit_index];
+
+    float last_state = 0.0f;
+    if (step > 0) {
+        last_state = (float)states[state_index - samples_amount * units];
+    }
+
+    float candidate_recurrent_part = get_recurrent_part(
+        states, recurrent_weights,
+        sample_index, 2 * units + unit_index, step,
+        samples_amount, units, gates_width
+    );
+
+    float candidate_derivative = loss_to_state_derivative * (1.0f - update_gate)
+        * (1.0f - candidate * candidate);
+    float update_gate_derivative = loss_to_state_derivative * (last_state - candidate)
+        * update_gate * (1.0f - update_gate);
+    float reset_gate_derivative = candidate_derivative * candidate_recurrent_part
+        * reset_gate * (1.0f - reset_gate);
+
+    input_gate_derivatives[gates_start + unit_index] = update_gate_derivative;
+    input_gate_derivatives[gates_start + units + unit_index] = reset_gate_derivative;
+    input_gate_derivatives[gates_start + 2 * units + unit_index] = candidate_derivative;
+
+    recurrent_gate_derivatives[gates_start + unit_index] = update_gate_derivative;
+    recurrent_gate_derivatives[gates_start + units + unit_index] = reset_gate_derivative;
+    // the recurrent part of the candidate is multiplied by the reset gate
+    recurrent_gate_derivatives[gates_start + 2 * units + unit_index] = candidate_derivative * reset_gate;
+
+    carried_derivatives[state_index] = loss_to_state_derivative * update_gate;
+}
@@ -0,0 +1,174 @@
+// the gates are stored in the order input (i), forget (f), candidate (g) and output (o)
+//
+// c = f * c_prev + i * g
+// h = o * tanh(c)
+
+kernel void propagate_timestep(
+    global float* inputs,
+    global float* input_weights,
+    global float* recurrent_weights,
+    global float* biases,
+
+    global float* states,
+    global float* cell_states,
+    global float* gates,
+    global float* outputs,
+
+    int step,
+    int timestep,
+
+    int samples_amount,
+    int timesteps,
+    int features,
+    int units,
+
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int unit_index = get_global_id(1);
+
+    if (unit_index >= units) {
+        return;
+    }
+
+    int gates_width = units * 4;
+
+    float activated_gates[4];
+
+    for (int gate = 0; gate < 4; gate++) {
+        int gate_index = gate * units + unit_index;
+
+        float pre_activation = get_input_part(
+            inputs, input_weights, biases,
+            sample_index, gate_index, timestep,
+            timesteps, features, gates_width
+        ) + get_recurrent_part(
+            states, recurrent_weights,
+            sample_index, gate_index, step,
+            samples_amount, units, gates_width
+        );
+
+        if (gate == 2) {
+            activated_gates[gate] = tanh(pre_activation);
+        } else {
+            activated_gates[gate] = sigmoid(pre_activation);
+        }
+    }
+
+    int state_index = (step * samples_amount + sample_index) * units + unit_index;
+
+    float last_cell_state = 0.0f;
+    if (step > 0) {
+        last_cell_state = (float)cell_states[state_index - samples_amount * units];
+    }
+
+    float cell_state = activated_gates[1] * last_cell_state + activated_gates[0] * activated_gates[2];
+    float state = activated_gates[3] * tanh(cell_state);
+
+    int gates_start = (step * samples_amount + sample_index) * gates_width;
+
+    for (int gate = 0; gate < 4; gate++) {
+        gates[gates_start + gate * units + unit_index] = activated_gates[gate];
+    }
+
+    cell_states[state_index] = cell_state;
+    states[state_index] = state;
+
+    store_output(
+        outputs, state,
+        sample_index, unit_index, step, timestep,
+        timesteps, return_sequences, output_width, direction_offset
+    );
+}
+
+kernel void back_propagate_timestep(
+    global float* loss_to_output_derivatives,
+    global float* recurrent_weights,
+
+    global float* states,
+    global float* cell_states,
+    global float* gates,
+
+    global float* input_gate_derivatives,
+    global float* recurrent_gate_derivatives,
+    // the derivatives of the loss with respect to the last cell state
+    global float* carried_derivatives,
+
+    int step,
+    int timestep,
+
+    int samples_amount,
+    int timesteps,
+    int units,
+
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int unit_index = get_global_id(1);
+
+    if (unit_index >= units) {
+        return;
+    }
+
+    int gates_width = units * 4;
+
+    float loss_to_state_derivative = get_loss_to_state_derivative(
+        loss_to_output_derivatives, recurrent_weights, recurrent_gate_derivatives,
+        sample_index, unit_index, step, timestep,
+        samples_amount, timesteps, units, gates_width,
+        return_sequences, output_width, direction_offset
+    );
+
+    int state_index = (step * samples_amount + sample_index) * units + unit_index;
+    int gates_start = (step * samples_amount + sample_index) * gates_width;
+
+    float input_gate = (float)gates[gates_start + unit_index];
+    float forget_gate = (float)gates[gates_start + units + unit_index];
+    float candidate = (float)gates[gates_start + 2 * units + unit_index];
+    float output_gate = (float)gates[gates_start + 3 * units + unit_index];
+
+    float activated_cell_state = tanh((float)cell_states[state_index]);
+
+    float last_cell_state = 0.0f;
+    if (step > 0) {
+        last_cell_state = (float)cell_states[state_index - samples_amount * units];
+    }
+
+    float loss_to_cell_state_derivative = loss_to_state_derivative * output_gate
+        * (1.0f - activated_cell_state * activated_cell_state);
+    if (step < timesteps - 1) {
+        loss_to_cell_state_derivative += (float)carried_derivatives[state_index + samples_amount * units];
+    }
+
+    float pre_activation_derivatives[4];
+
+    pre_activation_derivatives[0] = loss_to_cell_state_derivative * candidate
+        * input_gate * (1.0f - input_gate);
+    pre_activation_derivatives[1] = loss_to_cell_state_derivative * last_cell_state
+        * forget_gate * (1.0f - forget_gate);
+    pre_activation_derivatives[2] = loss_to_cell_state_derivative * input_gate
+        * (1.0f - candidate * candidate);
+    pre_activation_derivatives[3] = loss_to_state_derivative * activated_cell_state
+        * output_gate * (1.0f - output_gate);
+
+    for (int gate = 0; gate < 4; gate++) {
+        input_gate_derivatives[gates_start + gate * units + unit_index] = pre_activation_derivatives[gate];
+        recurrent_gate_derivatives[gates_start + gate * units + unit_index] = pre_activation_derivatives[gate];
+    }
+
+    carried_derivatives[state_index] = loss_to_cell_state_derivative * forget_gate;
+}
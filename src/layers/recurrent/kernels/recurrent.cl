@@ -0,0 +1,167 @@
+// the kernels that are the same for all of the recurrent cells, they all use the derivatives of
+// the loss with respect to the pre activations of the gates computed while back propagating
+// through time, which are separated into the ones used with the input weights and the ones used
+// with the recurrent weights since they are not always the same (e.g. in the GRU)
+
+kernel void compute_input_weights_gradients(
+    global float* inputs,
+    global float* input_gate_derivatives,
+
+    global float* gradients,
+
+    int reversed,
+
+    int samples_amount,
+    int timesteps,
+    int features,
+    int gates_width
+) {
+    int feature_index = get_global_id(0);
+
+    if (feature_index >= features) {
+        return;
+    }
+
+    int gate_index = get_global_id(1);
+
+    if (gate_index >= gates_width) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int step = 0; step < timesteps; step++) {
+        int timestep = step;
+        if (reversed != 0) {
+            timestep = timesteps - 1 - step;
+        }
+
+        for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+            int input_index = (sample_index * timesteps + timestep) * features + feature_index;
+            int derivative_index = (step * samples_amount + sample_index) * gates_width + gate_index;
+
+            gradient += (float)inputs[input_index] * (float)input_gate_derivatives[derivative_index];
+        }
+    }
+
+    gradients[feature_index * gates_width + gate_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_recurrent_weights_gradients(
+    global float* states,
+    global float* recurrent_gate_derivatives,
+
+    global float* gradients,
+
+    int samples_amount,
+    int timesteps,
+    int units,
+    int gates_width
+) {
+    int unit_index = get_global_id(0);
+
+    if (unit_index >= units) {
+        return;
+    }
+
+    int gate_index = get_global_id(1);
+
+    if (gate_index >= gates_width) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    // the first step has no previous state, so the recurrent weights have no effect on it
+    for (int step = 1; step < timesteps; step++) {
+        for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+            int state_index = ((step - 1) * samples_amount + sample_index) * units + unit_index;
+            int derivative_index = (step * samples_amount + sample_index) * gates_width + gate_index;
+
+            gradient += (float)states[state_index] * (float)recurrent_gate_derivatives[derivative_index];
+        }
+    }
+
+    gradients[unit_index * gates_width + gate_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_bias_gradients(
+    global float* input_gate_derivatives,
+
+    global float* gradients,
+
+    int samples_amount,
+    int timesteps,
+    int gates_width
+) {
+    int gate_index = get_global_id(0);
+
+    if (gate_index >= gates_width) {
+        return;
+    }
+
+    float gradient = 0.0f;
+
+    for (int step = 0; step < timesteps; step++) {
+        for (int sample_index = 0; sample_index < samples_amount; sample_index++) {
+            gradient += (float)input_gate_derivatives[(step * samples_amount + sample_index) * gates_width + gate_index];
+        }
+    }
+
+    gradients[gate_index] = gradient / (float)samples_amount;
+}
+
+kernel void compute_loss_to_input_derivatives(
+    global float* input_weights,
+    global float* input_gate_derivatives,
+
+    global float* loss_to_input_derivatives,
+
+    int reversed,
+    // weather or not to add to the derivatives that are already in the buffer,
+    // used for summing up the derivatives of both directions of a bidirectional layer
+    int accumulate,
+
+    int samples_amount,
+    int timesteps,
+    int features,
+    int gates_width
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int input_index = get_global_id(1);
+
+    if (input_index >= timesteps * features) {
+        return;
+    }
+
+    int timestep = input_index / features;
+    int feature_index = input_index % features;
+
+    int step = timestep;
+    if (reversed != 0) {
+        step = timesteps - 1 - timestep;
+    }
+
+    int derivatives_start = (step * samples_amount + sample_index) * gates_width;
+    int weights_start = feature_index * gates_width;
+
+    float loss_to_input_derivative = 0.0f;
+
+    for (int gate_index = 0; gate_index < gates_width; gate_index++) {
+        loss_to_input_derivative += (float)input_gate_derivatives[derivatives_start + gate_index]
+            * (float)input_weights[weights_start + gate_index];
+    }
+
+    int flat_input_index = sample_index * timesteps * features + input_index;
+
+    if (accumulate != 0) {
+        loss_to_input_derivatives[flat_input_index] += loss_to_input_derivative;
+    } else {
+        loss_to_input_derivatives[flat_input_index] = loss_to_input_derivative;
+    }
+}
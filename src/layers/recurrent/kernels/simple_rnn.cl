@@ -0,0 +1,112 @@
+// h = tanh(x * W + h_prev * U + b)
+
+kernel void propagate_timestep(
+    global float* inputs,
+    global float* input_weights,
+    global float* recurrent_weights,
+    global float* biases,
+
+    global float* states,
+    global float* cell_states, // not used by this cell
+    global float* gates,
+    global float* outputs,
+
+    int step,
+    int timestep,
+
+    int samples_amount,
+    int timesteps,
+    int features,
+    int units,
+
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int unit_index = get_global_id(1);
+
+    if (unit_index >= units) {
+        return;
+    }
+
+    float pre_activation = get_input_part(
+        inputs, input_weights, biases,
+        sample_index, unit_index, timestep,
+        timesteps, features, units
+    ) + get_recurrent_part(
+        states, recurrent_weights,
+        sample_index, unit_index, step,
+        samples_amount, units, units
+    );
+
+    float state = tanh(pre_activation);
+
+    int state_index = (step * samples_amount + sample_index) * units + unit_index;
+
+    states[state_index] = state;
+    gates[state_index] = state;
+
+    store_output(
+        outputs, state,
+        sample_index, unit_index, step, timestep,
+        timesteps, return_sequences, output_width, direction_offset
+    );
+}
+
+kernel void back_propagate_timestep(
+    global float* loss_to_output_derivatives,
+    global float* recurrent_weights,
+
+    global float* states,
+    global float* cell_states, // not used by this cell
+    global float* gates,
+
+    global float* input_gate_derivatives,
+    global float* recurrent_gate_derivatives,
+    global float* carried_derivatives, // not used by this cell
+
+    int step,
+    int timestep,
+
+    int samples_amount,
+    int timesteps,
+    int units,
+
+    int return_sequences,
+    int output_width,
+    int direction_offset
+) {
+    int sample_index = get_global_id(0);
+
+    if (sample_index >= samples_amount) {
+        return;
+    }
+
+    int unit_index = get_global_id(1);
+
+    if (unit_index >= units) {
+        return;
+    }
+
+    float loss_to_state_derivative = get_loss_to_state_derivative(
+        loss_to_output_derivatives, recurrent_weights, recurrent_gate_derivatives,
+        sample_index, unit_index, step, timestep,
+        samples_amount, timesteps, units, units,
+        return_sequences, output_width, direction_offset
+    );
+
+    int state_index = (step * samples_amount + sample_index) * units + unit_index;
+
+    float state = (float)states[state_index];
+
+    float pre_activation_derivative = loss_to_state_derivative * (1.0f - state * state);
+
+    input_gate_derivatives[state_index] = pre_activation_derivative;
+    recurrent_gate_derivatives[state_index] = pre_activation_derivative;
+}
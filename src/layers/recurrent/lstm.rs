@@ -0,0 +1,150 @@
+//! The module that contains the LSTM layer.
+
+use opencl3::{device::cl_float, memory::Buffer};
+use savefile_derive::Savefile;
+
+use crate::types::ModelLayer;
+
+use super::{Recurrent, RecurrentCell};
+
+#[derive(Debug, Savefile)]
+/// A Long Short-Term Memory recurrent layer, that besides its state carries a cell state between
+/// the timesteps, which is controlled by input, forget and output gates.
+///
+/// Expects the inputs of each sample to be a flattened sequence of `timesteps` timesteps with
+/// `features` features each.
+pub struct LSTM<'a> {
+    /// The Recurrent layer that does all of the work of this layer.
+    pub recurrent: Recurrent<'a>,
+}
+
+impl<'a> LSTM<'a> {
+    /// Creates a new LSTM raw, meaning that it is not inside of a ModelLayer enum.
+    pub fn new_raw(timesteps: usize, features: usize, units: usize) -> LSTM<'a> {
+        LSTM {
+            recurrent: Recurrent::new_raw(RecurrentCell::LSTM, timesteps, features, units),
+        }
+    }
+
+    /// Creates a new LSTM inside of a ModelLayer enum.
+    pub fn new(timesteps: usize, features: usize, units: usize) -> ModelLayer<'a> {
+        Self::new_raw(timesteps, features, units).into()
+    }
+
+    /// Sets weather or not the layer outputs its states on all of the timesteps instead of only
+    /// the last one, which is **false** by default.
+    pub fn set_return_sequences(mut self, return_sequences: bool) -> Self {
+        self.recurrent.return_sequences = return_sequences;
+
+        self
+    }
+
+    /// Sets weather or not the layer also goes through the sequences backwards, which is
+    /// **false** by default.
+    pub fn set_bidirectional(mut self, bidirectional: bool) -> Self {
+        self.recurrent.bidirectional = bidirectional;
+
+        self
+    }
+}
+
+impl_layer_for_recurrent_wrapper!(LSTM);
+
+#[cfg(test)]
+mod lstm_tests {
+    use crate::{
+        layers::{recurrent::RecurrentDirection, Layer},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::LSTM;
+
+    #[test]
+    fn should_propagate_and_back_propagate_through_time_correctly() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.7, -0.3, 0.4, 0.9,
+            -0.5, 0.3, 0.8, 0.1, 0.0, 0.4,
+        ];
+        let loss_to_output_derivatives = vec![
+            -0.2, -0.3,
+            0.2, -0.4,
+        ];
+
+        let expected_outputs = vec![
+            0.0539, -0.2583,
+            0.0512, -0.224,
+        ];
+        let expected_input_weights_gradients = vec![
+            -0.0011, 0.0101, -0.0003, 0.0056, -0.0082, -0.0313, -0.0015, 0.0047,
+            -0.0019, 0.0184, 0.0001, 0.0082, -0.0014, -0.0283, -0.0018, 0.0209,
+        ];
+        let expected_recurrent_weights_gradients = vec![
+            0.0, 0.001, 0.0, 0.0006, 0.0001, -0.0028, 0.0, 0.0011,
+            0.0, -0.0058, 0.0, -0.0034, -0.0003, 0.0143, -0.0001, -0.0064,
+        ];
+        let expected_biases_gradients = vec![-0.0009, 0.0376, 0.0001, 0.0186, -0.0016, -0.0967, -0.0005, 0.0323];
+        let expected_loss_to_input_derivatives = vec![
+            0.0028, 0.0002, 0.0036, 0.0095, 0.0182, 0.0049,
+            0.0025, 0.0065, 0.0052, 0.0158, 0.022, 0.0312,
+        ];
+
+        let mut layer = LSTM::new_raw(3, 2, 2);
+        layer.recurrent.directions.push(RecurrentDirection {
+            input_weights: vec![vec![-0.4, 0.3, 0.3, -0.2, 0.0, -0.1, 0.2, 0.3], vec![-0.4, -0.5, 0.3, -0.1, 0.3, -0.5, -0.1, 0.2]],
+            recurrent_weights: vec![vec![0.5, 0.4, -0.4, -0.4, 0.3, 0.2, 0.2, -0.2], vec![0.1, 0.1, 0.1, -0.3, -0.1, -0.1, 0.2, 0.5]],
+            biases: vec![-0.3, 0.0, -0.1, 0.1, 0.1, -0.4, -0.5, 0.3],
+            ..Default::default()
+        });
+        layer.init(&opencl_state).expect("unable to init LSTM");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the LSTM");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.001);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the LSTM");
+        let gradients = gradients
+            .iter()
+            .map(|gradient| {
+                Vec::<f32>::from_buffer(&gradient.value, false, &opencl_state)
+                    .expect("unable to read a gradients buffer")
+            })
+            .collect::<Vec<Vec<f32>>>();
+
+        assert_approx_equal_distance(&gradients[0], &expected_input_weights_gradients, 0.001);
+        assert_approx_equal_distance(&gradients[1], &expected_recurrent_weights_gradients, 0.001);
+        assert_approx_equal_distance(&gradients[2], &expected_biases_gradients, 0.001);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the LSTM");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.001,
+        );
+    }
+}
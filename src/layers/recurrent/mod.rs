@@ -0,0 +1,1059 @@
+//! The module that contains the recurrent layers, which go through sequences of inputs one
+//! timestep at a time carrying a state between the timesteps.
+//!
+//! All of them expect the inputs of each sample to be a flattened `(timesteps, features)`
+//! sequence and do back-propagation through time on the device.
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::{cl_int, ClError},
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use rayon::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{KernelNotFoundError, ModelLayer, ProgramNotFoundError, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    initializers::{ConstantInitializer, GlorotUniformInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+// implements the Layer trait for a struct that just wraps a Recurrent in its `recurrent` field
+macro_rules! impl_layer_for_recurrent_wrapper {
+    ($name:ident) => {
+        impl<'a> crate::layers::Layer<'a> for $name<'a> {
+            fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+                self.recurrent.get_last_inputs()
+            }
+
+            fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+                self.recurrent.get_last_outputs()
+            }
+
+            fn get_inputs_amount(&self) -> usize {
+                self.recurrent.get_inputs_amount()
+            }
+
+            fn get_outputs_amount(&self) -> usize {
+                self.recurrent.get_outputs_amount()
+            }
+
+            fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+                self.recurrent.get_flattened_parameter_data(parameter)
+            }
+
+            fn get_initializer_for_parameter<'b>(
+                &'b self,
+                parameter: &str,
+            ) -> Option<&'b crate::layers::initializers::Initializer> {
+                self.recurrent.get_initializer_for_parameter(parameter)
+            }
+
+            fn set_initializer_for_parameter(
+                self,
+                initializer: crate::layers::initializers::Initializer,
+                parameter: &'a str,
+            ) -> crate::types::ModelLayer<'a> {
+                self.recurrent
+                    .set_initializer_for_parameter(initializer, parameter)
+            }
+
+            fn init(
+                &mut self,
+                opencl_state: &'a crate::utils::OpenCLState,
+            ) -> Result<(), crate::layers::LayerInitializationError> {
+                self.recurrent.init(opencl_state)
+            }
+
+            fn clean_up_gpu_state(&mut self) -> () {
+                self.recurrent.clean_up_gpu_state()
+            }
+
+            fn sync_data_from_buffers_to_host(
+                &mut self,
+            ) -> Result<(), crate::types::SyncDataError> {
+                self.recurrent.sync_data_from_buffers_to_host()
+            }
+
+            fn propagate(
+                &mut self,
+                inputs: &Buffer<cl_float>,
+            ) -> Result<&Buffer<cl_float>, crate::layers::LayerPropagationError> {
+                self.recurrent.propagate(inputs)
+            }
+
+            fn compute_gradients(
+                &self,
+                layer_output_to_error_derivative: &Buffer<cl_float>,
+            ) -> Result<Vec<crate::layers::Gradient>, crate::layers::LayerGradientComputationError>
+            {
+                self.recurrent
+                    .compute_gradients(layer_output_to_error_derivative)
+            }
+
+            fn optimize_parameters(
+                &mut self,
+                optimizer: &dyn crate::optimizers::Optimizer<'a>,
+                layer_index: usize,
+                timestep: usize,
+            ) -> Result<(), crate::layers::ParametersOptimizationError> {
+                self.recurrent
+                    .optimize_parameters(optimizer, layer_index, timestep)
+            }
+
+            fn apply_gradients(
+                &mut self,
+                per_parameter_type_gradients: &[crate::layers::Gradient],
+                optimizer: &mut dyn crate::optimizers::Optimizer<'a>,
+                layer_index: usize,
+                timestep: usize,
+            ) -> Result<(), crate::layers::LayerGradientApplicationError> {
+                self.recurrent.apply_gradients(
+                    per_parameter_type_gradients,
+                    optimizer,
+                    layer_index,
+                    timestep,
+                )
+            }
+
+            fn compute_loss_to_input_derivatives(
+                &self,
+                layer_output_to_error_derivative: &Buffer<cl_float>,
+            ) -> Result<Buffer<cl_float>, crate::layers::LayerLossToInputDifferentiationError>
+            {
+                self.recurrent
+                    .compute_loss_to_input_derivatives(layer_output_to_error_derivative)
+            }
+        }
+    };
+}
+
+pub mod gru;
+pub mod lstm;
+pub mod simple_rnn;
+
+pub use gru::GRU;
+pub use lstm::LSTM;
+pub use simple_rnn::SimpleRNN;
+
+const RECURRENT_PROGRAM_NAME: &str = "RECURRENT";
+const RECURRENT_PROGRAM_SOURCE: &str = include_str!("kernels/recurrent.cl");
+const CELL_HELPERS_SOURCE: &str = include_str!("kernels/cell_helpers.cl");
+
+const SIMPLE_RNN_PROGRAM_NAME: &str = "SIMPLE_RNN";
+const SIMPLE_RNN_PROGRAM_SOURCE: &str = include_str!("kernels/simple_rnn.cl");
+const LSTM_PROGRAM_NAME: &str = "LSTM";
+const LSTM_PROGRAM_SOURCE: &str = include_str!("kernels/lstm.cl");
+const GRU_PROGRAM_NAME: &str = "GRU";
+const GRU_PROGRAM_SOURCE: &str = include_str!("kernels/gru.cl");
+
+const PROPAGATE_TIMESTEP_KERNEL_NAME: &str = "propagate_timestep";
+const BACK_PROPAGATE_TIMESTEP_KERNEL_NAME: &str = "back_propagate_timestep";
+
+const INPUT_WEIGHTS_GRADIENTS_KERNEL_NAME: &str = "compute_input_weights_gradients";
+const RECURRENT_WEIGHTS_GRADIENTS_KERNEL_NAME: &str = "compute_recurrent_weights_gradients";
+const BIAS_GRADIENTS_KERNEL_NAME: &str = "compute_bias_gradients";
+const LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_recurrent(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let recurrent_kernels = &[
+        INPUT_WEIGHTS_GRADIENTS_KERNEL_NAME.to_string(),
+        RECURRENT_WEIGHTS_GRADIENTS_KERNEL_NAME.to_string(),
+        BIAS_GRADIENTS_KERNEL_NAME.to_string(),
+        LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        RECURRENT_PROGRAM_NAME.to_string(),
+        RECURRENT_PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        recurrent_kernels,
+    )?;
+
+    let cell_kernels = &[
+        PROPAGATE_TIMESTEP_KERNEL_NAME.to_string(),
+        BACK_PROPAGATE_TIMESTEP_KERNEL_NAME.to_string(),
+    ];
+
+    for cell in [RecurrentCell::SimpleRNN, RecurrentCell::LSTM, RecurrentCell::GRU] {
+        ensure_program(
+            opencl_state,
+            cell.get_program_name().to_string(),
+            [CELL_HELPERS_SOURCE, cell.get_program_source()].concat(),
+            "".to_string(),
+            cell_kernels,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Savefile)]
+/// The computation a recurrent layer does on each timestep to get to its next state.
+pub enum RecurrentCell {
+    /// Just a tanh of the inputs and the last state multiplied by their weights.
+    SimpleRNN,
+    /// A Long Short-Term Memory cell, with input, forget, candidate and output gates
+    /// as well as a cell state carried between the timesteps.
+    LSTM,
+    /// A Gated Recurrent Unit cell, with update, reset and candidate gates.
+    GRU,
+}
+
+impl RecurrentCell {
+    /// Gets the amount of gates the cell has, each one with its own weights for each unit.
+    pub fn get_gates_amount(&self) -> usize {
+        match self {
+            RecurrentCell::SimpleRNN => 1,
+            RecurrentCell::LSTM => 4,
+            RecurrentCell::GRU => 3,
+        }
+    }
+
+    fn get_program_name(&self) -> &'static str {
+        match self {
+            RecurrentCell::SimpleRNN => SIMPLE_RNN_PROGRAM_NAME,
+            RecurrentCell::LSTM => LSTM_PROGRAM_NAME,
+            RecurrentCell::GRU => GRU_PROGRAM_NAME,
+        }
+    }
+
+    fn get_program_source(&self) -> &'static str {
+        match self {
+            RecurrentCell::SimpleRNN => SIMPLE_RNN_PROGRAM_SOURCE,
+            RecurrentCell::LSTM => LSTM_PROGRAM_SOURCE,
+            RecurrentCell::GRU => GRU_PROGRAM_SOURCE,
+        }
+    }
+}
+
+#[derive(Debug, Default, Savefile)]
+/// The parameters and the last forward pass of one of the directions a recurrent layer goes
+/// through its sequences in.
+pub struct RecurrentDirection {
+    /// The weights of the inputs for each gate, with the shape (features, gates * units).
+    pub input_weights: Vec<Vec<f32>>,
+    /// The weights of the last state for each gate, with the shape (units, gates * units).
+    pub recurrent_weights: Vec<Vec<f32>>,
+    /// The biases of each gate.
+    pub biases: Vec<f32>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened input weights.
+    pub input_weights_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened recurrent weights.
+    pub recurrent_weights_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the biases.
+    pub biases_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The states of each step of the last forward pass, with the shape (steps, samples, units).
+    pub last_states_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cell states of each step of the last forward pass, only used by the LSTM.
+    pub last_cell_states_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The activated gates of each step of the last forward pass, with the shape
+    /// (steps, samples, gates * units).
+    pub last_gates_buffer: Option<Buffer<cl_float>>,
+}
+
+#[derive(Debug, Savefile)]
+/// The layer that does all of the work of the recurrent layers for any of the RecurrentCells,
+/// it is not a ModelLayer by itself and is used through the SimpleRNN, the LSTM and the GRU.
+pub struct Recurrent<'a> {
+    /// The cell this layer uses on each timestep.
+    pub cell: RecurrentCell,
+
+    /// The amount of timesteps in each input sequence.
+    pub timesteps: usize,
+    /// The amount of features in each timestep of the input sequences.
+    pub features: usize,
+    /// The amount of units, which is the size of the state carried between the timesteps.
+    pub units: usize,
+
+    /// Weather or not to output the states of every timestep instead of only the last one.
+    pub return_sequences: bool,
+    /// Weather or not to also go through the sequences backwards with another set of parameters,
+    /// concatenating the states of both directions in the outputs.
+    pub bidirectional: bool,
+
+    /// The parameters of each direction, the first one being forwards and the second one, if
+    /// the layer is bidirectional, backwards.
+    pub directions: Vec<RecurrentDirection>,
+
+    /// The initializers that will generate the initial parameters for each direction of the
+    /// layer (input_weights, recurrent_weights and biases).
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this layer.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this layer.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> From<Recurrent<'a>> for ModelLayer<'a> {
+    fn from(recurrent: Recurrent<'a>) -> Self {
+        match recurrent.cell {
+            RecurrentCell::SimpleRNN => SimpleRNN { recurrent }.into(),
+            RecurrentCell::LSTM => LSTM { recurrent }.into(),
+            RecurrentCell::GRU => GRU { recurrent }.into(),
+        }
+    }
+}
+
+impl<'a> Recurrent<'a> {
+    /// Creates a new unidirectional Recurrent layer that only returns its last state.
+    pub fn new_raw(
+        cell: RecurrentCell,
+        timesteps: usize,
+        features: usize,
+        units: usize,
+    ) -> Recurrent<'a> {
+        let mut initializers = HashMap::with_capacity(3);
+        initializers.insert(
+            "input_weights".to_string(),
+            GlorotUniformInitializer::new().into(),
+        );
+        initializers.insert(
+            "recurrent_weights".to_string(),
+            GlorotUniformInitializer::new().into(),
+        );
+        initializers.insert("biases".to_string(), ConstantInitializer::new(0.0).into());
+
+        Recurrent {
+            cell,
+
+            timesteps,
+            features,
+            units,
+
+            return_sequences: false,
+            bidirectional: false,
+
+            directions: Vec::default(),
+
+            initializers,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Gets the amount of directions this layer goes through the sequences in.
+    pub fn get_directions_amount(&self) -> usize {
+        if self.bidirectional {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn get_gates_width(&self) -> usize {
+        self.cell.get_gates_amount() * self.units
+    }
+
+    fn get_parameter_id(direction_index: usize, parameter: &str) -> String {
+        if direction_index == 0 {
+            parameter.to_string()
+        } else {
+            format!("backward_{}", parameter)
+        }
+    }
+
+    // goes back through the timesteps of one of the directions computing the derivatives of the
+    // loss with respect to the pre activations of the gates, for the input and the recurrent
+    // weights respectively
+    fn back_propagate_through_time<E>(
+        &self,
+        direction_index: usize,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+        samples_amount: usize,
+        state: &OpenCLState,
+    ) -> Result<(Buffer<cl_float>, Buffer<cl_float>), E>
+    where
+        E: From<ClError> + From<ProgramNotFoundError> + From<KernelNotFoundError>,
+    {
+        let queue = state.queues.first().unwrap();
+        let direction = &self.directions[direction_index];
+
+        let gates_width = self.get_gates_width();
+        let output_width = self.get_directions_amount() * self.units;
+
+        let input_gate_derivatives = empty_buffer(
+            self.timesteps * samples_amount * gates_width,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+        let recurrent_gate_derivatives = empty_buffer(
+            self.timesteps * samples_amount * gates_width,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+        let carried_derivatives = empty_buffer(
+            self.timesteps * samples_amount * self.units,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let states_buffer = direction.last_states_buffer.as_ref().unwrap();
+
+        let program = state.get_prgm(self.cell.get_program_name())?;
+        let kernel = program.get_krnl(BACK_PROPAGATE_TIMESTEP_KERNEL_NAME)?;
+
+        for step in (0..self.timesteps).rev() {
+            let timestep = get_timestep(direction_index, step, self.timesteps);
+
+            ExecuteKernel::new(kernel)
+                .set_arg(layer_output_to_error_derivative)
+                .set_arg(direction.recurrent_weights_buffer.as_ref().unwrap())
+                .set_arg(states_buffer)
+                .set_arg(direction.last_cell_states_buffer.as_ref().unwrap_or(states_buffer))
+                .set_arg(direction.last_gates_buffer.as_ref().unwrap())
+                .set_arg(&input_gate_derivatives)
+                .set_arg(&recurrent_gate_derivatives)
+                .set_arg(&carried_derivatives)
+                .set_arg(&(step as cl_int))
+                .set_arg(&(timestep as cl_int))
+                .set_arg(&(samples_amount as cl_int))
+                .set_arg(&(self.timesteps as cl_int))
+                .set_arg(&(self.units as cl_int))
+                .set_arg(&(self.return_sequences as cl_int))
+                .set_arg(&(output_width as cl_int))
+                .set_arg(&((direction_index * self.units) as cl_int))
+                .set_global_work_sizes(&[samples_amount, self.units])
+                .enqueue_nd_range(queue)?;
+        }
+
+        queue.finish()?;
+
+        Ok((input_gate_derivatives, recurrent_gate_derivatives))
+    }
+}
+
+// the index of the timestep of the inputs that is used on a certain step of a direction
+fn get_timestep(direction_index: usize, step: usize, timesteps: usize) -> usize {
+    if direction_index == 1 {
+        timesteps - 1 - step
+    } else {
+        step
+    }
+}
+
+fn flatten(matrix: &Vec<Vec<f32>>) -> Vec<f32> {
+    matrix.par_iter().flatten().map(|x| *x).collect()
+}
+
+fn unflatten(flat: &Vec<f32>, columns: usize) -> Vec<Vec<f32>> {
+    flat.par_chunks(columns).map(|row| row.to_vec()).collect()
+}
+
+impl<'a> Layer<'a> for Recurrent<'a> {
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.timesteps * self.features
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        if self.return_sequences {
+            self.timesteps * self.get_directions_amount() * self.units
+        } else {
+            self.get_directions_amount() * self.units
+        }
+    }
+
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        let (direction_index, parameter) = match parameter.strip_prefix("backward_") {
+            Some(parameter) => (1, parameter),
+            None => (0, parameter),
+        };
+
+        let direction = self.directions.get(direction_index)?;
+
+        match parameter {
+            "input_weights" => Some(flatten(&direction.input_weights)),
+            "recurrent_weights" => Some(flatten(&direction.recurrent_weights)),
+            "biases" => Some(direction.biases.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        let gates_width = self.get_gates_width();
+
+        self.directions
+            .resize_with(self.get_directions_amount(), RecurrentDirection::default);
+
+        for direction_index in 0..self.directions.len() {
+            if self.directions[direction_index].input_weights.is_empty() {
+                if let Some(initializer) = self.initializers.get("input_weights") {
+                    self.directions[direction_index].input_weights =
+                        initializer.initialize_2d((self.features, gates_width), self);
+                } else {
+                    return Err(LayerInitializationError::MissingParameterInitializer(
+                        "input_weights",
+                    ));
+                }
+            }
+
+            if self.directions[direction_index].recurrent_weights.is_empty() {
+                if let Some(initializer) = self.initializers.get("recurrent_weights") {
+                    self.directions[direction_index].recurrent_weights =
+                        initializer.initialize_2d((self.units, gates_width), self);
+                } else {
+                    return Err(LayerInitializationError::MissingParameterInitializer(
+                        "recurrent_weights",
+                    ));
+                }
+            }
+
+            if self.directions[direction_index].biases.is_empty() {
+                if let Some(initializer) = self.initializers.get("biases") {
+                    self.directions[direction_index].biases =
+                        initializer.initialize_1d(gates_width, self);
+                } else {
+                    return Err(LayerInitializationError::MissingParameterInitializer(
+                        "biases",
+                    ));
+                }
+            }
+
+            let direction = &mut self.directions[direction_index];
+
+            direction.input_weights_buffer =
+                Some(flatten(&direction.input_weights).to_buffer(false, opencl_state)?);
+            direction.recurrent_weights_buffer =
+                Some(flatten(&direction.recurrent_weights).to_buffer(false, opencl_state)?);
+            direction.biases_buffer = Some(direction.biases.to_buffer(false, opencl_state)?);
+        }
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        for direction in self.directions.iter() {
+            if direction.input_weights_buffer.is_some() {
+                drop(direction.input_weights_buffer.as_ref().unwrap());
+            }
+
+            if direction.recurrent_weights_buffer.is_some() {
+                drop(direction.recurrent_weights_buffer.as_ref().unwrap());
+            }
+
+            if direction.biases_buffer.is_some() {
+                drop(direction.biases_buffer.as_ref().unwrap());
+            }
+
+            if direction.last_states_buffer.is_some() {
+                drop(direction.last_states_buffer.as_ref().unwrap());
+            }
+
+            if direction.last_cell_states_buffer.is_some() {
+                drop(direction.last_cell_states_buffer.as_ref().unwrap());
+            }
+
+            if direction.last_gates_buffer.is_some() {
+                drop(direction.last_gates_buffer.as_ref().unwrap());
+            }
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        let gates_width = self.get_gates_width();
+
+        for direction in self.directions.iter_mut() {
+            if direction.input_weights_buffer.is_none() {
+                return Err(SyncDataError::NotAllocatedInDevice {
+                    field_name: "input_weights_buffer".to_string(),
+                });
+            }
+
+            if direction.recurrent_weights_buffer.is_none() {
+                return Err(SyncDataError::NotAllocatedInDevice {
+                    field_name: "recurrent_weights_buffer".to_string(),
+                });
+            }
+
+            if direction.biases_buffer.is_none() {
+                return Err(SyncDataError::NotAllocatedInDevice {
+                    field_name: "biases_buffer".to_string(),
+                });
+            }
+
+            let input_weights = Vec::<f32>::from_buffer(
+                direction.input_weights_buffer.as_ref().unwrap(),
+                false,
+                state,
+            )?;
+            let recurrent_weights = Vec::<f32>::from_buffer(
+                direction.recurrent_weights_buffer.as_ref().unwrap(),
+                false,
+                state,
+            )?;
+
+            direction.input_weights = unflatten(&input_weights, gates_width);
+            direction.recurrent_weights = unflatten(&recurrent_weights, gates_width);
+            direction.biases =
+                Vec::<f32>::from_buffer(direction.biases_buffer.as_ref().unwrap(), false, state)?;
+        }
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_total_count = inputs.size()? / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let outputs_buffer = empty_buffer(
+            self.get_outputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(self.cell.get_program_name())?;
+        let kernel = program.get_krnl(PROPAGATE_TIMESTEP_KERNEL_NAME)?;
+
+        let timesteps = self.timesteps;
+        let features = self.features;
+        let units = self.units;
+        let gates_width = self.get_gates_width();
+        let output_width = self.get_directions_amount() * units;
+        let return_sequences = self.return_sequences;
+        let has_cell_states = self.cell == RecurrentCell::LSTM;
+
+        for (direction_index, direction) in self.directions.iter_mut().enumerate() {
+            let states_buffer =
+                empty_buffer(timesteps * samples_amount * units, CL_MEM_READ_WRITE, state)?;
+            let gates_buffer = empty_buffer(
+                timesteps * samples_amount * gates_width,
+                CL_MEM_READ_WRITE,
+                state,
+            )?;
+            let cell_states_buffer = if has_cell_states {
+                Some(empty_buffer(
+                    timesteps * samples_amount * units,
+                    CL_MEM_READ_WRITE,
+                    state,
+                )?)
+            } else {
+                None
+            };
+
+            // the steps need to happen in order since each one uses the state of the last
+            for step in 0..timesteps {
+                let timestep = get_timestep(direction_index, step, timesteps);
+
+                ExecuteKernel::new(kernel)
+                    .set_arg(inputs)
+                    .set_arg(direction.input_weights_buffer.as_ref().unwrap())
+                    .set_arg(direction.recurrent_weights_buffer.as_ref().unwrap())
+                    .set_arg(direction.biases_buffer.as_ref().unwrap())
+                    .set_arg(&states_buffer)
+                    .set_arg(cell_states_buffer.as_ref().unwrap_or(&states_buffer))
+                    .set_arg(&gates_buffer)
+                    .set_arg(&outputs_buffer)
+                    .set_arg(&(step as cl_int))
+                    .set_arg(&(timestep as cl_int))
+                    .set_arg(&(samples_amount as cl_int))
+                    .set_arg(&(timesteps as cl_int))
+                    .set_arg(&(features as cl_int))
+                    .set_arg(&(units as cl_int))
+                    .set_arg(&(return_sequences as cl_int))
+                    .set_arg(&(output_width as cl_int))
+                    .set_arg(&((direction_index * units) as cl_int))
+                    .set_global_work_sizes(&[samples_amount, units])
+                    .enqueue_nd_range(queue)?;
+            }
+
+            queue.finish()?;
+
+            direction.last_states_buffer = Some(states_buffer);
+            direction.last_cell_states_buffer = cell_states_buffer;
+            direction.last_gates_buffer = Some(gates_buffer);
+        }
+
+        self.last_outputs_buffer = Some(outputs_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_total_count =
+            layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        if self.last_inputs_buffer.is_none()
+            || self
+                .directions
+                .iter()
+                .any(|direction| direction.last_states_buffer.is_none())
+        {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+        let gates_width = self.get_gates_width();
+
+        let program = state.get_prgm(RECURRENT_PROGRAM_NAME)?;
+        let input_weights_kernel = program.get_krnl(INPUT_WEIGHTS_GRADIENTS_KERNEL_NAME)?;
+        let recurrent_weights_kernel = program.get_krnl(RECURRENT_WEIGHTS_GRADIENTS_KERNEL_NAME)?;
+        let biases_kernel = program.get_krnl(BIAS_GRADIENTS_KERNEL_NAME)?;
+
+        let mut gradients = Vec::with_capacity(self.directions.len() * 3);
+
+        for (direction_index, direction) in self.directions.iter().enumerate() {
+            let (input_gate_derivatives, recurrent_gate_derivatives) = self
+                .back_propagate_through_time::<LayerGradientComputationError>(
+                    direction_index,
+                    layer_output_to_error_derivative,
+                    samples_amount,
+                    state,
+                )?;
+
+            let input_weights_gradients =
+                empty_buffer(self.features * gates_width, CL_MEM_READ_WRITE, state)?;
+            let recurrent_weights_gradients =
+                empty_buffer(self.units * gates_width, CL_MEM_READ_WRITE, state)?;
+            let biases_gradients = empty_buffer(gates_width, CL_MEM_READ_WRITE, state)?;
+
+            ExecuteKernel::new(input_weights_kernel)
+                .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+                .set_arg(&input_gate_derivatives)
+                .set_arg(&input_weights_gradients)
+                .set_arg(&(direction_index as cl_int))
+                .set_arg(&(samples_amount as cl_int))
+                .set_arg(&(self.timesteps as cl_int))
+                .set_arg(&(self.features as cl_int))
+                .set_arg(&(gates_width as cl_int))
+                .set_global_work_sizes(&[self.features, gates_width])
+                .enqueue_nd_range(queue)?;
+
+            ExecuteKernel::new(recurrent_weights_kernel)
+                .set_arg(direction.last_states_buffer.as_ref().unwrap())
+                .set_arg(&recurrent_gate_derivatives)
+                .set_arg(&recurrent_weights_gradients)
+                .set_arg(&(samples_amount as cl_int))
+                .set_arg(&(self.timesteps as cl_int))
+                .set_arg(&(self.units as cl_int))
+                .set_arg(&(gates_width as cl_int))
+                .set_global_work_sizes(&[self.units, gates_width])
+                .enqueue_nd_range(queue)?;
+
+            ExecuteKernel::new(biases_kernel)
+                .set_arg(&input_gate_derivatives)
+                .set_arg(&biases_gradients)
+                .set_arg(&(samples_amount as cl_int))
+                .set_arg(&(self.timesteps as cl_int))
+                .set_arg(&(gates_width as cl_int))
+                .set_global_work_size(gates_width)
+                .enqueue_nd_range(queue)?;
+
+            queue.finish()?;
+
+            gradients.push(Gradient {
+                parameter_id: Self::get_parameter_id(direction_index, "input_weights"),
+                value: input_weights_gradients,
+                optimizable: true,
+            });
+            gradients.push(Gradient {
+                parameter_id: Self::get_parameter_id(direction_index, "recurrent_weights"),
+                value: recurrent_weights_gradients,
+                optimizable: true,
+            });
+            gradients.push(Gradient {
+                parameter_id: Self::get_parameter_id(direction_index, "biases"),
+                value: biases_gradients,
+                optimizable: true,
+            });
+        }
+
+        Ok(gradients)
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        for (direction_index, direction) in self.directions.iter_mut().enumerate() {
+            if direction.input_weights_buffer.is_none() {
+                return Err(ParametersOptimizationError::EmptyParameter(
+                    Self::get_parameter_id(direction_index, "input_weights"),
+                ));
+            }
+
+            if direction.recurrent_weights_buffer.is_none() {
+                return Err(ParametersOptimizationError::EmptyParameter(
+                    Self::get_parameter_id(direction_index, "recurrent_weights"),
+                ));
+            }
+
+            if direction.biases_buffer.is_none() {
+                return Err(ParametersOptimizationError::EmptyParameter(
+                    Self::get_parameter_id(direction_index, "biases"),
+                ));
+            }
+
+            optimizer.optimize_parameters(
+                direction.input_weights_buffer.as_mut().unwrap(),
+                Self::get_parameter_id(direction_index, "input_weights"),
+                timestep,
+                layer_index,
+            )?;
+            optimizer.optimize_parameters(
+                direction.recurrent_weights_buffer.as_mut().unwrap(),
+                Self::get_parameter_id(direction_index, "recurrent_weights"),
+                timestep,
+                layer_index,
+            )?;
+            optimizer.optimize_parameters(
+                direction.biases_buffer.as_mut().unwrap(),
+                Self::get_parameter_id(direction_index, "biases"),
+                timestep,
+                layer_index,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != self.directions.len() * 3 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_index,
+            timestep,
+            state,
+        )?;
+
+        for (direction, direction_update_vectors) in self
+            .directions
+            .iter_mut()
+            .zip(update_vectors.chunks(3))
+        {
+            direction
+                .input_weights_buffer
+                .as_mut()
+                .unwrap()
+                .subtract_inplc(&direction_update_vectors[0], state)?;
+            direction
+                .recurrent_weights_buffer
+                .as_mut()
+                .unwrap()
+                .subtract_inplc(&direction_update_vectors[1], state)?;
+            direction
+                .biases_buffer
+                .as_mut()
+                .unwrap()
+                .subtract_inplc(&direction_update_vectors[2], state)?;
+        }
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_total_count =
+            layer_output_to_error_derivative.size()? / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        if self
+            .directions
+            .iter()
+            .any(|direction| direction.last_states_buffer.is_none())
+        {
+            return Err(LayerLossToInputDifferentiationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+        let gates_width = self.get_gates_width();
+
+        let loss_to_input_derivatives = empty_buffer(
+            samples_amount * self.get_inputs_amount(),
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(RECURRENT_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(LOSS_TO_INPUT_DIFFERENTIATION_KERNEL_NAME)?;
+
+        for (direction_index, direction) in self.directions.iter().enumerate() {
+            let (input_gate_derivatives, _) = self
+                .back_propagate_through_time::<LayerLossToInputDifferentiationError>(
+                    direction_index,
+                    layer_output_to_error_derivative,
+                    samples_amount,
+                    state,
+                )?;
+
+            ExecuteKernel::new(kernel)
+                .set_arg(direction.input_weights_buffer.as_ref().unwrap())
+                .set_arg(&input_gate_derivatives)
+                .set_arg(&loss_to_input_derivatives)
+                .set_arg(&(direction_index as cl_int))
+                .set_arg(&((direction_index > 0) as cl_int))
+                .set_arg(&(samples_amount as cl_int))
+                .set_arg(&(self.timesteps as cl_int))
+                .set_arg(&(self.features as cl_int))
+                .set_arg(&(gates_width as cl_int))
+                .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+                .enqueue_nd_range(queue)?;
+
+            queue.finish()?;
+        }
+
+        Ok(loss_to_input_derivatives)
+    }
+}
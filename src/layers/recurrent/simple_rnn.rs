@@ -0,0 +1,232 @@
+//! The module that contains the SimpleRNN layer.
+
+use opencl3::{device::cl_float, memory::Buffer};
+use savefile_derive::Savefile;
+
+use crate::types::ModelLayer;
+
+use super::{Recurrent, RecurrentCell};
+
+#[derive(Debug, Savefile)]
+/// A recurrent layer that on each timestep computes its next state as the tanh of the inputs of
+/// the timestep and its last state multiplied by their respective weights.
+///
+/// Expects the inputs of each sample to be a flattened sequence of `timesteps` timesteps with
+/// `features` features each.
+pub struct SimpleRNN<'a> {
+    /// The Recurrent layer that does all of the work of this layer.
+    pub recurrent: Recurrent<'a>,
+}
+
+impl<'a> SimpleRNN<'a> {
+    /// Creates a new SimpleRNN raw, meaning that it is not inside of a ModelLayer enum.
+    pub fn new_raw(timesteps: usize, features: usize, units: usize) -> SimpleRNN<'a> {
+        SimpleRNN {
+            recurrent: Recurrent::new_raw(RecurrentCell::SimpleRNN, timesteps, features, units),
+        }
+    }
+
+    /// Creates a new SimpleRNN inside of a ModelLayer enum.
+    pub fn new(timesteps: usize, features: usize, units: usize) -> ModelLayer<'a> {
+        Self::new_raw(timesteps, features, units).into()
+    }
+
+    /// Sets weather or not the layer outputs its states on all of the timesteps instead of only
+    /// the last one, which is **false** by default.
+    pub fn set_return_sequences(mut self, return_sequences: bool) -> Self {
+        self.recurrent.return_sequences = return_sequences;
+
+        self
+    }
+
+    /// Sets weather or not the layer also goes through the sequences backwards, which is
+    /// **false** by default.
+    pub fn set_bidirectional(mut self, bidirectional: bool) -> Self {
+        self.recurrent.bidirectional = bidirectional;
+
+        self
+    }
+}
+
+impl_layer_for_recurrent_wrapper!(SimpleRNN);
+
+#[cfg(test)]
+mod simple_rnn_tests {
+    use crate::{
+        layers::{recurrent::RecurrentDirection, Layer},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::SimpleRNN;
+
+    #[test]
+    fn should_propagate_and_back_propagate_through_time_correctly() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.7, -0.3, 0.4, 0.9,
+            -0.5, 0.3, 0.8, 0.1, 0.0, 0.4,
+        ];
+        let loss_to_output_derivatives = vec![
+            -0.2, -0.3, 0.2, -0.4, 0.0, -0.1,
+            -0.4, 0.0, -0.5, -0.1, -0.4, -0.4,
+        ];
+
+        let expected_outputs = vec![
+            -0.1877, -0.0699, -0.6267, 0.2192, -0.5306, -0.3785,
+            -0.01, -0.207, -0.4717, 0.2902, -0.4868, -0.3668,
+        ];
+        let expected_input_weights_gradients = vec![-0.0626, -0.135, -0.2739, -0.0713];
+        let expected_recurrent_weights_gradients = vec![0.0656, 0.1404, 0.0158, -0.0627];
+        let expected_biases_gradients = vec![-0.8735, -0.3269];
+        let expected_loss_to_input_derivatives = vec![
+            0.0515, -0.0436, -0.1447, 0.0998, -0.0257, 0.0171,
+            0.3132, -0.2304, 0.2901, -0.2139, 0.0182, -0.0223,
+        ];
+
+        let mut layer = SimpleRNN::new_raw(3, 2, 2).set_return_sequences(true);
+        layer.recurrent.directions.push(RecurrentDirection {
+            input_weights: vec![vec![-0.4, 0.3], vec![0.3, -0.2]],
+            recurrent_weights: vec![vec![0.5, 0.4], vec![-0.4, -0.4]],
+            biases: vec![-0.3, 0.0],
+            ..Default::default()
+        });
+        layer.init(&opencl_state).expect("unable to init SimpleRNN");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the SimpleRNN");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the SimpleRNN");
+        let gradients = gradients
+            .iter()
+            .map(|gradient| {
+                Vec::<f32>::from_buffer(&gradient.value, false, &opencl_state)
+                    .expect("unable to read a gradients buffer")
+            })
+            .collect::<Vec<Vec<f32>>>();
+
+        assert_approx_equal_distance(&gradients[0], &expected_input_weights_gradients, 0.01);
+        assert_approx_equal_distance(&gradients[1], &expected_recurrent_weights_gradients, 0.01);
+        assert_approx_equal_distance(&gradients[2], &expected_biases_gradients, 0.01);
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the SimpleRNN");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_go_through_the_sequences_in_both_directions_when_bidirectional() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.7, -0.3, 0.4, 0.9,
+            -0.5, 0.3, 0.8, 0.1, 0.0, 0.4,
+        ];
+        let loss_to_output_derivatives = vec![
+            -0.2, -0.3, 0.2, -0.4,
+            0.0, -0.1, -0.4, 0.0,
+        ];
+
+        let expected_outputs = vec![
+            -0.5306, -0.3785, 0.2466, 0.1571,
+            -0.4868, -0.3668, 0.4255, 0.4337,
+        ];
+        let expected_gradients = vec![
+            vec![-0.076, 0.0142, -0.0483, -0.1592],
+            vec![0.0551, 0.0865, -0.0093, -0.0493],
+            vec![-0.1349, -0.0898],
+            vec![0.0527, -0.1046, -0.0107, -0.1223],
+            vec![-0.0193, -0.0344, -0.0037, -0.0171],
+            vec![-0.1388, -0.3453],
+        ];
+        let expected_loss_to_input_derivatives = vec![
+            0.0911, 0.1043, 0.1455, -0.0267, 0.0105, 0.0277,
+            0.098, 0.0329, 0.069, 0.0181, -0.0007, 0.0336,
+        ];
+
+        let mut layer = SimpleRNN::new_raw(3, 2, 2).set_bidirectional(true);
+        layer.recurrent.directions.push(RecurrentDirection {
+            input_weights: vec![vec![-0.4, 0.3], vec![0.3, -0.2]],
+            recurrent_weights: vec![vec![0.5, 0.4], vec![-0.4, -0.4]],
+            biases: vec![-0.3, 0.0],
+            ..Default::default()
+        });
+        layer.recurrent.directions.push(RecurrentDirection {
+            input_weights: vec![vec![-0.3, -0.4], vec![-0.1, -0.3]],
+            recurrent_weights: vec![vec![0.1, 0.2], vec![0.3, 0.4]],
+            biases: vec![0.3, 0.3],
+            ..Default::default()
+        });
+        layer.init(&opencl_state).expect("unable to init SimpleRNN");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the SimpleRNN");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the SimpleRNN");
+
+        assert_eq!(gradients[3].parameter_id, "backward_input_weights");
+
+        for (gradient, expected_gradient) in gradients.iter().zip(expected_gradients.iter()) {
+            let gradient = Vec::<f32>::from_buffer(&gradient.value, false, &opencl_state)
+                .expect("unable to read a gradients buffer");
+
+            assert_approx_equal_distance(&gradient, expected_gradient, 0.01);
+        }
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the SimpleRNN");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
@@ -0,0 +1,449 @@
+//! The module that contains the layers that only change the shape of their inputs, Flatten and
+//! Reshape.
+
+use std::{ffi::c_void, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::ClError,
+    memory::{cl_buffer_region, create_sub_buffer, Buffer, ClMem, CL_BUFFER_CREATE_TYPE_REGION},
+};
+
+use savefile_derive::Savefile;
+
+use crate::{
+    layers::{
+        initializers::Initializer, Gradient, Layer, LayerGradientApplicationError,
+        LayerGradientComputationError, LayerInitializationError,
+        LayerLossToInputDifferentiationError, LayerPropagationError, ParametersOptimizationError,
+        SyncDataError,
+    },
+    optimizers::Optimizer,
+    types::ModelLayer,
+    utils::{opencl::BufferOperationError, OpenCLState},
+};
+
+// the numbers of every sample are laid out the same way in the device no matter their shape, so
+// the outputs can just be a view over the same memory as the inputs
+//
+// OpenCL can't make sub-buffers out of other sub-buffers, which is what the Model gives out as the
+// batches while fitting, so in that case the view is made over the same region of the parent
+fn view_of_buffer(buffer: &Buffer<cl_float>) -> Result<Buffer<cl_float>, BufferOperationError> {
+    let parent = buffer.associated_memobject()?;
+    let (parent, origin) = if parent.is_null() {
+        (buffer.get(), 0)
+    } else {
+        (parent, buffer.offset()?)
+    };
+
+    let region = cl_buffer_region {
+        origin,
+        size: buffer.size()?,
+    };
+    let view = create_sub_buffer(
+        parent,
+        0,
+        CL_BUFFER_CREATE_TYPE_REGION,
+        &region as *const cl_buffer_region as *const c_void,
+    )
+    .map_err(ClError)?;
+
+    Ok(Buffer::new(view))
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that flattens inputs of any shape into just a list of numbers per sample, used mostly
+/// to go from convolutional layers into Dense layers while stating explicitly the shape of the
+/// images that are coming in.
+///
+/// The shape is the one of a single sample, for example `vec![channels, height, width]` for the
+/// outputs of a Conv2D, and since the numbers are already laid out contiguously in the device,
+/// this does not copy anything when propagating.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::Flatten;
+///
+/// // this will make a layer that takes 8 channels of 26x26 images and outputs 5408 numbers
+/// let my_layer: Flatten = Flatten::new_raw(vec![8, 26, 26]);
+/// ```
+pub struct Flatten<'a> {
+    /// The shape of the inputs of each sample.
+    pub inputs_shape: Vec<usize>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The inputs last forward passed into this Flatten.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this Flatten.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> Flatten<'a> {
+    /// Creates a raw version of the Flatten layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(inputs_shape: Vec<usize>) -> Flatten<'a> {
+        assert!(
+            !inputs_shape.is_empty() && inputs_shape.iter().all(|size| *size > 0),
+            "the shape of the inputs of a Flatten must have only non-zero sizes"
+        );
+
+        Flatten {
+            inputs_shape,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the Flatten layer, to be used with a Model.
+    pub fn new(inputs_shape: Vec<usize>) -> ModelLayer<'a> {
+        Self::new_raw(inputs_shape).into()
+    }
+
+    fn get_reshaped_shape(&self) -> Vec<usize> {
+        vec![self.inputs_shape.iter().product()]
+    }
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that changes the shape of the inputs of each sample into another shape with the same
+/// amount of numbers, keeping them in the same order.
+///
+/// Since the numbers are already laid out contiguously in the device, this does not copy
+/// anything when propagating, the shapes are there so that the layers before and after it can
+/// be checked to agree with each other.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::Reshape;
+///
+/// // this will make a layer that takes 784 numbers and outputs them as one 28x28 image
+/// let my_layer: Reshape = Reshape::new_raw(vec![784], vec![1, 28, 28]);
+/// ```
+pub struct Reshape<'a> {
+    /// The shape of the inputs of each sample.
+    pub inputs_shape: Vec<usize>,
+    /// The shape of the outputs of each sample.
+    pub outputs_shape: Vec<usize>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The inputs last forward passed into this Reshape.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this Reshape.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> Reshape<'a> {
+    /// Creates a raw version of the Reshape layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if both shapes do not have the same amount of numbers.
+    pub fn new_raw(inputs_shape: Vec<usize>, outputs_shape: Vec<usize>) -> Reshape<'a> {
+        assert!(
+            !inputs_shape.is_empty() && inputs_shape.iter().all(|size| *size > 0),
+            "the shape of the inputs of a Reshape must have only non-zero sizes"
+        );
+        assert_eq!(
+            inputs_shape.iter().product::<usize>(),
+            outputs_shape.iter().product::<usize>(),
+            "the inputs shape {:?} of a Reshape can't be reshaped into {:?}",
+            inputs_shape,
+            outputs_shape,
+        );
+
+        Reshape {
+            inputs_shape,
+            outputs_shape,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the Reshape layer, to be used with a Model.
+    pub fn new(inputs_shape: Vec<usize>, outputs_shape: Vec<usize>) -> ModelLayer<'a> {
+        Self::new_raw(inputs_shape, outputs_shape).into()
+    }
+
+    fn get_reshaped_shape(&self) -> Vec<usize> {
+        self.outputs_shape.clone()
+    }
+}
+
+macro_rules! impl_reshaping_layer {
+    ($layer:ident) => {
+        impl<'a> Layer<'a> for $layer<'a> {
+            fn get_flattened_parameter_data(&self, _parameter: &str) -> Option<Vec<f32>> {
+                None
+            }
+
+            fn get_initializer_for_parameter<'b>(
+                &'b self,
+                _parameter: &str,
+            ) -> Option<&'b Initializer> {
+                None
+            }
+
+            fn set_initializer_for_parameter(
+                self,
+                _initializer: Initializer,
+                _parameter: &'a str,
+            ) -> ModelLayer<'a> {
+                self.into()
+            }
+
+            fn init(
+                &mut self,
+                opencl_state: &'a OpenCLState,
+            ) -> Result<(), LayerInitializationError> {
+                self.opencl_state = Some(opencl_state);
+
+                Ok(())
+            }
+
+            fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+                self.last_inputs_buffer.as_ref()
+            }
+
+            fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+                self.last_outputs_buffer.as_ref()
+            }
+
+            fn get_inputs_amount(&self) -> usize {
+                self.inputs_shape.iter().product()
+            }
+
+            fn get_outputs_amount(&self) -> usize {
+                // both shapes are guaranteed to have the same amount of numbers
+                self.inputs_shape.iter().product()
+            }
+
+            fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+                Some(self.inputs_shape.clone())
+            }
+
+            fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+                Some(self.get_reshaped_shape())
+            }
+
+            fn clean_up_gpu_state(&mut self) -> () {
+                if self.last_inputs_buffer.is_some() {
+                    drop(self.last_inputs_buffer.as_ref().unwrap());
+                }
+
+                if self.last_outputs_buffer.is_some() {
+                    drop(self.last_outputs_buffer.as_ref().unwrap());
+                }
+            }
+
+            fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+                Ok(())
+            }
+
+            fn propagate(
+                &mut self,
+                inputs: &Buffer<cl_float>,
+            ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+                if self.opencl_state.is_none() {
+                    return Err(LayerPropagationError::LayerNotInitialized);
+                }
+
+                let inputs_size = inputs.size()?;
+                let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+                if inputs_total_count % self.get_inputs_amount() != 0 {
+                    return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+                }
+
+                self.last_inputs_buffer = Some(view_of_buffer(inputs)?);
+                self.last_outputs_buffer = Some(view_of_buffer(inputs)?);
+
+                Ok(self.last_outputs_buffer.as_ref().unwrap())
+            }
+
+            fn apply_gradients(
+                &mut self,
+                _per_parameter_type_gradients: &[Gradient],
+                _optimizer: &mut dyn Optimizer<'a>,
+                _layer_index: usize,
+                _timestep: usize,
+            ) -> Result<(), LayerGradientApplicationError> {
+                Ok(())
+            }
+
+            fn compute_gradients(
+                &self,
+                _layer_output_to_error_derivative: &Buffer<cl_float>,
+            ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+                Ok(Vec::default())
+            }
+
+            fn optimize_parameters(
+                &mut self,
+                _optimizer: &dyn Optimizer<'a>,
+                _layer_index: usize,
+                _timestep: usize,
+            ) -> Result<(), ParametersOptimizationError> {
+                Ok(())
+            }
+
+            fn compute_loss_to_input_derivatives(
+                &self,
+                layer_output_to_error_derivative: &Buffer<cl_float>,
+            ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+                if self.opencl_state.is_none() {
+                    return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+                }
+
+                let derivatives_size = layer_output_to_error_derivative.size()?;
+                let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+                if derivatives_total_count % self.get_outputs_amount() != 0 {
+                    return Err(
+                        LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape,
+                    );
+                }
+
+                Ok(view_of_buffer(layer_output_to_error_derivative)?)
+            }
+        }
+    };
+}
+
+impl_reshaping_layer!(Flatten);
+impl_reshaping_layer!(Reshape);
+
+#[cfg(test)]
+mod reshape_tests {
+    use opencl3::memory::ClMem;
+
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::{Flatten, Reshape};
+
+    #[test]
+    fn should_keep_the_same_numbers_when_flattening_and_reshaping() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.3, 0.7, 0.2, 0.9,
+            0.4, 0.8, 0.6, 0.3, 0.5, 0.1,
+        ];
+        let loss_to_output_derivatives = vec![
+            -0.1, 0.2, 0.3, -0.4, 0.5, 0.6,
+            0.7, -0.8, 0.9, 1.0, -1.1, 1.2,
+        ];
+
+        let mut reshape = Reshape::new_raw(vec![6], vec![2, 3]);
+        let mut flatten = Flatten::new_raw(vec![2, 3]);
+        assert_eq!(reshape.get_outputs_amount(), flatten.get_inputs_amount());
+
+        reshape.init(&opencl_state).expect("unable to init Reshape");
+        flatten.init(&opencl_state).expect("unable to init Flatten");
+
+        let inputs_buffer = inputs
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the inputs buffer");
+        let reshaped_buffer = reshape
+            .propagate(&inputs_buffer)
+            .expect("unable to propagate the Reshape");
+        let outputs_buffer = flatten
+            .propagate(reshaped_buffer)
+            .expect("unable to propagate the Flatten");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &inputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+        let loss_to_reshaped_derivatives = flatten
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the Flatten");
+        let loss_to_input_derivatives_buffer = reshape
+            .compute_loss_to_input_derivatives(&loss_to_reshaped_derivatives)
+            .expect("unable to compute the loss to input derivatives of the Reshape");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &loss_to_output_derivatives,
+            0.01,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_reshape_into_a_shape_with_a_different_amount_of_numbers() {
+        Reshape::new_raw(vec![2, 3], vec![4, 2]);
+    }
+
+    #[test]
+    fn should_propagate_batches_that_are_sub_buffers_without_copying_them() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.3, 0.7, 0.2, 0.9,
+            0.4, 0.8, 0.6, 0.3, 0.5, 0.1,
+        ];
+
+        let mut flatten = Flatten::new_raw(vec![2, 3]);
+        flatten.init(&opencl_state).expect("unable to init Flatten");
+
+        let inputs_buffer = inputs
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the inputs buffer");
+        // the same way that the Model gives out the batches while fitting
+        let batch_buffer = inputs_buffer
+            .create_sub_buffer(0, 6, 6)
+            .expect("unable to create the batch sub-buffer");
+
+        let outputs_buffer = flatten
+            .propagate(&batch_buffer)
+            .expect("unable to propagate the Flatten");
+
+        assert_eq!(
+            outputs_buffer
+                .associated_memobject()
+                .expect("unable to get the memory object of the outputs"),
+            inputs_buffer.get()
+        );
+
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &inputs[6..].to_vec(), 0.01);
+    }
+}
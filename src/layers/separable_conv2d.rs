@@ -0,0 +1,1006 @@
+//! The module that defines the depthwise separable covolutional layer
+
+use std::{collections::HashMap, mem};
+
+use opencl3::{
+    device::cl_float,
+    error_codes::{cl_int, ClError},
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+use rayon::prelude::*;
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{KernelNotFoundError, ModelLayer, ProgramNotFoundError, SyncDataError},
+    utils::{
+        opencl::{
+            empty_buffer, ensure_program, BufferLike, BufferOperations,
+            EnsureKernelsAndProgramError, InplaceBufferOperations,
+        },
+        OpenCLState,
+    },
+};
+
+use super::{
+    compute_update_vectors,
+    conv2d::{get_convolution_outputs_size_and_padding, Conv2DPadding},
+    initializers::{ConstantInitializer, GlorotUniformInitializer, Initializer, InitializerTrait},
+    Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
+    LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
+    ParametersOptimizationError,
+};
+
+const SEPARABLE_CONV2D_PROGRAM_NAME: &str = "SEPARABLE_CONV2D";
+const PROGRAM_SOURCE: &str = include_str!("kernels/separable_conv2d.cl");
+
+const DEPTHWISE_PROPAGATION_KERNEL_NAME: &str = "convolute_depthwise";
+const POINTWISE_PROPAGATION_KERNEL_NAME: &str = "convolute_pointwise";
+const COMPUTE_POINTWISE_WEIGHTS_GRADIENTS_KERNEL_NAME: &str =
+    "compute_pointwise_weights_gradients";
+const COMPUTE_BIASES_GRADIENTS_KERNEL_NAME: &str = "compute_biases_gradients";
+const COMPUTE_LOSS_TO_DEPTHWISE_OUTPUTS_DERIVATIVES_KERNEL_NAME: &str =
+    "compute_loss_to_depthwise_outputs_derivatives";
+const COMPUTE_DEPTHWISE_WEIGHTS_GRADIENTS_KERNEL_NAME: &str =
+    "compute_depthwise_weights_gradients";
+const COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME: &str = "compute_loss_to_input_derivatives";
+
+pub(crate) fn compile_separable_conv2d(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        DEPTHWISE_PROPAGATION_KERNEL_NAME.to_string(),
+        POINTWISE_PROPAGATION_KERNEL_NAME.to_string(),
+        COMPUTE_POINTWISE_WEIGHTS_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_BIASES_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_LOSS_TO_DEPTHWISE_OUTPUTS_DERIVATIVES_KERNEL_NAME.to_string(),
+        COMPUTE_DEPTHWISE_WEIGHTS_GRADIENTS_KERNEL_NAME.to_string(),
+        COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        SEPARABLE_CONV2D_PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that does the same as a Conv2D but in two much cheaper steps, first each channel of
+/// the images goes through its own filter (the depthwise convolution) and then the resulting
+/// channels are mixed into the outputs with a 1x1 convolution (the pointwise convolution).
+///
+/// With `c` channels, `f` filters and a filter of `w` by `h` pixels this layer has
+/// `c * w * h + f * c + f` parameters instead of the `f * c * w * h + f` of a Conv2D.
+///
+/// The inputs and the outputs are laid out in the same way as with a Conv2D, that is, all of the
+/// pixels of the first channel, then all of the pixels of the second channel and so on, with the
+/// outputs having one channel per filter.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::{Conv2DPadding, SeparableConv2D};
+/// use intricate::types::ModelLayer;
+///
+/// // this will make a separable conv layer that goes through 28x28 images with 16 channels
+/// // with a 3x3 filter per channel, and mixes them into 32 channels
+/// let my_layer: ModelLayer = SeparableConv2D::new_raw((28, 28), 16, (3, 3), 32)
+///     .set_padding(Conv2DPadding::Same)
+///     .into();
+/// ```
+pub struct SeparableConv2D<'a> {
+    /// The size of the inputs, width and height respectively.
+    pub inputs_size: (usize, usize),
+    /// The amount of channels that each input image has.
+    pub channels: usize,
+    /// The size of the depthwise filters, width and height respectively.
+    pub filter_size: (usize, usize),
+    /// The amount of pointwise filters, which is also the amount of channels that the outputs
+    /// will have.
+    pub filters: usize,
+
+    /// How many pixels the depthwise filters move at each step, horizontally and vertically
+    /// respectively.
+    pub stride: (usize, usize),
+    /// The padding of zeros that is added around the images.
+    pub padding: Conv2DPadding,
+    /// The spacing between the pixels of the depthwise filters, horizontally and vertically
+    /// respectively. A dilation of 1 means the filters are contiguous.
+    pub dilation: (usize, usize),
+
+    /// This is a vec containing the weights of the depthwise filter of each channel in the shape
+    /// `[channels][height][width]`.
+    pub depthwise_weights: Vec<Vec<Vec<f32>>>,
+    /// This is a vec containing the weights of the pointwise convolution in the shape
+    /// `[filters][channels]`.
+    pub pointwise_weights: Vec<Vec<f32>>,
+
+    /// This is a vec containing one bias for each filter.
+    pub biases: Vec<f32>,
+
+    /// The initializer that will be used to generate the initial parameters for the filter's
+    /// weights.
+    pub initializers: HashMap<String, Initializer>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened depthwise weights.
+    pub depthwise_weights_buff: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the flattened pointwise weights.
+    pub pointwise_weights_buff: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The allocated buffer with OpenCL that contains the filter biases.
+    pub biases_buff: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The buffer that contains the flattened inputs per sample that were last forwad passed into
+    /// this SeparableConv2D layer.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The buffer that contains the outputs of the depthwise convolution of the last forward
+    /// pass, which are the inputs of the pointwise convolution.
+    pub last_depthwise_outputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The buffer that contains the flattened outputs per sample that last came out of a forward
+    /// pass into this SeparableConv2D layer.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> SeparableConv2D<'a> {
+    /// Creates a new 2D Depthwise Separable Convolutional layer with random filters ready for
+    /// being used in a Model.
+    pub fn new(
+        inputs_size: (usize, usize),
+        channels: usize,
+        filter_size: (usize, usize),
+        filters: usize,
+    ) -> ModelLayer<'a> {
+        Self::new_raw(inputs_size, channels, filter_size, filters).into()
+    }
+
+    /// Crates a new raw 2D Depthwise Separable Convolutional layer with random filters.
+    pub fn new_raw(
+        inputs_size: (usize, usize),
+        channels: usize,
+        filter_size: (usize, usize),
+        filters: usize,
+    ) -> Self {
+        let mut initializers = HashMap::with_capacity(3);
+        initializers.insert(
+            "depthwise_weights".to_string(),
+            GlorotUniformInitializer::new().into(),
+        );
+        initializers.insert(
+            "pointwise_weights".to_string(),
+            GlorotUniformInitializer::new().into(),
+        );
+        initializers.insert("biases".to_string(), ConstantInitializer::new(0.0).into());
+
+        SeparableConv2D {
+            inputs_size,
+            channels,
+            filter_size,
+            filters,
+            stride: (1, 1),
+            padding: Conv2DPadding::Valid,
+            dilation: (1, 1),
+            depthwise_weights: Vec::default(),
+            pointwise_weights: Vec::default(),
+            biases: Vec::default(),
+            initializers,
+            depthwise_weights_buff: None,
+            pointwise_weights_buff: None,
+            biases_buff: None,
+            last_inputs_buffer: None,
+            last_depthwise_outputs_buffer: None,
+            last_outputs_buffer: None,
+            opencl_state: None,
+        }
+    }
+
+    /// Sets how many pixels the depthwise filters move at each step, horizontally and vertically
+    /// respectively, and returns the mutated Self.
+    pub fn set_stride(mut self, stride: (usize, usize)) -> Self {
+        assert!(
+            stride.0 > 0 && stride.1 > 0,
+            "the stride of a SeparableConv2D must be at least 1"
+        );
+        self.stride = stride;
+
+        self
+    }
+
+    /// Sets the padding that is added around the images and returns the mutated Self.
+    pub fn set_padding(mut self, padding: Conv2DPadding) -> Self {
+        self.padding = padding;
+
+        self
+    }
+
+    /// Sets the spacing between the pixels of the depthwise filters, horizontally and vertically
+    /// respectively, and returns the mutated Self.
+    pub fn set_dilation(mut self, dilation: (usize, usize)) -> Self {
+        assert!(
+            dilation.0 > 0 && dilation.1 > 0,
+            "the dilation of a SeparableConv2D must be at least 1"
+        );
+        self.dilation = dilation;
+
+        self
+    }
+
+    fn get_outputs_size_and_padding(&self) -> ((usize, usize), (usize, usize)) {
+        get_convolution_outputs_size_and_padding(
+            self.inputs_size,
+            self.filter_size,
+            self.stride,
+            self.dilation,
+            self.padding,
+        )
+    }
+
+    /// Gets the size of each one of the images that come out of the convolution, width and
+    /// height respectively.
+    pub fn get_outputs_size(&self) -> (usize, usize) {
+        let (outputs_size, _) = self.get_outputs_size_and_padding();
+
+        outputs_size
+    }
+
+    // the derivatives are needed both for the gradients of the depthwise weights and for the
+    // derivatives with respect to the inputs
+    fn compute_loss_to_depthwise_outputs_derivatives<E>(
+        &self,
+        layer_output_to_error_derivatives: &Buffer<cl_float>,
+        samples_amount: usize,
+        state: &OpenCLState,
+    ) -> Result<Buffer<cl_float>, E>
+    where
+        E: From<ClError> + From<ProgramNotFoundError> + From<KernelNotFoundError>,
+    {
+        let queue = state.queues.first().unwrap();
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        let outputs_volume = outputs_width * outputs_height;
+
+        let loss_to_depthwise_outputs_derivatives = empty_buffer(
+            samples_amount * self.channels * outputs_volume,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(SEPARABLE_CONV2D_PROGRAM_NAME)?;
+
+        ExecuteKernel::new(
+            program.get_krnl(COMPUTE_LOSS_TO_DEPTHWISE_OUTPUTS_DERIVATIVES_KERNEL_NAME)?,
+        )
+        .set_arg(self.pointwise_weights_buff.as_ref().unwrap())
+        .set_arg(layer_output_to_error_derivatives)
+        .set_arg(&loss_to_depthwise_outputs_derivatives)
+        .set_arg(&(self.channels as cl_int))
+        .set_arg(&(outputs_volume as cl_int))
+        .set_arg(&(self.filters as cl_int))
+        .set_arg(&(samples_amount as cl_int))
+        .set_global_work_sizes(&[samples_amount, self.channels * outputs_volume])
+        .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_depthwise_outputs_derivatives)
+    }
+}
+
+impl<'a> Layer<'a> for SeparableConv2D<'a> {
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        match parameter {
+            "depthwise_weights" => Some(
+                self.depthwise_weights
+                    .par_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|x| *x)
+                    .collect(),
+            ),
+            "pointwise_weights" => Some(
+                self.pointwise_weights
+                    .par_iter()
+                    .flatten()
+                    .map(|x| *x)
+                    .collect(),
+            ),
+            "biases" => Some(self.biases.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.initializers.get(parameter)
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.initializers.insert(parameter.to_string(), initializer);
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_size.0 * self.inputs_size.1 * self.channels
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        outputs_width * outputs_height * self.filters
+    }
+
+    fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+        Some(vec![self.channels, self.inputs_size.1, self.inputs_size.0])
+    }
+
+    fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        Some(vec![self.filters, outputs_height, outputs_width])
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.depthwise_weights_buff.is_some() {
+            drop(self.depthwise_weights_buff.as_ref().unwrap());
+        }
+
+        if self.pointwise_weights_buff.is_some() {
+            drop(self.pointwise_weights_buff.as_ref().unwrap());
+        }
+
+        if self.biases_buff.is_some() {
+            drop(self.biases_buff.as_ref().unwrap());
+        }
+
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_depthwise_outputs_buffer.is_some() {
+            drop(self.last_depthwise_outputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.opencl_state.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(SyncDataError::NoCommandQueue);
+        }
+
+        if self.depthwise_weights_buff.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "depthwise_weights".to_string(),
+            });
+        }
+
+        if self.pointwise_weights_buff.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "pointwise_weights".to_string(),
+            });
+        }
+
+        if self.biases_buff.is_none() {
+            return Err(SyncDataError::NotAllocatedInDevice {
+                field_name: "biases".to_string(),
+            });
+        }
+
+        let depthwise_weights =
+            Vec::<f32>::from_buffer(self.depthwise_weights_buff.as_ref().unwrap(), false, state)?;
+
+        self.depthwise_weights = depthwise_weights
+            .par_chunks(self.filter_size.0 * self.filter_size.1)
+            .map(|channel| {
+                channel
+                    .chunks(self.filter_size.0)
+                    .map(|row| row.to_vec())
+                    .collect()
+            })
+            .collect();
+
+        let pointwise_weights =
+            Vec::<f32>::from_buffer(self.pointwise_weights_buff.as_ref().unwrap(), false, state)?;
+
+        self.pointwise_weights = pointwise_weights
+            .par_chunks(self.channels)
+            .map(|filter| filter.to_vec())
+            .collect();
+
+        self.biases = Vec::<f32>::from_buffer(self.biases_buff.as_ref().unwrap(), false, state)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.get_outputs_amount() == 0 {
+            return Err(LayerInitializationError::FilterBiggerThanInputs);
+        }
+
+        if self.depthwise_weights.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("depthwise_weights") {
+                self.depthwise_weights = initializer.initialize_3d(
+                    (self.channels, self.filter_size.1, self.filter_size.0),
+                    self,
+                );
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "depthwise_weights",
+                ));
+            }
+        }
+
+        if self.pointwise_weights.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("pointwise_weights") {
+                self.pointwise_weights =
+                    initializer.initialize_2d((self.filters, self.channels), self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "pointwise_weights",
+                ));
+            }
+        }
+
+        if self.biases.is_empty() {
+            if let Some(initializer) = self.get_initializer_for_parameter("biases") {
+                self.biases = initializer.initialize_1d(self.filters, self);
+            } else {
+                return Err(LayerInitializationError::MissingParameterInitializer(
+                    "biases",
+                ));
+            }
+        }
+
+        self.depthwise_weights_buff = Some(
+            self.depthwise_weights
+                .par_iter()
+                .flatten()
+                .flatten()
+                .map(|x| *x)
+                .collect::<Vec<f32>>()
+                .to_buffer(false, opencl_state)?,
+        );
+
+        self.pointwise_weights_buff = Some(
+            self.pointwise_weights
+                .par_iter()
+                .flatten()
+                .map(|x| *x)
+                .collect::<Vec<f32>>()
+                .to_buffer(false, opencl_state)?,
+        );
+
+        self.biases_buff = Some(self.biases.to_buffer(false, opencl_state)?);
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let ((outputs_width, outputs_height), (padding_x, padding_y)) =
+            self.get_outputs_size_and_padding();
+        let outputs_volume = outputs_width * outputs_height;
+
+        let program = state.get_prgm(SEPARABLE_CONV2D_PROGRAM_NAME)?;
+
+        let depthwise_outputs = empty_buffer(
+            samples_amount * self.channels * outputs_volume,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        ExecuteKernel::new(program.get_krnl(DEPTHWISE_PROPAGATION_KERNEL_NAME)?)
+            .set_arg(inputs)
+            .set_arg(self.depthwise_weights_buff.as_ref().unwrap())
+            .set_arg(&depthwise_outputs)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_height as cl_int))
+            .set_arg(&(self.filter_size.0 as cl_int))
+            .set_arg(&(self.filter_size.1 as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(padding_x as cl_int))
+            .set_arg(&(padding_y as cl_int))
+            .set_arg(&(self.dilation.0 as cl_int))
+            .set_arg(&(self.dilation.1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.channels * outputs_volume])
+            .enqueue_nd_range(queue)?;
+
+        let outputs = empty_buffer(
+            self.get_outputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        ExecuteKernel::new(program.get_krnl(POINTWISE_PROPAGATION_KERNEL_NAME)?)
+            .set_arg(&depthwise_outputs)
+            .set_arg(self.pointwise_weights_buff.as_ref().unwrap())
+            .set_arg(self.biases_buff.as_ref().unwrap())
+            .set_arg(&outputs)
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_volume as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_outputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        self.last_depthwise_outputs_buffer = Some(depthwise_outputs);
+        self.last_outputs_buffer = Some(outputs);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivatives: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerGradientComputationError::NoCommandQueueFound);
+        }
+
+        if self.last_inputs_buffer.is_none() || self.last_depthwise_outputs_buffer.is_none() {
+            return Err(LayerGradientComputationError::HasNotPropagatedBeforeCalculation);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivatives.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let ((outputs_width, outputs_height), (padding_x, padding_y)) =
+            self.get_outputs_size_and_padding();
+        let outputs_volume = outputs_width * outputs_height;
+        let filter_volume = self.filter_size.0 * self.filter_size.1;
+
+        let program = state.get_prgm(SEPARABLE_CONV2D_PROGRAM_NAME)?;
+
+        let pointwise_weights_gradients =
+            empty_buffer(self.filters * self.channels, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_POINTWISE_WEIGHTS_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(self.last_depthwise_outputs_buffer.as_ref().unwrap())
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&pointwise_weights_gradients)
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_volume as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[self.filters, self.channels])
+            .enqueue_nd_range(queue)?;
+
+        let biases_gradients = empty_buffer(self.filters, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_BIASES_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(layer_output_to_error_derivatives)
+            .set_arg(&biases_gradients)
+            .set_arg(&(outputs_volume as cl_int))
+            .set_arg(&(self.filters as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_size(self.filters)
+            .enqueue_nd_range(queue)?;
+
+        let loss_to_depthwise_outputs_derivatives = self
+            .compute_loss_to_depthwise_outputs_derivatives::<LayerGradientComputationError>(
+                layer_output_to_error_derivatives,
+                samples_amount,
+                state,
+            )?;
+
+        let depthwise_weights_gradients =
+            empty_buffer(self.channels * filter_volume, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(program.get_krnl(COMPUTE_DEPTHWISE_WEIGHTS_GRADIENTS_KERNEL_NAME)?)
+            .set_arg(self.last_inputs_buffer.as_ref().unwrap())
+            .set_arg(&loss_to_depthwise_outputs_derivatives)
+            .set_arg(&depthwise_weights_gradients)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_height as cl_int))
+            .set_arg(&(self.filter_size.0 as cl_int))
+            .set_arg(&(self.filter_size.1 as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(padding_x as cl_int))
+            .set_arg(&(padding_y as cl_int))
+            .set_arg(&(self.dilation.0 as cl_int))
+            .set_arg(&(self.dilation.1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[self.channels, filter_volume])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(vec![
+            Gradient {
+                optimizable: true,
+                parameter_id: "depthwise_weights".to_string(),
+                value: depthwise_weights_gradients,
+            },
+            Gradient {
+                optimizable: true,
+                parameter_id: "pointwise_weights".to_string(),
+                value: pointwise_weights_gradients,
+            },
+            Gradient {
+                optimizable: true,
+                parameter_id: "biases".to_string(),
+                value: biases_gradients,
+            },
+        ])
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.depthwise_weights_buff.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "depthwise_weights".to_string(),
+            ));
+        }
+
+        if self.pointwise_weights_buff.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "pointwise_weights".to_string(),
+            ));
+        }
+
+        if self.biases_buff.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "biases".to_string(),
+            ));
+        }
+
+        optimizer.optimize_parameters(
+            self.depthwise_weights_buff.as_mut().unwrap(),
+            "depthwise_weights".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        optimizer.optimize_parameters(
+            self.pointwise_weights_buff.as_mut().unwrap(),
+            "pointwise_weights".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        optimizer.optimize_parameters(
+            self.biases_buff.as_mut().unwrap(),
+            "biases".to_string(),
+            timestep,
+            layer_index,
+        )?;
+
+        Ok(())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_model_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if per_parameter_type_gradients.len() != 3 {
+            return Err(LayerGradientApplicationError::GradientsDontMatchExpectedShape);
+        }
+
+        let update_vectors = compute_update_vectors(
+            optimizer,
+            per_parameter_type_gradients,
+            layer_model_index,
+            timestep,
+            state,
+        )?;
+
+        let depthwise_weights_buff = self.depthwise_weights_buff.as_mut().unwrap();
+        depthwise_weights_buff.subtract_inplc(&update_vectors[0], state)?;
+        let pointwise_weights_buff = self.pointwise_weights_buff.as_mut().unwrap();
+        pointwise_weights_buff.subtract_inplc(&update_vectors[1], state)?;
+        let biases_buff = self.biases_buff.as_mut().unwrap();
+        biases_buff.subtract_inplc(&update_vectors[2], state)?;
+
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        if self.depthwise_weights_buff.is_none() {
+            return Err(LayerLossToInputDifferentiationError::MissingParameter(
+                "depthwise_weights",
+            ));
+        }
+
+        if self.pointwise_weights_buff.is_none() {
+            return Err(LayerLossToInputDifferentiationError::MissingParameter(
+                "pointwise_weights",
+            ));
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let ((outputs_width, outputs_height), (padding_x, padding_y)) =
+            self.get_outputs_size_and_padding();
+
+        let loss_to_depthwise_outputs_derivatives = self
+            .compute_loss_to_depthwise_outputs_derivatives::<LayerLossToInputDifferentiationError>(
+                layer_output_to_error_derivative,
+                samples_amount,
+                state,
+            )?;
+
+        let loss_to_input_derivatives_buffer = empty_buffer(
+            self.get_inputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(SEPARABLE_CONV2D_PROGRAM_NAME)?;
+        let kernel = program.get_krnl(COMPUTE_LOSS_TO_INPUT_DERIVATIVES_KERNEL_NAME)?;
+
+        ExecuteKernel::new(kernel)
+            .set_arg(self.depthwise_weights_buff.as_ref().unwrap())
+            .set_arg(&loss_to_depthwise_outputs_derivatives)
+            .set_arg(&loss_to_input_derivatives_buffer)
+            .set_arg(&(self.inputs_size.0 as cl_int))
+            .set_arg(&(self.inputs_size.1 as cl_int))
+            .set_arg(&(self.channels as cl_int))
+            .set_arg(&(outputs_width as cl_int))
+            .set_arg(&(outputs_height as cl_int))
+            .set_arg(&(self.filter_size.0 as cl_int))
+            .set_arg(&(self.filter_size.1 as cl_int))
+            .set_arg(&(self.stride.0 as cl_int))
+            .set_arg(&(self.stride.1 as cl_int))
+            .set_arg(&(padding_x as cl_int))
+            .set_arg(&(padding_y as cl_int))
+            .set_arg(&(self.dilation.0 as cl_int))
+            .set_arg(&(self.dilation.1 as cl_int))
+            .set_arg(&(samples_amount as cl_int))
+            .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod separable_conv2d_tests {
+    use crate::{
+        layers::{Conv2DPadding, Layer},
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::SeparableConv2D;
+
+    #[test]
+    fn should_propagate_and_compute_gradients_of_both_convolutions() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.3,
+            0.7, 0.2, 0.9,
+            0.4, 0.8, 0.6,
+
+            0.2, 0.4, 0.1,
+            0.3, 0.9, 0.5,
+            0.6, 0.7, 0.8,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.1, 0.2,
+            0.3, 0.4,
+
+            0.5, -0.6,
+            0.7, 0.8,
+
+            -0.2, 0.3,
+            0.1, 0.9,
+        ];
+
+        let expected_outputs = vec![
+            0.539, 0.41,
+            0.268, 0.194,
+
+            0.057, -0.04,
+            -0.176, 0.008,
+
+            0.755, 0.52,
+            0.22, 0.424,
+        ];
+        let expected_depthwise_weights_gradients = vec![
+            0.521, 0.091, 0.315, -0.018,
+            0.769, 0.227, 0.013, 0.054,
+        ];
+        let expected_pointwise_weights_gradients = vec![0.184, 0.283, 0.208, 0.391, 0.084, 0.31];
+        let expected_biases_gradients = vec![1.0, 1.4, 1.1];
+        let expected_loss_to_input_derivatives = vec![
+            -0.009, -0.018, 0.042,
+            -0.027, -0.036, 0.126,
+            0.017, 0.034, 0.056,
+
+            0.03, -0.036, -0.005,
+            0.042, 0.048, -0.007,
+            0.145, -0.174, 0.365,
+        ];
+
+        let mut layer = SeparableConv2D::new_raw((3, 3), 2, (2, 2), 3)
+            .set_stride((2, 2))
+            .set_padding(Conv2DPadding::Same);
+        assert_eq!(layer.get_outputs_size(), (2, 2));
+
+        layer.depthwise_weights = vec![
+            vec![vec![0.1, 0.2], vec![0.3, 0.4]],
+            vec![vec![0.5, -0.6], vec![0.7, 0.8]],
+        ];
+        layer.pointwise_weights = vec![vec![0.9, 0.1], vec![-0.2, 0.3], vec![0.4, 0.5]];
+        layer.biases = vec![0.1, -0.1, 0.2];
+        layer.init(&opencl_state).expect("unable to init SeparableConv2D");
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the SeparableConv2D");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the SeparableConv2D");
+
+        let expected_gradients = [
+            expected_depthwise_weights_gradients,
+            expected_pointwise_weights_gradients,
+            expected_biases_gradients,
+        ];
+
+        for (gradient, expected_gradient) in gradients.iter().zip(expected_gradients.iter()) {
+            let actual_gradient = Vec::<f32>::from_buffer(&gradient.value, false, &opencl_state)
+                .expect("unable to read a gradients buffer");
+
+            assert_approx_equal_distance(&actual_gradient, expected_gradient, 0.01);
+        }
+
+        let loss_to_input_derivatives_buffer = layer
+            .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the loss to input derivatives of the SeparableConv2D");
+        let loss_to_input_derivatives =
+            Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+}
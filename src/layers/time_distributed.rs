@@ -0,0 +1,404 @@
+//! The module that defines the TimeDistributed layer.
+
+use opencl3::{device::cl_float, memory::{Buffer, ClMem}};
+use savefile::{load_noschema, save_noschema, SavefileError};
+use savefile_derive::Savefile;
+
+use crate::{
+    optimizers::Optimizer,
+    types::{ModelLayer, SyncDataError},
+    utils::{opencl::InplaceBufferOperations, OpenCLState},
+};
+
+use super::{
+    initializers::Initializer, Gradient, Layer, LayerGradientApplicationError,
+    LayerGradientComputationError, LayerInitializationError, LayerLossToInputDifferentiationError,
+    LayerPropagationError, ParametersOptimizationError,
+};
+
+const LAYER_DATA_VERSION: u32 = 0;
+
+#[derive(Debug, Savefile)]
+/// A layer that applies the same layer, with the same parameters, to every one of the timesteps
+/// of sequences, treating each timestep as if it was one more sample in the batch.
+///
+/// The inputs are the timesteps of each sample one after the other, and the outputs are the
+/// outputs of the wrapped layer for each timestep also one after the other.
+///
+/// Since the wrapped layer computes its gradients as an average over all of the timesteps of all
+/// of the samples, they are multiplied by the amount of timesteps so that the gradients are
+/// accumulated across the timesteps and only averaged across the samples.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::{Dense, TimeDistributed};
+///
+/// // this will apply the same Dense layer to each one of the 10 timesteps
+/// // of a sequence with 5 numbers per timestep
+/// let my_layer: TimeDistributed = TimeDistributed::new_raw(Dense::new(5, 3), 10);
+/// ```
+pub struct TimeDistributed<'a> {
+    /// The amount of timesteps in each one of the input samples.
+    pub timesteps: usize,
+
+    /// The amount of inputs the wrapped layer expects for each timestep.
+    pub inputs_amount: usize,
+    /// The amount of outputs the wrapped layer gives out for each timestep.
+    pub outputs_amount: usize,
+
+    // Savefile can't describe a ModelLayer that contains another ModelLayer, so the wrapped
+    // layer is saved into these bytes instead every time it changes in the host
+    layer_data: Vec<u8>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The layer that is applied to every timestep, which is only None after the
+    /// TimeDistributed is loaded and before it is initialized.
+    pub layer: Option<Box<ModelLayer<'a>>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+fn save_layer_data(layer: &ModelLayer) -> Result<Vec<u8>, SavefileError> {
+    let mut layer_data = Vec::default();
+    save_noschema(&mut layer_data, LAYER_DATA_VERSION, layer)?;
+
+    Ok(layer_data)
+}
+
+impl<'a> TimeDistributed<'a> {
+    /// Creates a new TimeDistributed layer that applies **layer** to all of the **timesteps**
+    /// ready to be used in a Model.
+    pub fn new(layer: ModelLayer<'a>, timesteps: usize) -> ModelLayer<'a> {
+        Self::new_raw(layer, timesteps).into()
+    }
+
+    /// Creates a new raw TimeDistributed layer that applies **layer** to all of the
+    /// **timesteps**.
+    pub fn new_raw(layer: ModelLayer<'a>, timesteps: usize) -> Self {
+        TimeDistributed {
+            timesteps,
+            inputs_amount: layer.get_inputs_amount(),
+            outputs_amount: layer.get_outputs_amount(),
+            // so that the TimeDistributed can be loaded back even if it is saved before syncing
+            layer_data: save_layer_data(&layer)
+                .expect("unable to save the layer wrapped by the TimeDistributed"),
+            layer: Some(Box::new(layer)),
+            opencl_state: None,
+        }
+    }
+}
+
+impl<'a> Layer<'a> for TimeDistributed<'a> {
+    fn get_flattened_parameter_data(&self, parameter: &str) -> Option<Vec<f32>> {
+        self.layer
+            .as_ref()
+            .and_then(|layer| layer.get_flattened_parameter_data(parameter))
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, parameter: &str) -> Option<&'b Initializer> {
+        self.layer
+            .as_ref()
+            .and_then(|layer| layer.get_initializer_for_parameter(parameter))
+    }
+
+    fn set_initializer_for_parameter(
+        mut self,
+        initializer: Initializer,
+        parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        if let Some(layer) = self.layer.take() {
+            let layer = layer.set_initializer_for_parameter(initializer, parameter);
+
+            self.layer_data = save_layer_data(&layer)
+                .expect("unable to save the layer wrapped by the TimeDistributed");
+            self.layer = Some(Box::new(layer));
+        }
+
+        self.into()
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.layer.as_ref().and_then(|layer| layer.get_last_inputs())
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.layer.as_ref().and_then(|layer| layer.get_last_outputs())
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.timesteps * self.inputs_amount
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.timesteps * self.outputs_amount
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if let Some(layer) = self.layer.as_mut() {
+            layer.clean_up_gpu_state();
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        if self.layer.is_none() {
+            return Err(SyncDataError::NotInitialized);
+        }
+
+        let layer = self.layer.as_mut().unwrap();
+
+        layer.sync_data_from_buffers_to_host()?;
+
+        self.layer_data = save_layer_data(layer)?;
+
+        Ok(())
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        if self.layer.is_none() {
+            let layer: ModelLayer<'a> =
+                load_noschema(&mut self.layer_data.as_slice(), LAYER_DATA_VERSION)?;
+            self.layer = Some(Box::new(layer));
+        }
+
+        self.layer.as_mut().unwrap().init(opencl_state)?;
+
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn set_training_mode(&mut self, is_training: bool) -> () {
+        if let Some(layer) = self.layer.as_mut() {
+            layer.set_training_mode(is_training);
+        }
+    }
+
+    fn set_updating_running_statistics(&mut self, should_update: bool) -> () {
+        if let Some(layer) = self.layer.as_mut() {
+            layer.set_updating_running_statistics(should_update);
+        }
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() || self.layer.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let inputs_total_count = inputs.size()? / std::mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        // the timesteps of each sample already come one after the other, so the wrapped layer
+        // just sees them as more samples
+        self.layer.as_mut().unwrap().propagate(inputs)
+    }
+
+    fn compute_gradients(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        if self.opencl_state.is_none() || self.layer.is_none() {
+            return Err(LayerGradientComputationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        let derivatives_total_count =
+            layer_output_to_error_derivative.size()? / std::mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerGradientComputationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let mut gradients = self
+            .layer
+            .as_ref()
+            .unwrap()
+            .compute_gradients(layer_output_to_error_derivative)?;
+
+        for gradient in gradients.iter_mut() {
+            gradient
+                .value
+                .scale_inplc(self.timesteps as f32, state)?;
+        }
+
+        Ok(gradients)
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        layer_index: usize,
+        timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        if self.layer.is_none() {
+            return Err(ParametersOptimizationError::EmptyParameter(
+                "layer".to_string(),
+            ));
+        }
+
+        self.layer
+            .as_mut()
+            .unwrap()
+            .optimize_parameters(optimizer, layer_index, timestep)
+    }
+
+    fn apply_gradients(
+        &mut self,
+        per_parameter_type_gradients: &[Gradient],
+        optimizer: &mut dyn Optimizer<'a>,
+        layer_model_index: usize,
+        timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        if self.layer.is_none() {
+            return Err(LayerGradientApplicationError::LayerNotInitialized);
+        }
+
+        self.layer.as_mut().unwrap().apply_gradients(
+            per_parameter_type_gradients,
+            optimizer,
+            layer_model_index,
+            timestep,
+        )
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.layer.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        self.layer
+            .as_ref()
+            .unwrap()
+            .compute_loss_to_input_derivatives(layer_output_to_error_derivative)
+    }
+}
+
+#[cfg(test)]
+mod time_distributed_tests {
+    use savefile::{load_noschema, save_noschema};
+
+    use crate::{
+        layers::{Dense, Layer},
+        types::ModelLayer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::TimeDistributed;
+
+    #[test]
+    fn should_share_the_wrapped_layer_across_timesteps() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let mut dense = Dense::new_raw(2, 1);
+        dense.weights = vec![vec![1.0], vec![2.0]];
+        dense.biases = vec![0.5];
+
+        let mut layer = TimeDistributed::new_raw(dense.into(), 3);
+        layer.init(&opencl_state).expect("unable to init TimeDistributed");
+
+        let inputs = vec![
+            1.0, 0.0, 0.0, 1.0, 1.0, 1.0,
+            2.0, 1.0, 0.0, 0.0, -1.0, 1.0,
+        ];
+        let loss_to_output_derivatives = vec![
+            1.0, 2.0, 0.0,
+            0.0, 1.0, 1.0,
+        ];
+
+        let expected_outputs = vec![1.5, 2.5, 3.5, 4.5, 0.5, 1.5];
+        // summed across the timesteps but averaged across the 2 samples
+        let expected_weights_gradients = vec![0.0, 1.5];
+        let expected_biases_gradients = vec![2.5];
+        let expected_loss_to_input_derivatives = vec![
+            1.0, 2.0, 2.0, 4.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 2.0, 1.0, 2.0,
+        ];
+
+        let outputs_buffer = layer
+            .propagate(
+                &inputs
+                    .to_buffer(false, &opencl_state)
+                    .expect("unable to get the inputs buffer"),
+            )
+            .expect("unable to propagate the TimeDistributed");
+        let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+            .expect("unable to read the outputs buffer");
+
+        assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+        let loss_to_output_derivatives_buffer = loss_to_output_derivatives
+            .to_buffer(false, &opencl_state)
+            .expect("unable to get the loss to output derivatives buffer");
+
+        let gradients = layer
+            .compute_gradients(&loss_to_output_derivatives_buffer)
+            .expect("unable to compute the gradients of the TimeDistributed");
+        let weights_gradients = Vec::<f32>::from_buffer(&gradients[0].value, false, &opencl_state)
+            .expect("unable to read the weights gradients buffer");
+        let biases_gradients = Vec::<f32>::from_buffer(&gradients[1].value, false, &opencl_state)
+            .expect("unable to read the biases gradients buffer");
+
+        assert_approx_equal_distance(&weights_gradients, &expected_weights_gradients, 0.01);
+        assert_approx_equal_distance(&biases_gradients, &expected_biases_gradients, 0.01);
+
+        let loss_to_input_derivatives = Vec::<f32>::from_buffer(
+            &layer
+                .compute_loss_to_input_derivatives(&loss_to_output_derivatives_buffer)
+                .expect("unable to compute the loss to input derivatives"),
+            false,
+            &opencl_state,
+        )
+        .expect("unable to read the loss to input derivatives buffer");
+
+        assert_approx_equal_distance(
+            &loss_to_input_derivatives,
+            &expected_loss_to_input_derivatives,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn should_load_back_the_wrapped_layer_without_syncing_before_saving() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let mut dense = Dense::new_raw(2, 1);
+        dense.weights = vec![vec![1.0], vec![2.0]];
+        dense.biases = vec![0.5];
+
+        let layer = TimeDistributed::new(dense.into(), 3);
+
+        let mut layer_bytes = Vec::new();
+        save_noschema(&mut layer_bytes, 0, &layer).expect("unable to save the TimeDistributed");
+        let mut loaded_layer: ModelLayer = load_noschema(&mut layer_bytes.as_slice(), 0)
+            .expect("unable to load the TimeDistributed");
+
+        loaded_layer
+            .init(&opencl_state)
+            .expect("unable to init the loaded TimeDistributed");
+
+        assert_approx_equal_distance(
+            &loaded_layer
+                .get_flattened_parameter_data("weights")
+                .expect("unable to get the weights of the wrapped Dense"),
+            &vec![1.0, 2.0],
+            0.01,
+        );
+    }
+}
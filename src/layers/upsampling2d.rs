@@ -0,0 +1,499 @@
+//! The module that contains the Upsampling2D layer.
+
+use std::mem;
+
+use opencl3::{
+    device::cl_float,
+    error_codes::cl_int,
+    kernel::ExecuteKernel,
+    memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
+};
+
+use savefile_derive::Savefile;
+
+use crate::{
+    layers::{
+        initializers::Initializer, Gradient, Layer, LayerGradientApplicationError,
+        LayerGradientComputationError, LayerInitializationError,
+        LayerLossToInputDifferentiationError, LayerPropagationError, ParametersOptimizationError,
+        SyncDataError,
+    },
+    optimizers::Optimizer,
+    types::ModelLayer,
+    utils::{
+        opencl::{empty_buffer, ensure_program, BufferOperations, EnsureKernelsAndProgramError},
+        OpenCLState,
+    },
+};
+
+const PROGRAM_NAME: &str = "UPSAMPLING2D";
+const PROGRAM_SOURCE: &str = include_str!("kernels/upsampling2d.cl");
+const PROPAGATE_NEAREST_KERNEL_NAME: &str = "propagate_nearest";
+const BACK_PROPAGATE_NEAREST_KERNEL_NAME: &str = "back_propagate_nearest";
+const PROPAGATE_BILINEAR_KERNEL_NAME: &str = "propagate_bilinear";
+const BACK_PROPAGATE_BILINEAR_KERNEL_NAME: &str = "back_propagate_bilinear";
+
+pub(crate) fn compile_upsampling2d(
+    opencl_state: &mut OpenCLState,
+) -> Result<(), EnsureKernelsAndProgramError> {
+    let kernels = &[
+        PROPAGATE_NEAREST_KERNEL_NAME.to_string(),
+        BACK_PROPAGATE_NEAREST_KERNEL_NAME.to_string(),
+        PROPAGATE_BILINEAR_KERNEL_NAME.to_string(),
+        BACK_PROPAGATE_BILINEAR_KERNEL_NAME.to_string(),
+    ];
+
+    ensure_program(
+        opencl_state,
+        PROGRAM_NAME.to_string(),
+        PROGRAM_SOURCE.to_string(),
+        "".to_string(),
+        kernels,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Savefile)]
+/// The way the new pixels of the upsampled images are computed from the original ones.
+pub enum UpsamplingInterpolation {
+    /// Each pixel is just repeated as many times as the scale in each axis.
+    Nearest,
+    /// Each new pixel is a weighted average of the four closest original pixels considering
+    /// their centers, with the pixels at the borders being repeated.
+    Bilinear,
+}
+
+impl Default for UpsamplingInterpolation {
+    fn default() -> Self {
+        Self::Nearest
+    }
+}
+
+#[derive(Debug, Savefile)]
+/// A layer that upsamples each channel of its input images by a certain integer scale in each
+/// one of the axes, without any parameters.
+///
+/// The inputs are expected to be in the same layout as the outputs of a Conv2D, that is, all of
+/// the pixels of the first channel, then all of the pixels of the second channel and so on.
+///
+/// # Examples
+///
+/// ```rust
+/// use intricate::layers::{Upsampling2D, UpsamplingInterpolation};
+/// use intricate::types::ModelLayer;
+///
+/// // this will make a layer that doubles the size of 14x14 images with 8 channels
+/// let my_layer: ModelLayer = Upsampling2D::new_raw((14, 14), 8, (2, 2))
+///     .set_interpolation(UpsamplingInterpolation::Bilinear)
+///     .into();
+/// ```
+pub struct Upsampling2D<'a> {
+    /// The size of the inputs, width and height respectively.
+    pub inputs_size: (usize, usize),
+    /// The amount of channels that each input image has.
+    pub channels: usize,
+    /// How many times bigger the outputs are, horizontally and vertically respectively.
+    pub scale: (usize, usize),
+    /// The way the new pixels are computed.
+    pub interpolation: UpsamplingInterpolation,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The cloned inputs last forward passed into this Upsampling2D.
+    pub last_inputs_buffer: Option<Buffer<cl_float>>,
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    /// The outputs that came out from the last forward pass into this Upsampling2D.
+    pub last_outputs_buffer: Option<Buffer<cl_float>>,
+
+    #[savefile_ignore]
+    #[savefile_introspect_ignore]
+    opencl_state: Option<&'a OpenCLState>,
+}
+
+impl<'a> Upsampling2D<'a> {
+    /// Creates a raw version of the Upsampling2D layer, this is good for
+    /// being used when you don't want to use the layer in a Model.
+    pub fn new_raw(
+        inputs_size: (usize, usize),
+        channels: usize,
+        scale: (usize, usize),
+    ) -> Upsampling2D<'a> {
+        assert!(scale.0 > 0 && scale.1 > 0, "the scale of a Upsampling2D must be at least 1");
+
+        Upsampling2D {
+            inputs_size,
+            channels,
+            scale,
+            interpolation: UpsamplingInterpolation::Nearest,
+
+            last_inputs_buffer: None,
+            last_outputs_buffer: None,
+
+            opencl_state: None,
+        }
+    }
+
+    /// Creates a ModelLayer version of the Upsampling2D layer, to be used with a Model.
+    pub fn new(
+        inputs_size: (usize, usize),
+        channels: usize,
+        scale: (usize, usize),
+    ) -> ModelLayer<'a> {
+        Self::new_raw(inputs_size, channels, scale).into()
+    }
+
+    /// Sets the way the new pixels are computed and returns the mutated Self.
+    pub fn set_interpolation(mut self, interpolation: UpsamplingInterpolation) -> Self {
+        self.interpolation = interpolation;
+
+        self
+    }
+
+    /// Gets the size of each one of the images that come out of the upsampling, width and
+    /// height respectively.
+    pub fn get_outputs_size(&self) -> (usize, usize) {
+        (
+            self.inputs_size.0 * self.scale.0,
+            self.inputs_size.1 * self.scale.1,
+        )
+    }
+}
+
+impl<'a> Layer<'a> for Upsampling2D<'a> {
+    fn get_flattened_parameter_data(&self, _parameter: &str) -> Option<Vec<f32>> {
+        None
+    }
+
+    fn get_initializer_for_parameter<'b>(&'b self, _parameter: &str) -> Option<&'b Initializer> {
+        None
+    }
+
+    fn set_initializer_for_parameter(
+        self,
+        _initializer: Initializer,
+        _parameter: &'a str,
+    ) -> ModelLayer<'a> {
+        self.into()
+    }
+
+    fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        self.opencl_state = Some(opencl_state);
+
+        Ok(())
+    }
+
+    fn get_last_inputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_inputs_buffer.as_ref()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.last_outputs_buffer.as_ref()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.inputs_size.0 * self.inputs_size.1 * self.channels
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        outputs_width * outputs_height * self.channels
+    }
+
+    fn get_inputs_shape(&self) -> Option<Vec<usize>> {
+        Some(vec![self.channels, self.inputs_size.1, self.inputs_size.0])
+    }
+
+    fn get_outputs_shape(&self) -> Option<Vec<usize>> {
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+        Some(vec![self.channels, outputs_height, outputs_width])
+    }
+
+    fn clean_up_gpu_state(&mut self) -> () {
+        if self.last_inputs_buffer.is_some() {
+            drop(self.last_inputs_buffer.as_ref().unwrap());
+        }
+
+        if self.last_outputs_buffer.is_some() {
+            drop(self.last_outputs_buffer.as_ref().unwrap());
+        }
+    }
+
+    fn sync_data_from_buffers_to_host(&mut self) -> Result<(), SyncDataError> {
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        inputs: &Buffer<cl_float>,
+    ) -> Result<&Buffer<cl_float>, LayerPropagationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerPropagationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerPropagationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let inputs_size = inputs.size()?;
+        let inputs_total_count = inputs_size / mem::size_of::<cl_float>();
+
+        if inputs_total_count % self.get_inputs_amount() != 0 {
+            return Err(LayerPropagationError::InputsDontMatchExpectedShape);
+        }
+
+        let samples_amount = inputs_total_count / self.get_inputs_amount();
+
+        self.last_inputs_buffer = Some(inputs.clone(state)?);
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+
+        let outputs_buffer = empty_buffer(
+            self.get_outputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+
+        match self.interpolation {
+            UpsamplingInterpolation::Nearest => {
+                ExecuteKernel::new(program.get_krnl(PROPAGATE_NEAREST_KERNEL_NAME)?)
+                    .set_arg(inputs)
+                    .set_arg(&outputs_buffer)
+                    .set_arg(&(self.inputs_size.0 as cl_int))
+                    .set_arg(&((self.inputs_size.0 * self.inputs_size.1) as cl_int))
+                    .set_arg(&(outputs_width as cl_int))
+                    .set_arg(&((outputs_width * outputs_height) as cl_int))
+                    .set_arg(&(self.scale.0 as cl_int))
+                    .set_arg(&(self.scale.1 as cl_int))
+                    .set_arg(&(self.channels as cl_int))
+                    .set_arg(&(samples_amount as cl_int))
+                    .set_global_work_sizes(&[samples_amount, self.get_outputs_amount()])
+                    .enqueue_nd_range(queue)?;
+            }
+            UpsamplingInterpolation::Bilinear => {
+                ExecuteKernel::new(program.get_krnl(PROPAGATE_BILINEAR_KERNEL_NAME)?)
+                    .set_arg(inputs)
+                    .set_arg(&outputs_buffer)
+                    .set_arg(&(self.inputs_size.0 as cl_int))
+                    .set_arg(&(self.inputs_size.1 as cl_int))
+                    .set_arg(&(outputs_width as cl_int))
+                    .set_arg(&(outputs_height as cl_int))
+                    .set_arg(&(self.scale.0 as cl_int))
+                    .set_arg(&(self.scale.1 as cl_int))
+                    .set_arg(&(self.channels as cl_int))
+                    .set_arg(&(samples_amount as cl_int))
+                    .set_global_work_sizes(&[samples_amount, self.get_outputs_amount()])
+                    .enqueue_nd_range(queue)?;
+            }
+        }
+
+        queue.finish()?;
+
+        self.last_outputs_buffer = Some(outputs_buffer);
+
+        Ok(self.last_outputs_buffer.as_ref().unwrap())
+    }
+
+    fn apply_gradients(
+        &mut self,
+        _per_parameter_type_gradients: &[Gradient],
+        _optimizer: &mut dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), LayerGradientApplicationError> {
+        Ok(())
+    }
+
+    fn compute_gradients(
+        &self,
+        _layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Vec<Gradient>, LayerGradientComputationError> {
+        Ok(Vec::default())
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        _optimizer: &dyn Optimizer<'a>,
+        _layer_index: usize,
+        _timestep: usize,
+    ) -> Result<(), ParametersOptimizationError> {
+        Ok(())
+    }
+
+    fn compute_loss_to_input_derivatives(
+        &self,
+        layer_output_to_error_derivative: &Buffer<cl_float>,
+    ) -> Result<Buffer<cl_float>, LayerLossToInputDifferentiationError> {
+        if self.opencl_state.is_none() {
+            return Err(LayerLossToInputDifferentiationError::LayerNotInitialized);
+        }
+
+        let state = self.opencl_state.unwrap();
+
+        if state.queues.is_empty() {
+            return Err(LayerLossToInputDifferentiationError::NoCommandQueueFound);
+        }
+
+        let queue = state.queues.first().unwrap();
+
+        let derivatives_size = layer_output_to_error_derivative.size()?;
+        let derivatives_total_count = derivatives_size / mem::size_of::<cl_float>();
+
+        if derivatives_total_count % self.get_outputs_amount() != 0 {
+            return Err(LayerLossToInputDifferentiationError::DerivativesDontMatchExpectedShape);
+        }
+
+        let samples_amount = derivatives_total_count / self.get_outputs_amount();
+
+        let (outputs_width, outputs_height) = self.get_outputs_size();
+
+        let loss_to_input_derivatives_buffer = empty_buffer(
+            self.get_inputs_amount() * samples_amount,
+            CL_MEM_READ_WRITE,
+            state,
+        )?;
+
+        let program = state.get_prgm(PROGRAM_NAME)?;
+
+        match self.interpolation {
+            UpsamplingInterpolation::Nearest => {
+                ExecuteKernel::new(program.get_krnl(BACK_PROPAGATE_NEAREST_KERNEL_NAME)?)
+                    .set_arg(layer_output_to_error_derivative)
+                    .set_arg(&loss_to_input_derivatives_buffer)
+                    .set_arg(&(self.inputs_size.0 as cl_int))
+                    .set_arg(&((self.inputs_size.0 * self.inputs_size.1) as cl_int))
+                    .set_arg(&(outputs_width as cl_int))
+                    .set_arg(&((outputs_width * outputs_height) as cl_int))
+                    .set_arg(&(self.scale.0 as cl_int))
+                    .set_arg(&(self.scale.1 as cl_int))
+                    .set_arg(&(self.channels as cl_int))
+                    .set_arg(&(samples_amount as cl_int))
+                    .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+                    .enqueue_nd_range(queue)?;
+            }
+            UpsamplingInterpolation::Bilinear => {
+                ExecuteKernel::new(program.get_krnl(BACK_PROPAGATE_BILINEAR_KERNEL_NAME)?)
+                    .set_arg(layer_output_to_error_derivative)
+                    .set_arg(&loss_to_input_derivatives_buffer)
+                    .set_arg(&(self.inputs_size.0 as cl_int))
+                    .set_arg(&(self.inputs_size.1 as cl_int))
+                    .set_arg(&(outputs_width as cl_int))
+                    .set_arg(&(outputs_height as cl_int))
+                    .set_arg(&(self.scale.0 as cl_int))
+                    .set_arg(&(self.scale.1 as cl_int))
+                    .set_arg(&(self.channels as cl_int))
+                    .set_arg(&(samples_amount as cl_int))
+                    .set_global_work_sizes(&[samples_amount, self.get_inputs_amount()])
+                    .enqueue_nd_range(queue)?;
+            }
+        }
+
+        queue.finish()?;
+
+        Ok(loss_to_input_derivatives_buffer)
+    }
+}
+
+#[cfg(test)]
+mod upsampling2d_tests {
+    use crate::{
+        layers::Layer,
+        utils::{
+            approx_eq::assert_approx_equal_distance,
+            opencl::{BufferLike, DeviceType},
+            setup_opencl,
+        },
+    };
+
+    use super::{Upsampling2D, UpsamplingInterpolation};
+
+    #[test]
+    fn should_upsample_and_back_propagate_with_both_interpolations() {
+        let opencl_state = setup_opencl(DeviceType::GPU).expect("unable to setup opencl");
+
+        let inputs = vec![
+            0.1, 0.5, 0.3,
+            0.7, 0.2, 0.9,
+        ];
+        let loss_to_output_derivatives = vec![
+            0.0, 0.7, 0.3, 1.0, 0.6, 0.2,
+            0.9, 0.5, 0.1, 0.8, 0.4, 0.0,
+            0.7, 0.3, 1.0, 0.6, 0.2, 0.9,
+            0.5, 0.1, 0.8, 0.4, 0.0, 0.7,
+        ];
+
+        let expected_nearest_outputs = vec![
+            0.1, 0.1, 0.5, 0.5, 0.3, 0.3,
+            0.1, 0.1, 0.5, 0.5, 0.3, 0.3,
+            0.7, 0.7, 0.2, 0.2, 0.9, 0.9,
+            0.7, 0.7, 0.2, 0.2, 0.9, 0.9,
+        ];
+        let expected_nearest_loss_to_input_derivatives = vec![
+            2.1, 2.2, 1.2,
+            1.6, 2.8, 1.8,
+        ];
+        let expected_bilinear_outputs = vec![
+            0.1, 0.2, 0.4, 0.45, 0.35, 0.3,
+            0.25, 0.2938, 0.3813, 0.4313, 0.4437, 0.45,
+            0.55, 0.4812, 0.3438, 0.3937, 0.6313, 0.75,
+            0.7, 0.575, 0.325, 0.375, 0.725, 0.9,
+        ];
+        let expected_bilinear_loss_to_input_derivatives = vec![
+            1.8687, 2.3062, 1.575,
+            1.9812, 2.1437, 1.825,
+        ];
+
+        for (interpolation, expected_outputs, expected_loss_to_input_derivatives) in [
+            (
+                UpsamplingInterpolation::Nearest,
+                expected_nearest_outputs,
+                expected_nearest_loss_to_input_derivatives,
+            ),
+            (
+                UpsamplingInterpolation::Bilinear,
+                expected_bilinear_outputs,
+                expected_bilinear_loss_to_input_derivatives,
+            ),
+        ] {
+            let mut layer =
+                Upsampling2D::new_raw((3, 2), 1, (2, 2)).set_interpolation(interpolation);
+            layer.init(&opencl_state).expect("unable to init Upsampling2D");
+
+            let outputs_buffer = layer
+                .propagate(
+                    &inputs
+                        .to_buffer(false, &opencl_state)
+                        .expect("unable to get the inputs buffer"),
+                )
+                .expect("unable to propagate the Upsampling2D");
+            let outputs = Vec::<f32>::from_buffer(outputs_buffer, false, &opencl_state)
+                .expect("unable to read the outputs buffer");
+
+            assert_approx_equal_distance(&outputs, &expected_outputs, 0.01);
+
+            let loss_to_input_derivatives_buffer = layer
+                .compute_loss_to_input_derivatives(
+                    &loss_to_output_derivatives
+                        .to_buffer(false, &opencl_state)
+                        .expect("unable to get the loss to output derivatives buffer"),
+                )
+                .expect("unable to compute the loss to input derivatives of the Upsampling2D");
+            let loss_to_input_derivatives =
+                Vec::<f32>::from_buffer(&loss_to_input_derivatives_buffer, false, &opencl_state)
+                    .expect("unable to read the loss to input derivatives buffer");
+
+            assert_approx_equal_distance(
+                &loss_to_input_derivatives,
+                &expected_loss_to_input_derivatives,
+                0.01,
+            );
+        }
+    }
+}
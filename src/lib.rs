@@ -12,10 +12,13 @@
 pub mod layers;
 pub mod loss_functions;
 pub mod model;
+pub mod graph_model;
 pub mod utils;
 pub mod optimizers;
+pub mod callbacks;
 
 pub use model::Model;
+pub use graph_model::GraphModel;
 pub use types::TrainingOptions;
 pub use types::TrainingVerbosity;
 
@@ -64,6 +64,9 @@ pub struct CategoricalCrossEntropy<'a> {
     /// This keeps track of weather or not the last layer in the Model using the loss_fn is a
     /// Softmax
     is_optimized_for_softmax: bool,
+    /// The amount of classes at each position of the outputs, or None if all of the outputs of a
+    /// sample are the classes.
+    pub channels: Option<usize>,
 }
 
 impl<'a> CategoricalCrossEntropy<'a> {
@@ -75,6 +78,28 @@ impl<'a> CategoricalCrossEntropy<'a> {
         CategoricalCrossEntropy { 
             opencl_state: None,
             is_optimized_for_softmax: false,
+            channels: None,
+        }
+    }
+
+    /// Makes the loss treat the outputs of each sample as having **channels** classes at each one
+    /// of their positions, such as the pixels of the outputs of a Conv2D that go through a SoftMax
+    /// with the same amount of channels, with all of the outputs of a channel one after the other.
+    ///
+    /// The loss of each sample is then the average of the losses of each position, which is good
+    /// for per-pixel classification like segmentation.
+    pub fn set_channels(mut self, channels: usize) -> Self {
+        assert!(channels > 0, "the amount of channels must be greater than zero");
+
+        self.channels = Some(channels);
+        self
+    }
+
+    fn get_positions_amount(&self, outputs_amount: usize) -> f32 {
+        if let Some(channels) = self.channels {
+            outputs_amount as f32 / channels as f32
+        } else {
+            1.0
         }
     }
 
@@ -131,6 +156,10 @@ impl<'a> LossFunction<'a> for CategoricalCrossEntropy<'a> {
         Ok(())
     }
 
+    fn is_optimized_for_softmax(&self) -> bool {
+        self.is_optimized_for_softmax
+    }
+
     fn compute_loss(
         &self,
         output_samples: &Buffer<cl_float>,
@@ -174,6 +203,7 @@ impl<'a> LossFunction<'a> for CategoricalCrossEntropy<'a> {
             .set_arg(&sample_losses_buffer)
             .set_arg(&(outputs_amount as cl_int))
             .set_arg(&(samples_amount as cl_int))
+            .set_arg(&(self.get_positions_amount(outputs_amount) as cl_float))
             .set_global_work_size(samples_amount)
             .enqueue_nd_range(queue)?;
 
@@ -229,6 +259,7 @@ impl<'a> LossFunction<'a> for CategoricalCrossEntropy<'a> {
                 .set_arg(&derivatives_buffer)
                 .set_arg(&(samples_amount as cl_int))
                 .set_arg(&(outputs_amount as cl_int))
+                .set_arg(&(self.get_positions_amount(outputs_amount) as cl_float))
                 .set_global_work_sizes(&[samples_amount, outputs_amount])
                 .enqueue_nd_range(queue)?;
         } else {
@@ -240,6 +271,7 @@ impl<'a> LossFunction<'a> for CategoricalCrossEntropy<'a> {
                 .set_arg(&derivatives_buffer)
                 .set_arg(&(samples_amount as cl_int))
                 .set_arg(&(outputs_amount as cl_int))
+                .set_arg(&(self.get_positions_amount(outputs_amount) as cl_float))
                 .set_global_work_sizes(&[samples_amount, outputs_amount])
                 .enqueue_nd_range(queue)?;
         }
@@ -262,7 +294,10 @@ mod categorical_cross_entropy_tests {
 
     use super::CategoricalCrossEntropy;
     use crate::utils::{approx_eq::assert_approx_equal_distance, setup_opencl, OpenCLState};
-    use crate::{loss_functions::LossFunction, utils::opencl::DeviceType};
+    use crate::{
+        loss_functions::LossFunction,
+        utils::opencl::{BufferLike, DeviceType},
+    };
 
     #[test]
     fn should_compute_derivatives_up_to_a_certain_precision() {
@@ -439,4 +474,43 @@ mod categorical_cross_entropy_tests {
         );
         assert!((expected_loss - actual_loss).abs() / largest_loss <= 0.001);
     }
+
+    #[test]
+    fn should_average_the_loss_over_the_positions_when_there_are_channels() {
+        let opencl_state: OpenCLState = setup_opencl(DeviceType::GPU).unwrap();
+
+        let mut loss = CategoricalCrossEntropy::new_raw().set_channels(2);
+        loss.init(&opencl_state).unwrap();
+
+        // two classes at each one of the two positions
+        let outputs: Vec<f32> = vec![0.5, 0.75, 0.5, 0.25];
+        let expected_outputs: Vec<f32> = vec![1.0, 0.0, 0.0, 1.0];
+
+        let expected_loss = -(0.5_f32.ln() + 0.25_f32.ln()) / 2.0;
+        let expected_derivatives = vec![-1.0, 0.0, 0.0, -2.0];
+
+        let outputs_buf = outputs.to_buffer(false, &opencl_state).unwrap();
+        let expected_outputs_buf = expected_outputs.to_buffer(false, &opencl_state).unwrap();
+
+        let actual_loss = loss
+            .compute_loss(&outputs_buf, &expected_outputs_buf, 1)
+            .unwrap();
+
+        assert!((expected_loss - actual_loss).abs() <= 0.001);
+
+        let derivatives = Vec::<f32>::from_buffer(
+            &loss
+                .compute_loss_derivative_with_respect_to_output_samples(
+                    &outputs_buf,
+                    &expected_outputs_buf,
+                    1,
+                )
+                .unwrap(),
+            false,
+            &opencl_state,
+        )
+        .unwrap();
+
+        assert_approx_equal_distance(&derivatives, &expected_derivatives, 0.01);
+    }
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+// the positions_amount is the amount of positions, such as pixels, that each have their own
+// classes in the outputs of a sample, the loss and its derivatives are averaged over them
 kernel void compute_loss(
     global float* output_samples,
     global float* expected_output_samples,
@@ -5,7 +7,9 @@ kernel void compute_loss(
     global float* sample_losses,
 
     int outputs_amount,
-    int samples_amount
+    int samples_amount,
+
+    float positions_amount
 ) {
     int sample_index = get_global_id(0);
 
@@ -25,7 +29,7 @@ kernel void compute_loss(
             /* + (1.0f - expected_output) * log(1.0f - output); */
     }
 
-    sample_losses[sample_index] = sample_loss;
+    sample_losses[sample_index] = sample_loss / positions_amount;
 }
 
 kernel void compute_loss_to_output_derivatives_optimized_for_softmax(
@@ -35,7 +39,9 @@ kernel void compute_loss_to_output_derivatives_optimized_for_softmax(
     global float* loss_to_output_derivatives,
 
     int samples_amount,
-    int outputs_amount
+    int outputs_amount,
+
+    float positions_amount
 ) {
     int sample_index = get_global_id(0);
 
@@ -45,7 +51,7 @@ kernel void compute_loss_to_output_derivatives_optimized_for_softmax(
 
     int output_index = get_global_id(1);
     
-    if (output_index > outputs_amount) {
+    if (output_index >= outputs_amount) {
         return;
     }
 
@@ -54,7 +60,7 @@ kernel void compute_loss_to_output_derivatives_optimized_for_softmax(
     float activeted_output = (float) activated_output_samples[flat_i];
     float expected_output = (float) expected_output_samples[flat_i];
 
-    loss_to_output_derivatives[flat_i] = activeted_output - expected_output;
+    loss_to_output_derivatives[flat_i] = (activeted_output - expected_output) / positions_amount;
 }
 
 kernel void compute_loss_to_output_derivatives(
@@ -64,7 +70,9 @@ kernel void compute_loss_to_output_derivatives(
     global float* loss_to_output_derivatives,
 
     int samples_amount,
-    int outputs_amount
+    int outputs_amount,
+
+    float positions_amount
 ) {
     int sample_index = get_global_id(0);
 
@@ -74,7 +82,7 @@ kernel void compute_loss_to_output_derivatives(
 
     int output_index = get_global_id(1);
     
-    if (output_index > outputs_amount) {
+    if (output_index >= outputs_amount) {
         return;
     }
 
@@ -84,7 +92,7 @@ kernel void compute_loss_to_output_derivatives(
     float expected_output = (float) expected_output_samples[flat_i];
     output = min(max(output, 0.0000001f), 0.9999999f);
 
-    loss_to_output_derivatives[flat_i] = -expected_output / output;
+    loss_to_output_derivatives[flat_i] = -expected_output / output / positions_amount;
         //+ (1.0f - expected_output) / (1.0f - output);
 }
 
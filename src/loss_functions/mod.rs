@@ -143,6 +143,12 @@ where
         expected_outputs: &Buffer<cl_float>,
         samples_amount: usize,
     ) -> Result<Buffer<cl_float>, LossToModelOutputsDerivativesComputationError>;
+
+    /// Weather or not the derivatives given out by this loss function are already with respect
+    /// to the inputs of a SoftMax that comes right before it.
+    fn is_optimized_for_softmax(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, FromForAllUnnamedVariants)]
@@ -223,4 +229,11 @@ impl<'a> LossFunction<'a> for LossFn<'a> {
                 ),
         }
     }
+
+    fn is_optimized_for_softmax(&self) -> bool {
+        match self {
+            LossFn::CategoricalCrossEntropy(loss) => loss.is_optimized_for_softmax(),
+            _ => false,
+        }
+    }
 }
@@ -1,7 +1,7 @@
 //! The module that implements a sequential Model, that contains some layers, and forward passes
 //! some inputs over and over again from one layer to another.
 
-use std::{fmt::Write, time::Instant};
+use std::{fmt::Write, ptr, time::Instant};
 
 use super::utils::OpenCLState;
 use indicatif::{ProgressBar, ProgressState, ProgressStyle};
@@ -15,11 +15,14 @@ use opencl3::{
     memory::{Buffer, ClMem, CL_MEM_READ_WRITE},
 };
 use opencl3::{error_codes::cl_int, kernel::ExecuteKernel, memory::CL_MEM_READ_ONLY};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use savefile::{load_noschema, save_noschema};
 use savefile_derive::Savefile;
 use std::mem;
 
 use crate::{
+    callbacks::{CallbackAction, EpochResults, TrainingCallback, TrainingModel},
     layers::{
         Gradient, Layer, LayerGradientApplicationError, LayerGradientComputationError,
         LayerInitializationError, LayerLossToInputDifferentiationError, LayerPropagationError,
@@ -31,8 +34,9 @@ use crate::{
     },
     optimizers::Optimizer,
     types::{
-        HaltingCondition, KernelNotFoundError, ModelLayer, ProgramNotFoundError, SyncDataError,
-        TrainingOptions, TrainingResults,
+        EvaluationResults, HaltingCondition, KernelNotFoundError, Metric, ModelLayer,
+        MonitoredQuantity, ProgramNotFoundError, SyncDataError, TrainingOptions,
+        TrainingResults, ValidationData,
     },
     utils::{
         opencl::{
@@ -46,11 +50,17 @@ use crate::{
 const MODEL_PROGRAM_SOURCE: &str = include_str!("kernels/model.cl");
 const MODEL_PROGRAM_NAME: &str = "MODEL";
 const COMPUTE_ACCURACIES_KERNEL_NAME: &str = "compute_accuracy_per_output";
+const GATHER_SAMPLES_KERNEL_NAME: &str = "gather_samples";
+
+pub(crate) const SAVED_PARAMETERS_VERSION: u32 = 0;
 
 pub(crate) fn compile_model(
     opencl_state: &mut OpenCLState,
 ) -> Result<(), EnsureKernelsAndProgramError> {
-    let kernels = &[COMPUTE_ACCURACIES_KERNEL_NAME.to_string()];
+    let kernels = &[
+        COMPUTE_ACCURACIES_KERNEL_NAME.to_string(),
+        GATHER_SAMPLES_KERNEL_NAME.to_string(),
+    ];
 
     ensure_program(
         opencl_state,
@@ -117,6 +127,13 @@ pub enum ModelPredictionError {
     LayerPropagation(LayerPropagationError),
     /// Happens when the Model has no layers inside of it
     NoLayers,
+
+    /// Happens when a required program was not found
+    ProgramNotFound(ProgramNotFoundError),
+    /// Happens when a required kernel was not found in a program
+    KernelNotFound(KernelNotFoundError),
+    /// Happens when something goes wrong in a predefined buffer operation
+    BufferOperation(BufferOperationError),
 }
 
 #[derive(Debug, FromForAllUnnamedVariants)]
@@ -140,6 +157,9 @@ pub enum ModelFittingError {
     /// Happens when the Halting Condition for the training process is the `MinAccuracyReached` which
     /// requires that the accuracy is computed in the training process
     NoAccuracyForHaltingCondition,
+    /// Happens when the Halting Condition for the training process is the `EarlyStopping`
+    /// monitoring a validation quantity but there is no validation data
+    NoValidationDataForHaltingCondition,
 
     /// Happens when something goes wrong in a predefined buffer operation
     BufferOperation(BufferOperationError),
@@ -164,6 +184,48 @@ pub enum ModelFittingError {
     NoLayers,
     /// Happens when something goes wrong while computing the overall loss of the Model
     LossComputation(LossComputationError),
+    /// Happens when something goes wrong while evaluating the Model on the validation data
+    ModelEvaluation(ModelEvaluationError),
+    /// Happens when something goes wrong while saving the best parameters of the Model for the
+    /// `EarlyStopping` halting condition
+    SyncData(SyncDataError),
+    /// Happens when something goes wrong while restoring the best parameters of the Model for the
+    /// `EarlyStopping` halting condition
+    LayerInitialization(LayerInitializationError),
+}
+
+#[derive(Debug, FromForAllUnnamedVariants)]
+/// An enum containing all of the possible errors that can happen when evaluating a Model.
+pub enum ModelEvaluationError {
+    /// Happens when the Model was not initialized before calling the method.
+    NotInitialized,
+    /// Happens mostly if there is no device in the current OpenCLState.
+    NoCommandQueue,
+    /// Happens when the Model has no layers inside of it
+    NoLayers,
+
+    /// Happens when there are no samples to evaluate the Model on.
+    NoSamples,
+    /// Happens when the batch size is zero.
+    ZeroBatchSize,
+    /// Happens when there is not the same amount of input samples and expected output samples.
+    SamplesAmountsDontMatch,
+
+    /// Happens when a required program was not found
+    ProgramNotFound(ProgramNotFoundError),
+    /// Happens when a required kernel was not found in a program
+    KernelNotFound(KernelNotFoundError),
+    /// Happens when something goes wrong in a predefined buffer operation
+    BufferOperation(BufferOperationError),
+
+    /// Happens if something goes wrong with OpenCL.
+    OpenCL(ClError),
+    /// Happens when converting a Vec into a buffer.
+    Conversion(BufferConversionError),
+    /// Happens when something goes wrong in the prediction of the Model.
+    ModelPrediction(ModelPredictionError),
+    /// Happens when something goes wrong while computing the loss of the Model
+    LossComputation(LossComputationError),
 }
 
 #[derive(Debug, FromForAllUnnamedVariants)]
@@ -180,6 +242,13 @@ pub enum ModelGradientComputationError {
     /// Happens when there goes something wrong with OpenCL.
     OpenCL(ClError),
 
+    /// Happens when a required program was not found
+    ProgramNotFound(ProgramNotFoundError),
+    /// Happens when a required kernel was not found in a program
+    KernelNotFound(KernelNotFoundError),
+    /// Happens when something goes wrong in a predefined buffer operation
+    BufferOperation(BufferOperationError),
+
     /// Happens when the gradient computation of a layer goes wrong.
     ///
     /// This error also contains the index of the layer at which this error happenned.
@@ -271,7 +340,19 @@ impl<'a> Model<'a> {
     /// CompilationError (just a String with some stacktrace to the error).
     /// If the programs were compiled successfully don't put your guard down yet because OpenCL may
     /// yield some error if something it needs to do fails.
+    ///
+    /// This will also yield an error if the amount of outputs of some layer is not the amount of
+    /// inputs of the layer that comes after it, or if both of them have a shape, such as the
+    /// `vec![channels, height, width]` of the layers that deal with images, and the shapes differ.
     pub fn init(&mut self, opencl_state: &'a OpenCLState) -> Result<(), LayerInitializationError> {
+        for (i, layers) in self.layers.windows(2).enumerate() {
+            if layers[0].get_outputs_amount() != layers[1].get_inputs_amount()
+                || !shapes_match(layers[0].get_outputs_shape(), layers[1].get_inputs_shape())
+            {
+                return Err(LayerInitializationError::LayersShapesDontMatch(i));
+            }
+        }
+
         for layer in self.layers.iter_mut() {
             layer.init(opencl_state)?;
         }
@@ -418,10 +499,10 @@ impl<'a> Model<'a> {
         Ok(current_values)
     }
 
-    /// fits the Model to best suit the training data
-    /// using the back_propagate method of every layer
-    /// and prints the loss, if it is computing the loss
-    /// it will return the losses after every single **training step**.
+    /// Computes the loss and the requested **metrics** of the Model over some samples without
+    /// computing any gradients, forward passing at most **batch_size** samples at once.
+    ///
+    /// This is useful for scoring a Model that was already trained on a held-out test set.
     ///
     /// # Errors
     ///
@@ -429,325 +510,140 @@ impl<'a> Model<'a> {
     /// - the Model is not initialized;
     /// - there is no command queue in the OpenCLState;
     /// - there are no layers in the Model;
+    /// - there are no samples or the batch size is zero;
+    /// - the amounts of input samples and expected output samples are not the same;
     /// - something goes wrong in the initialization of the loss function;
-    /// - something goes wrong in the initialization of the optimizer;
-    /// - something goes wrong when trying to convert the training_inputs samples into a buffer;
-    /// - something goes wrong when trying to convert the training_expected_output_samples into a
-    /// buffer;
-    /// - there are no calculated losses for a HaltingCondition of MinLoss;
-    /// - there are no calculated accuracies for a HaltingCondition of MinAccuracy;
-    /// - something goes wrong inside of parameter optimization;
-    /// - something goes wrong in the gradients computation method;
-    /// - something goes wrong in the gradients application;
+    /// - something goes wrong when trying to convert the samples into buffers;
     /// - something goes wrong in the prediction of the Model;
     /// - something goes wrong in the loss computation;
     /// - something goes wrong inside OpenCL.
-    pub fn fit(
+    pub fn evaluate(
         &mut self,
-        training_input_samples: &Vec<Vec<f32>>,
-        training_expected_output_samples: &Vec<Vec<f32>>,
-        training_options: &mut TrainingOptions<'a>,
-    ) -> Result<TrainingResults, ModelFittingError> {
+        input_samples: &Vec<Vec<f32>>,
+        expected_output_samples: &Vec<Vec<f32>>,
+        loss_fn: &mut LossFn<'a>,
+        metrics: &[Metric],
+        batch_size: usize,
+    ) -> Result<EvaluationResults, ModelEvaluationError> {
         if self.opencl_state.is_none() {
-            return Err(ModelFittingError::NotInitialized);
+            return Err(ModelEvaluationError::NotInitialized);
         }
 
         let state = self.opencl_state.unwrap();
 
         if state.queues.is_empty() {
-            return Err(ModelFittingError::NoCommandQueue);
+            return Err(ModelEvaluationError::NoCommandQueue);
         }
 
         if self.layers.is_empty() {
-            return Err(ModelFittingError::ModelHasNoLayers);
+            return Err(ModelEvaluationError::NoLayers);
         }
 
-        let last_layer = self.layers.last().unwrap();
-
-        assert_eq!(
-            training_input_samples.len(),
-            training_expected_output_samples.len()
-        );
-
-        // This would mean that the last layer would need to be ignored when calculting gradients
-        // at back-prop
-        let optimizing_for_softmax = false;
+        let samples_amount = input_samples.len();
 
-        if let ModelLayer::SoftMax(_) = last_layer {
-            if let LossFn::CategoricalCrossEntropy(loss) = training_options.loss_fn {
-                loss.set_optimized_for_softmax(true);
-            }
+        if samples_amount == 0 {
+            return Err(ModelEvaluationError::NoSamples);
         }
 
-        training_options.loss_fn.init(state)?;
-        training_options.optimizer.init(state)?;
-
-        let inputs_amount = self.layers[0].get_inputs_amount();
-        let outputs_amount = self.layers.last().unwrap().get_outputs_amount();
-        let samples_amount = training_input_samples.len();
-
-        let input_samples_buffer = training_input_samples
-            .par_iter()
-            .flatten()
-            .map(|x| *x)
-            .collect::<Vec<f32>>()
-            .to_buffer(false, state)?;
+        if batch_size == 0 {
+            return Err(ModelEvaluationError::ZeroBatchSize);
+        }
 
-        let expected_output_samples_buffer = training_expected_output_samples
-            .par_iter()
-            .flatten()
-            .map(|x| *x)
-            .collect::<Vec<f32>>()
-            .to_buffer(false, state)?;
+        if samples_amount != expected_output_samples.len() {
+            return Err(ModelEvaluationError::SamplesAmountsDontMatch);
+        }
 
-        let steps_amount =
-            calculate_training_steps_amount(samples_amount, training_options.batch_size);
+        loss_fn.init(state)?;
 
-        let mut losses: Vec<f32> = Vec::with_capacity(training_options.epochs * steps_amount);
-        let mut accuracies: Vec<f32> = Vec::with_capacity(training_options.epochs * steps_amount);
+        let input_samples_buffer = flatten_into_buffer(input_samples, state)?;
+        let expected_output_samples_buffer = flatten_into_buffer(expected_output_samples, state)?;
 
-        let per_step_inputs: Vec<Buffer<cl_float>> = separate_into_sub_buffer_batches(
+        let (loss, accuracy) = compute_metrics_in_batches(
+            self,
             &input_samples_buffer,
-            steps_amount,
-            samples_amount,
-            training_options.batch_size,
-            inputs_amount,
-        )?;
-
-        let per_step_outputs: Vec<Buffer<cl_float>> = separate_into_sub_buffer_batches(
             &expected_output_samples_buffer,
-            steps_amount,
             samples_amount,
-            training_options.batch_size,
-            outputs_amount,
+            batch_size,
+            loss_fn,
+            true,
+            metrics.contains(&Metric::Accuracy),
         )?;
 
-        let mut timestep: usize = 0;
-
-        for epoch_index in 0..training_options.epochs {
-            let start = Instant::now();
-
-            let mut progress = None;
-            if training_options.verbosity.show_current_epoch {
-                println!("---------");
-                println!("epoch #{}", epoch_index + 1);
-            }
-
-            if training_options.verbosity.show_epoch_progress
-                && training_options.batch_size < samples_amount
-            {
-                let pbar = ProgressBar::new(
-                    (samples_amount as f32 / training_options.batch_size as f32).ceil() as u64,
-                );
-                pbar.set_style(
-                    ProgressStyle::with_template(
-                        "[{bar:10}] [{per_second}/s] {pos}/{len} {elapsed}/{eta} {msg}",
-                    )
-                    .expect("unable to create epoch training steps progress bar")
-                    .with_key("elapsed", |state: &ProgressState, w: &mut dyn Write| {
-                        write!(w, "{}", format!("{:.2}s", state.elapsed().as_secs_f32())).unwrap()
-                    })
-                    .with_key("per_second", |state: &ProgressState, w: &mut dyn Write| {
-                        write!(w, "{}", format!("{:.2}", state.per_sec())).unwrap()
-                    })
-                    .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
-                        write!(w, "{}", format!("{:.2}s", state.eta().as_secs_f32())).unwrap()
-                    })
-                    .progress_chars("=> "),
-                );
-                progress = Some(pbar);
-            }
-
-            let mut epoch_losses: Vec<f32> = Vec::with_capacity(steps_amount);
-            let mut epoch_accuracies: Vec<f32> = Vec::with_capacity(steps_amount);
-
-            for i_batch in 0..steps_amount {
-                timestep += 1;
-
-                let batch_inputs = &per_step_inputs[i_batch];
-                let batch_outputs = &per_step_outputs[i_batch];
-
-                let local_batch_size;
-                if i_batch == steps_amount - 1 && samples_amount % training_options.batch_size != 0
-                {
-                    local_batch_size = samples_amount % training_options.batch_size;
-                } else {
-                    local_batch_size = training_options.batch_size;
-                }
-
-                let (optional_loss, optional_accuracy) = self.do_training_step(
-                    batch_inputs,
-                    batch_outputs,
-                    local_batch_size,
-                    timestep,
-                    optimizing_for_softmax,
-                    training_options,
-                )?;
-
-                if let Some(loss) = optional_loss {
-                    losses.push(loss);
-                    epoch_losses.push(loss);
-                }
-
-                if let Some(accuracy) = optional_accuracy {
-                    accuracies.push(accuracy);
-                    epoch_accuracies.push(accuracy);
-                }
-
-                if progress.is_some() {
-                    let pbar = progress.as_ref().unwrap();
-                    pbar.inc(1);
-                    if training_options.verbosity.print_loss || training_options.compute_loss {
-                        pbar.set_message(format!("(loss: {:.3})", losses.last().unwrap()));
-                    }
-                }
-            }
-
-            if progress.is_some() {
-                progress.as_ref().unwrap().finish_and_clear();
-            }
-
-            let epoch_loss = epoch_losses.iter().sum::<f32>() / steps_amount as f32;
-            let epoch_accuracy = epoch_accuracies.iter().sum::<f32>() / steps_amount as f32;
-
-            if training_options.verbosity.print_loss {
-                println!("got a loss of {} after epoch", epoch_loss);
-            }
-
-            if training_options.verbosity.print_accuracy {
-                println!(
-                    "got a accuracy of {:.3}% after epoch",
-                    epoch_accuracy * 100.0
-                );
-            }
-
-            if training_options.verbosity.show_epoch_elapsed {
-                println!("{:.3}s elapsed on epoch", start.elapsed().as_secs_f32());
-            }
-
-            if let Some(halting_condition) = &training_options.halting_condition {
-                match halting_condition {
-                    HaltingCondition::MinLossReached(min_loss) => {
-                        if losses.is_empty() {
-                            return Err(ModelFittingError::NoLossForHaltingCondition);
-                        }
-
-                        if min_loss >= &epoch_loss {
-                            if training_options.verbosity.halting_condition_warning {
-                                println!("stopping training process due to MinLossReached halting condition...");
-                            }
-
-                            break;
-                        }
-                    }
-                    HaltingCondition::MinAccuracyReached(min_acc) => {
-                        if accuracies.is_empty() {
-                            return Err(ModelFittingError::NoAccuracyForHaltingCondition);
-                        }
-
-                        if min_acc <= &epoch_accuracy {
-                            if training_options.verbosity.halting_condition_warning {
-                                println!("stopping training process due to MinAccuracyReached halting condition...");
-                            }
-
-                            break;
-                        }
-                    }
-                };
-            }
-        }
-
-        Ok(TrainingResults {
-            loss_per_training_steps: losses,
-            accuracy_per_training_steps: accuracies,
+        Ok(EvaluationResults {
+            loss: loss.unwrap(),
+            metrics: metrics
+                .iter()
+                .map(|metric| match metric {
+                    Metric::Accuracy => (*metric, accuracy.unwrap()),
+                })
+                .collect(),
         })
     }
 
-    fn do_training_step(
+    /// fits the Model to best suit the training data
+    /// using the back_propagate method of every layer
+    /// and prints the loss, if it is computing the loss
+    /// it will return the losses after every single **training step**.
+    ///
+    /// If the training options have validation data, the Model is also evaluated on it after
+    /// every epoch and the validation losses and accuracies are returned for each **epoch**.
+    ///
+    /// # Errors
+    ///
+    /// Yields an error if:
+    /// - the Model is not initialized;
+    /// - there is no command queue in the OpenCLState;
+    /// - there are no layers in the Model;
+    /// - something goes wrong in the initialization of the loss function;
+    /// - something goes wrong in the initialization of the optimizer;
+    /// - something goes wrong when trying to convert the training_inputs samples into a buffer;
+    /// - something goes wrong when trying to convert the training_expected_output_samples into a
+    /// buffer;
+    /// - there are no calculated losses for a HaltingCondition of MinLoss;
+    /// - there are no calculated accuracies for a HaltingCondition of MinAccuracy;
+    /// - there is no validation data for a HaltingCondition of EarlyStopping that monitors a
+    /// validation quantity;
+    /// - something goes wrong when saving or restoring the best parameters of the Model;
+    /// - something goes wrong inside of parameter optimization;
+    /// - something goes wrong in the gradients computation method;
+    /// - something goes wrong in the gradients application;
+    /// - something goes wrong in the prediction of the Model;
+    /// - something goes wrong in the loss computation;
+    /// - something goes wrong inside OpenCL.
+    pub fn fit(
         &mut self,
-        input_samples: &Buffer<cl_float>,
-        expected_output_samples: &Buffer<cl_float>,
-        samples_amount: usize,
-        timestep: usize,
-        optimizing_for_softmax: bool,
+        training_input_samples: &Vec<Vec<f32>>,
+        training_expected_output_samples: &Vec<Vec<f32>>,
         training_options: &mut TrainingOptions<'a>,
-    ) -> Result<(Option<f32>, Option<f32>), ModelFittingError> {
-        if self.opencl_state.is_none() {
-            return Err(ModelFittingError::NotInitialized);
-        }
-
-        let state = self.opencl_state.unwrap();
-
-        if state.queues.is_empty() {
-            return Err(ModelFittingError::NoCommandQueue);
-        }
-
-        let queue = &state.queues[0];
-
-        if self.layers.len() == 0 {
-            return Err(ModelFittingError::NoLayers);
-        }
-
-        for (i, layer) in self.layers.iter_mut().enumerate() {
-            if let Err(err) = layer.optimize_parameters(training_options.optimizer, i, timestep) {
-                return Err(ModelFittingError::ParameterOptimization(i, err));
-            }
-        }
-
-        let gradients = self.compute_gradients(
-            &input_samples,
-            &expected_output_samples,
-            training_options.loss_fn,
-            optimizing_for_softmax,
-        )?;
-
-        self.apply_gradients(gradients.as_slice(), training_options.optimizer, timestep)?;
-
-        let loss;
-        let accuracy;
-
-        if training_options.compute_loss || training_options.compute_accuracy {
-            self.predict_with_buffer(input_samples)?;
-        }
-
-        if training_options.compute_loss {
-            let actual_outputs = self.layers.last().unwrap().get_last_outputs().unwrap();
+    ) -> Result<TrainingResults, ModelFittingError> {
+        fit_model(
+            self,
+            training_input_samples,
+            training_expected_output_samples,
+            training_options,
+        )
+    }
 
-            loss = Some(training_options.loss_fn.compute_loss(
-                actual_outputs,
-                &expected_output_samples,
-                samples_amount,
-            )?);
-        } else {
-            loss = None;
+    /// Tells all of the layers of the Model weather or not they are being trained.
+    ///
+    /// This is already done by the `fit` method, so it is only needed when training the Model
+    /// by hand with methods such as `compute_gradients` and `apply_gradients`.
+    pub fn set_training_mode(&mut self, is_training: bool) -> () {
+        for layer in self.layers.iter_mut() {
+            layer.set_training_mode(is_training);
         }
+    }
 
-        if training_options.compute_accuracy {
-            let actual_outputs = self.layers.last().unwrap().get_last_outputs().unwrap();
-
-            let program = state.get_prgm(MODEL_PROGRAM_NAME)?;
-            let accuracy_kernel = program.get_krnl(COMPUTE_ACCURACIES_KERNEL_NAME)?;
-
-            let outputs_total_count = actual_outputs.size()? / mem::size_of::<cl_float>();
-
-            let accuracies = empty_buffer(outputs_total_count, CL_MEM_READ_WRITE, state)?;
-
-            ExecuteKernel::new(accuracy_kernel)
-                .set_arg(actual_outputs)
-                .set_arg(expected_output_samples)
-                .set_arg(&accuracies)
-                .set_arg(&(outputs_total_count as cl_int))
-                .set_global_work_size(outputs_total_count)
-                .enqueue_nd_range(queue)?;
-
-            queue.finish()?;
-
-            accuracy = Some(accuracies.sum(state)? / outputs_total_count as f32);
-        } else {
-            accuracy = None;
+    /// Tells all of the layers of the Model weather or not the forward passes made while training
+    /// should update the statistics they keep for predicting, such as the running mean and
+    /// variance of a BatchNorm.
+    ///
+    /// The `fit` method already turns this off when forward passing just to compute the metrics
+    /// of a training step.
+    pub fn set_updating_running_statistics(&mut self, should_update: bool) -> () {
+        for layer in self.layers.iter_mut() {
+            layer.set_updating_running_statistics(should_update);
         }
-
-        Ok((loss, accuracy))
     }
 
     /// Applies all the gradients calculated per layer calling each layer's respective
@@ -803,6 +699,10 @@ impl<'a> Model<'a> {
     /// Computes the gradients for each one of the layers in the Model calling each layer's
     /// `compute_gradients` in conjuction with the `compute_loss_to_input_derivatives`.
     ///
+    /// If the loss function is optimized for a SoftMax and the last layer is one, the derivatives
+    /// given out by the loss function are already with respect to the inputs of that SoftMax, so
+    /// its derivatives are not computed at all and its gradients are left empty.
+    ///
     /// # Errors
     ///
     /// Yields an error if:
@@ -820,7 +720,6 @@ impl<'a> Model<'a> {
         // training_actual_outputs: &Buffer<cl_float>,
         training_expected_output_samples: &Buffer<cl_float>,
         loss_function: &dyn LossFunction, //ModelLossFunction<'a>,
-        optimizing_for_softmax: bool,
     ) -> Result<Vec<Vec<Gradient>>, ModelGradientComputationError> {
         if self.opencl_state.is_none() {
             return Err(ModelGradientComputationError::NotInitialized);
@@ -844,6 +743,9 @@ impl<'a> Model<'a> {
 
         let layers_amount = self.layers.len();
 
+        let optimizing_for_softmax = loss_function.is_optimized_for_softmax()
+            && matches!(self.layers.last(), Some(ModelLayer::SoftMax(_)));
+
         let training_actual_outputs = self.predict_with_buffer(training_input_samples)?;
 
         let mut gradients: Vec<Vec<Gradient>> = Vec::with_capacity(layers_amount);
@@ -854,16 +756,13 @@ impl<'a> Model<'a> {
                 &training_expected_output_samples,
                 samples_amount,
             )?;
-        for (i, layer) in self
-            .layers
-            .iter()
-            .enumerate()
-            .rev()
-            .skip(match optimizing_for_softmax {
-                true => 1,
-                false => 0,
-            })
-        {
+        for (i, layer) in self.layers.iter().enumerate().rev() {
+            // the gradients must still be one per layer for them to be applied to the right layers
+            if optimizing_for_softmax && i == layers_amount - 1 {
+                gradients.push(Vec::default());
+                continue;
+            }
+
             let gradients_result = layer.compute_gradients(&last_loss_to_outputs_derivatives);
             if let Ok(layer_gradients) = gradients_result {
                 gradients.push(layer_gradients);
@@ -886,56 +785,954 @@ impl<'a> Model<'a> {
     }
 }
 
-fn calculate_training_steps_amount(samples_amount: usize, batch_size: usize) -> usize {
-    (samples_amount as f32 / batch_size as f32).ceil() as usize
-}
-
-#[test]
-fn should_calculate_training_steps_amount_correctly() {
-    let samples_amount = 25;
-    let batch_size = 4;
 
-    let correct_training_steps = 7;
-    let actual_training_steps = calculate_training_steps_amount(samples_amount, batch_size);
+/// The parts of a model that the training process needs, so that the same training loop can be
+/// used for both the sequential Model and the GraphModel.
+pub(crate) trait TrainableModel<'a> {
+    fn get_opencl_state(&self) -> Option<&'a OpenCLState>;
 
-    assert_eq!(correct_training_steps, actual_training_steps);
-}
+    fn has_layers(&self) -> bool;
 
-fn calculate_batch_origin_and_count(
-    steps_amount: usize,
-    batch_size: usize,
-    batch_index: usize,
-    samples_amount: usize, //   origin, count
-) -> (usize, usize) {
-    let (origin, count);
-    if batch_index == steps_amount - 1 && samples_amount % batch_size != 0 {
-        count = samples_amount % batch_size;
-        origin = samples_amount - count;
-    } else {
-        count = batch_size;
-        origin = batch_index * count;
-    }
+    fn get_inputs_amount(&self) -> usize;
 
-    (origin, count)
-}
+    fn get_outputs_amount(&self) -> usize;
 
-#[test]
-fn should_calculate_batch_origin_and_count_correctly_for_normal_batches() {
-    let samples_amount = 6123;
-    let batch_size = 25;
+    // the layer that gives out the predictions of the model, if the outputs come out from a layer
+    fn get_output_layer(&self) -> Option<&ModelLayer<'a>>;
 
-    let steps_amount = 245;
-    let batch_index = 123;
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>>;
 
-    let expected_origin = batch_size * batch_index;
-    let expected_count = batch_size;
+    fn set_training_mode(&mut self, is_training: bool) -> ();
 
-    let (origin, count) =
-        calculate_batch_origin_and_count(steps_amount, batch_size, batch_index, samples_amount);
+    fn set_updating_running_statistics(&mut self, should_update: bool) -> ();
 
-    assert_eq!(origin, expected_origin);
-    assert_eq!(count, expected_count);
-}
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        timestep: usize,
+    ) -> Result<(), ModelFittingError>;
+
+    fn compute_gradients(
+        &mut self,
+        training_input_samples: &Buffer<cl_float>,
+        training_expected_output_samples: &Buffer<cl_float>,
+        loss_function: &dyn LossFunction,
+    ) -> Result<Vec<Vec<Gradient>>, ModelGradientComputationError>;
+
+    fn apply_gradients(
+        &mut self,
+        gradients_per_layer: &[Vec<Gradient>],
+        optimizer: &mut dyn Optimizer<'a>,
+        timestep: usize,
+    ) -> Result<(), ModelGradientApplicationError>;
+
+    fn predict_with_buffer<'b>(
+        &'b mut self,
+        input_samples: &'b Buffer<cl_float>,
+    ) -> Result<&'b Buffer<cl_float>, ModelPredictionError>;
+
+    fn as_training_model(&mut self) -> TrainingModel<'_, 'a>;
+
+    // the parameters are synced to the host and saved into bytes so that the early stopping can
+    // restore them later on
+    fn save_parameters(&mut self) -> Result<Vec<u8>, SyncDataError>;
+
+    fn load_parameters(
+        &mut self,
+        parameters: &[u8],
+        opencl_state: &'a OpenCLState,
+    ) -> Result<(), LayerInitializationError>;
+}
+
+impl<'a> TrainableModel<'a> for Model<'a> {
+    fn get_opencl_state(&self) -> Option<&'a OpenCLState> {
+        self.opencl_state
+    }
+
+    fn has_layers(&self) -> bool {
+        !self.layers.is_empty()
+    }
+
+    fn get_inputs_amount(&self) -> usize {
+        self.layers[0].get_inputs_amount()
+    }
+
+    fn get_outputs_amount(&self) -> usize {
+        self.layers.last().unwrap().get_outputs_amount()
+    }
+
+    fn get_output_layer(&self) -> Option<&ModelLayer<'a>> {
+        self.layers.last()
+    }
+
+    fn get_last_outputs(&self) -> Option<&Buffer<cl_float>> {
+        self.layers.last()?.get_last_outputs()
+    }
+
+    fn set_training_mode(&mut self, is_training: bool) -> () {
+        Model::set_training_mode(self, is_training)
+    }
+
+    fn set_updating_running_statistics(&mut self, should_update: bool) -> () {
+        Model::set_updating_running_statistics(self, should_update)
+    }
+
+    fn optimize_parameters(
+        &mut self,
+        optimizer: &dyn Optimizer<'a>,
+        timestep: usize,
+    ) -> Result<(), ModelFittingError> {
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            if let Err(err) = layer.optimize_parameters(optimizer, i, timestep) {
+                return Err(ModelFittingError::ParameterOptimization(i, err));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compute_gradients(
+        &mut self,
+        training_input_samples: &Buffer<cl_float>,
+        training_expected_output_samples: &Buffer<cl_float>,
+        loss_function: &dyn LossFunction,
+    ) -> Result<Vec<Vec<Gradient>>, ModelGradientComputationError> {
+        Model::compute_gradients(
+            self,
+            training_input_samples,
+            training_expected_output_samples,
+            loss_function,
+        )
+    }
+
+    fn apply_gradients(
+        &mut self,
+        gradients_per_layer: &[Vec<Gradient>],
+        optimizer: &mut dyn Optimizer<'a>,
+        timestep: usize,
+    ) -> Result<(), ModelGradientApplicationError> {
+        Model::apply_gradients(self, gradients_per_layer, optimizer, timestep)
+    }
+
+    fn predict_with_buffer<'b>(
+        &'b mut self,
+        input_samples: &'b Buffer<cl_float>,
+    ) -> Result<&'b Buffer<cl_float>, ModelPredictionError> {
+        Model::predict_with_buffer(self, input_samples)
+    }
+
+    fn as_training_model(&mut self) -> TrainingModel<'_, 'a> {
+        TrainingModel::Model(self)
+    }
+
+    fn save_parameters(&mut self) -> Result<Vec<u8>, SyncDataError> {
+        self.sync_data_from_buffers_to_host()?;
+
+        let mut parameters = Vec::new();
+        save_noschema(&mut parameters, SAVED_PARAMETERS_VERSION, &self.layers)?;
+
+        Ok(parameters)
+    }
+
+    fn load_parameters(
+        &mut self,
+        mut parameters: &[u8],
+        opencl_state: &'a OpenCLState,
+    ) -> Result<(), LayerInitializationError> {
+        self.layers = load_noschema(&mut parameters, SAVED_PARAMETERS_VERSION)?;
+
+        self.init(opencl_state)
+    }
+}
+
+pub(crate) fn fit_model<'a, M: TrainableModel<'a>>(
+    model: &mut M,
+    training_input_samples: &Vec<Vec<f32>>,
+    training_expected_output_samples: &Vec<Vec<f32>>,
+    training_options: &mut TrainingOptions<'a>,
+) -> Result<TrainingResults, ModelFittingError> {
+    model.set_training_mode(true);
+
+    // the layers must go back to predicting even if something went wrong while fitting
+    let results = fit_in_training_mode(
+        model,
+        training_input_samples,
+        training_expected_output_samples,
+        training_options,
+    );
+
+    model.set_training_mode(false);
+
+    results
+}
+
+fn fit_in_training_mode<'a, M: TrainableModel<'a>>(
+    model: &mut M,
+    training_input_samples: &Vec<Vec<f32>>,
+    training_expected_output_samples: &Vec<Vec<f32>>,
+    training_options: &mut TrainingOptions<'a>,
+) -> Result<TrainingResults, ModelFittingError> {
+    if model.get_opencl_state().is_none() {
+        return Err(ModelFittingError::NotInitialized);
+    }
+
+    let state = model.get_opencl_state().unwrap();
+
+    if state.queues.is_empty() {
+        return Err(ModelFittingError::NoCommandQueue);
+    }
+
+    if !model.has_layers() {
+        return Err(ModelFittingError::ModelHasNoLayers);
+    }
+
+    assert_eq!(
+        training_input_samples.len(),
+        training_expected_output_samples.len()
+    );
+
+    let mut training_samples_amount = training_input_samples.len();
+
+    // the validation samples are sent to the device once so that they don't need to keep being
+    // borrowed from the training options while training
+    let validation_buffers = match &training_options.validation_data {
+        Some(ValidationData::Samples(validation_inputs, validation_expected_outputs)) => Some((
+            flatten_into_buffer(validation_inputs, state)?,
+            flatten_into_buffer(validation_expected_outputs, state)?,
+            validation_inputs.len(),
+        )),
+        Some(ValidationData::Split(fraction)) if training_samples_amount > 1 => {
+            let validation_samples_amount = ((training_samples_amount as f32 * fraction).round()
+                as usize)
+                .max(1)
+                .min(training_samples_amount - 1);
+            training_samples_amount -= validation_samples_amount;
+
+            Some((
+                flatten_into_buffer(&training_input_samples[training_samples_amount..], state)?,
+                flatten_into_buffer(
+                    &training_expected_output_samples[training_samples_amount..],
+                    state,
+                )?,
+                validation_samples_amount,
+            ))
+        }
+        _ => None,
+    };
+
+    let training_input_samples = &training_input_samples[..training_samples_amount];
+    let training_expected_output_samples =
+        &training_expected_output_samples[..training_samples_amount];
+
+    // when a SoftMax is followed by the Categorical Cross Entropy the loss already gives out the
+    // derivatives with respect to the inputs of the SoftMax, which are just the outputs minus
+    // the expected outputs, so the SoftMax needs to be ignored when calculating gradients at
+    // back-prop
+    if let LossFn::CategoricalCrossEntropy(loss) = training_options.loss_fn {
+        loss.set_optimized_for_softmax(matches!(
+            model.get_output_layer(),
+            Some(ModelLayer::SoftMax(_))
+        ));
+    }
+
+    training_options.loss_fn.init(state)?;
+    training_options.optimizer.init(state)?;
+
+    let inputs_amount = model.get_inputs_amount();
+    let outputs_amount = model.get_outputs_amount();
+    let samples_amount = training_input_samples.len();
+
+    let input_samples_buffer = flatten_into_buffer(training_input_samples, state)?;
+
+    let expected_output_samples_buffer =
+        flatten_into_buffer(training_expected_output_samples, state)?;
+
+    let steps_amount =
+        calculate_training_steps_amount(samples_amount, training_options.batch_size);
+
+    let mut losses: Vec<f32> = Vec::with_capacity(training_options.epochs * steps_amount);
+    let mut accuracies: Vec<f32> = Vec::with_capacity(training_options.epochs * steps_amount);
+
+    let mut validation_losses: Vec<f32> = Vec::with_capacity(training_options.epochs);
+    let mut validation_accuracies: Vec<f32> = Vec::with_capacity(training_options.epochs);
+
+    let mut per_step_inputs: Vec<Buffer<cl_float>> = separate_into_sub_buffer_batches(
+        &input_samples_buffer,
+        steps_amount,
+        samples_amount,
+        training_options.batch_size,
+        inputs_amount,
+    )?;
+
+    let mut per_step_outputs: Vec<Buffer<cl_float>> = separate_into_sub_buffer_batches(
+        &expected_output_samples_buffer,
+        steps_amount,
+        samples_amount,
+        training_options.batch_size,
+        outputs_amount,
+    )?;
+
+    let mut shuffling_rng = if training_options.shuffle {
+        Some(match training_options.shuffle_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        })
+    } else {
+        None
+    };
+    let mut samples_indices: Vec<cl_int> = (0..samples_amount as cl_int).collect();
+
+    let mut timestep: usize = 0;
+
+    // the best monitored quantity of the early stopping is kept with the sign flipped for the
+    // losses so that it is always better when it is greater
+    let mut best_monitored_quantity: Option<f32> = None;
+    let mut epochs_without_improvement: usize = 0;
+    let mut best_parameters: Option<Vec<u8>> = None;
+
+    let mut stop_requested =
+        call_callbacks(model, &mut training_options.callbacks, |callback, model| {
+            callback.on_train_begin(model)
+        });
+
+    for epoch_index in 0..training_options.epochs {
+        if stop_requested
+            || call_callbacks(model, &mut training_options.callbacks, |callback, model| {
+                callback.on_epoch_begin(epoch_index, model)
+            })
+        {
+            break;
+        }
+
+        let start = Instant::now();
+
+        if let Some(rng) = shuffling_rng.as_mut() {
+            samples_indices.shuffle(rng);
+
+            // the sub buffers keep the shuffled buffers alive until they are replaced in the
+            // next epoch
+            per_step_inputs = separate_into_sub_buffer_batches(
+                &gather_samples(&input_samples_buffer, &samples_indices, inputs_amount, state)?,
+                steps_amount,
+                samples_amount,
+                training_options.batch_size,
+                inputs_amount,
+            )?;
+            per_step_outputs = separate_into_sub_buffer_batches(
+                &gather_samples(
+                    &expected_output_samples_buffer,
+                    &samples_indices,
+                    outputs_amount,
+                    state,
+                )?,
+                steps_amount,
+                samples_amount,
+                training_options.batch_size,
+                outputs_amount,
+            )?;
+        }
+
+        let mut progress = None;
+        if training_options.verbosity.show_current_epoch {
+            println!("---------");
+            println!("epoch #{}", epoch_index + 1);
+        }
+
+        if training_options.verbosity.show_epoch_progress
+            && training_options.batch_size < samples_amount
+        {
+            let pbar = ProgressBar::new(
+                (samples_amount as f32 / training_options.batch_size as f32).ceil() as u64,
+            );
+            pbar.set_style(
+                ProgressStyle::with_template(
+                    "[{bar:10}] [{per_second}/s] {pos}/{len} {elapsed}/{eta} {msg}",
+                )
+                .expect("unable to create epoch training steps progress bar")
+                .with_key("elapsed", |state: &ProgressState, w: &mut dyn Write| {
+                    write!(w, "{}", format!("{:.2}s", state.elapsed().as_secs_f32())).unwrap()
+                })
+                .with_key("per_second", |state: &ProgressState, w: &mut dyn Write| {
+                    write!(w, "{}", format!("{:.2}", state.per_sec())).unwrap()
+                })
+                .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
+                    write!(w, "{}", format!("{:.2}s", state.eta().as_secs_f32())).unwrap()
+                })
+                .progress_chars("=> "),
+            );
+            progress = Some(pbar);
+        }
+
+        let mut epoch_losses: Vec<f32> = Vec::with_capacity(steps_amount);
+        let mut epoch_accuracies: Vec<f32> = Vec::with_capacity(steps_amount);
+
+        for i_batch in 0..steps_amount {
+            timestep += 1;
+
+            let batch_inputs = &per_step_inputs[i_batch];
+            let batch_outputs = &per_step_outputs[i_batch];
+
+            let local_batch_size;
+            if i_batch == steps_amount - 1 && samples_amount % training_options.batch_size != 0
+            {
+                local_batch_size = samples_amount % training_options.batch_size;
+            } else {
+                local_batch_size = training_options.batch_size;
+            }
+
+            let (optional_loss, optional_accuracy) = do_training_step(
+                model,
+                batch_inputs,
+                batch_outputs,
+                local_batch_size,
+                timestep,
+                training_options,
+            )?;
+
+            if let Some(loss) = optional_loss {
+                losses.push(loss);
+                epoch_losses.push(loss);
+            }
+
+            if let Some(accuracy) = optional_accuracy {
+                accuracies.push(accuracy);
+                epoch_accuracies.push(accuracy);
+            }
+
+            if progress.is_some() {
+                let pbar = progress.as_ref().unwrap();
+                pbar.inc(1);
+                if training_options.verbosity.print_loss || training_options.compute_loss {
+                    pbar.set_message(format!("(loss: {:.3})", losses.last().unwrap()));
+                }
+            }
+
+            if call_callbacks(model, &mut training_options.callbacks, |callback, model| {
+                callback.on_batch_end(
+                    epoch_index,
+                    i_batch,
+                    optional_loss,
+                    optional_accuracy,
+                    model,
+                )
+            }) {
+                stop_requested = true;
+                break;
+            }
+        }
+
+        if progress.is_some() {
+            progress.as_ref().unwrap().finish_and_clear();
+        }
+
+        if stop_requested {
+            break;
+        }
+
+        let epoch_loss = epoch_losses.iter().sum::<f32>() / steps_amount as f32;
+        let epoch_accuracy = epoch_accuracies.iter().sum::<f32>() / steps_amount as f32;
+
+        if training_options.verbosity.print_loss {
+            println!("got a loss of {} after epoch", epoch_loss);
+        }
+
+        if training_options.verbosity.print_accuracy {
+            println!(
+                "got a accuracy of {:.3}% after epoch",
+                epoch_accuracy * 100.0
+            );
+        }
+
+        let mut epoch_validation_loss = None;
+        let mut epoch_validation_accuracy = None;
+
+        if let Some((validation_inputs, validation_expected_outputs, validation_samples_amount)) =
+            &validation_buffers
+        {
+            // the validation samples are just forward passed, so layers such as the Dropout
+            // need to behave as if the Model was predicting
+            model.set_training_mode(false);
+
+            let validation_metrics = compute_metrics_in_batches(
+                model,
+                validation_inputs,
+                validation_expected_outputs,
+                *validation_samples_amount,
+                training_options.batch_size,
+                training_options.loss_fn,
+                training_options.compute_loss,
+                training_options.compute_accuracy,
+            );
+
+            model.set_training_mode(true);
+
+            let (optional_validation_loss, optional_validation_accuracy) = validation_metrics?;
+
+            epoch_validation_loss = optional_validation_loss;
+            epoch_validation_accuracy = optional_validation_accuracy;
+
+            if let Some(validation_loss) = optional_validation_loss {
+                validation_losses.push(validation_loss);
+
+                if training_options.verbosity.print_loss {
+                    println!("got a validation loss of {} after epoch", validation_loss);
+                }
+            }
+
+            if let Some(validation_accuracy) = optional_validation_accuracy {
+                validation_accuracies.push(validation_accuracy);
+
+                if training_options.verbosity.print_accuracy {
+                    println!(
+                        "got a validation accuracy of {:.3}% after epoch",
+                        validation_accuracy * 100.0
+                    );
+                }
+            }
+        }
+
+        if training_options.verbosity.show_epoch_elapsed {
+            println!("{:.3}s elapsed on epoch", start.elapsed().as_secs_f32());
+        }
+
+        let epoch_results = EpochResults {
+            loss: (!epoch_losses.is_empty()).then(|| epoch_loss),
+            accuracy: (!epoch_accuracies.is_empty()).then(|| epoch_accuracy),
+            validation_loss: epoch_validation_loss,
+            validation_accuracy: epoch_validation_accuracy,
+        };
+
+        if call_callbacks(model, &mut training_options.callbacks, |callback, model| {
+            callback.on_epoch_end(epoch_index, &epoch_results, model)
+        }) {
+            break;
+        }
+
+        if let Some(halting_condition) = &training_options.halting_condition {
+            match halting_condition {
+                HaltingCondition::MinLossReached(min_loss) => {
+                    if losses.is_empty() {
+                        return Err(ModelFittingError::NoLossForHaltingCondition);
+                    }
+
+                    if min_loss >= &epoch_loss {
+                        if training_options.verbosity.halting_condition_warning {
+                            println!("stopping training process due to MinLossReached halting condition...");
+                        }
+
+                        break;
+                    }
+                }
+                HaltingCondition::MinAccuracyReached(min_acc) => {
+                    if accuracies.is_empty() {
+                        return Err(ModelFittingError::NoAccuracyForHaltingCondition);
+                    }
+
+                    if min_acc <= &epoch_accuracy {
+                        if training_options.verbosity.halting_condition_warning {
+                            println!("stopping training process due to MinAccuracyReached halting condition...");
+                        }
+
+                        break;
+                    }
+                }
+                HaltingCondition::EarlyStopping {
+                    monitor,
+                    patience,
+                    min_delta,
+                    restore_best_parameters,
+                } => {
+                    let is_validation_quantity = matches!(
+                        monitor,
+                        MonitoredQuantity::ValidationLoss | MonitoredQuantity::ValidationAccuracy
+                    );
+
+                    if is_validation_quantity && validation_buffers.is_none() {
+                        return Err(ModelFittingError::NoValidationDataForHaltingCondition);
+                    }
+
+                    let monitored_quantity = match monitor {
+                        MonitoredQuantity::Loss => epoch_results.loss.map(|loss| -loss),
+                        MonitoredQuantity::Accuracy => epoch_results.accuracy,
+                        MonitoredQuantity::ValidationLoss => {
+                            epoch_results.validation_loss.map(|loss| -loss)
+                        }
+                        MonitoredQuantity::ValidationAccuracy => epoch_results.validation_accuracy,
+                    };
+
+                    let monitored_quantity = match (monitor, monitored_quantity) {
+                        (_, Some(quantity)) => quantity,
+                        (MonitoredQuantity::Loss | MonitoredQuantity::ValidationLoss, None) => {
+                            return Err(ModelFittingError::NoLossForHaltingCondition);
+                        }
+                        (_, None) => return Err(ModelFittingError::NoAccuracyForHaltingCondition),
+                    };
+
+                    let has_improved = best_monitored_quantity
+                        .map_or(true, |best| monitored_quantity - best > *min_delta);
+
+                    if has_improved {
+                        best_monitored_quantity = Some(monitored_quantity);
+                        epochs_without_improvement = 0;
+
+                        if *restore_best_parameters {
+                            best_parameters = Some(model.save_parameters()?);
+                        }
+                    } else {
+                        epochs_without_improvement += 1;
+
+                        if epochs_without_improvement >= *patience {
+                            if training_options.verbosity.halting_condition_warning {
+                                println!("stopping training process due to EarlyStopping halting condition...");
+                            }
+
+                            break;
+                        }
+                    }
+                }
+            };
+        }
+    }
+
+    // the parameters only need to be restored if there were worse epochs after the best one
+    if epochs_without_improvement > 0 {
+        if let Some(best_parameters) = &best_parameters {
+            model.load_parameters(best_parameters, state)?;
+        }
+    }
+
+    let results = TrainingResults {
+        loss_per_training_steps: losses,
+        accuracy_per_training_steps: accuracies,
+        validation_loss_per_epoch: validation_losses,
+        validation_accuracy_per_epoch: validation_accuracies,
+    };
+
+    call_callbacks(model, &mut training_options.callbacks, |callback, model| {
+        callback.on_train_end(&results, model);
+
+        CallbackAction::Continue
+    });
+
+    Ok(results)
+}
+
+// calls a hook of all of the callbacks, even if one of them requests for the training to stop,
+// and returns weather or not any of them did
+fn call_callbacks<'a, M: TrainableModel<'a>>(
+    model: &mut M,
+    callbacks: &mut [&'a mut dyn TrainingCallback<'a>],
+    mut hook: impl FnMut(&mut dyn TrainingCallback<'a>, TrainingModel<'_, 'a>) -> CallbackAction,
+) -> bool {
+    let mut stop_requested = false;
+
+    for callback in callbacks.iter_mut() {
+        if hook(&mut **callback, model.as_training_model()) == CallbackAction::StopTraining {
+            stop_requested = true;
+        }
+    }
+
+    stop_requested
+}
+
+fn do_training_step<'a, M: TrainableModel<'a>>(
+    model: &mut M,
+    input_samples: &Buffer<cl_float>,
+    expected_output_samples: &Buffer<cl_float>,
+    samples_amount: usize,
+    timestep: usize,
+    training_options: &mut TrainingOptions<'a>,
+) -> Result<(Option<f32>, Option<f32>), ModelFittingError> {
+    if model.get_opencl_state().is_none() {
+        return Err(ModelFittingError::NotInitialized);
+    }
+
+    let state = model.get_opencl_state().unwrap();
+
+    if state.queues.is_empty() {
+        return Err(ModelFittingError::NoCommandQueue);
+    }
+
+    if !model.has_layers() {
+        return Err(ModelFittingError::NoLayers);
+    }
+
+    model.optimize_parameters(training_options.optimizer, timestep)?;
+
+    let gradients = model.compute_gradients(
+        &input_samples,
+        &expected_output_samples,
+        training_options.loss_fn,
+    )?;
+
+    model.apply_gradients(gradients.as_slice(), training_options.optimizer, timestep)?;
+
+    // the batch was already forward passed for computing the gradients, so the statistics of
+    // layers such as the BatchNorm must not be updated again by the forward pass of the metrics
+    model.set_updating_running_statistics(false);
+
+    let metrics = compute_metrics(
+        model,
+        input_samples,
+        expected_output_samples,
+        samples_amount,
+        training_options.loss_fn,
+        training_options.compute_loss,
+        training_options.compute_accuracy,
+    );
+
+    model.set_updating_running_statistics(true);
+
+    Ok(metrics?)
+}
+
+fn compute_metrics<'a, M: TrainableModel<'a>>(
+    model: &mut M,
+    input_samples: &Buffer<cl_float>,
+    expected_output_samples: &Buffer<cl_float>,
+    samples_amount: usize,
+    loss_fn: &dyn LossFunction,
+    compute_loss: bool,
+    compute_accuracy: bool,
+) -> Result<(Option<f32>, Option<f32>), ModelEvaluationError> {
+    if model.get_opencl_state().is_none() {
+        return Err(ModelEvaluationError::NotInitialized);
+    }
+
+    let state = model.get_opencl_state().unwrap();
+
+    if state.queues.is_empty() {
+        return Err(ModelEvaluationError::NoCommandQueue);
+    }
+
+    let queue = &state.queues[0];
+
+    let loss;
+    let accuracy;
+
+    if compute_loss || compute_accuracy {
+        model.predict_with_buffer(input_samples)?;
+    }
+
+    if compute_loss {
+        let actual_outputs = model.get_last_outputs().unwrap();
+
+        loss = Some(loss_fn.compute_loss(
+            actual_outputs,
+            &expected_output_samples,
+            samples_amount,
+        )?);
+    } else {
+        loss = None;
+    }
+
+    if compute_accuracy {
+        let actual_outputs = model.get_last_outputs().unwrap();
+
+        let program = state.get_prgm(MODEL_PROGRAM_NAME)?;
+        let accuracy_kernel = program.get_krnl(COMPUTE_ACCURACIES_KERNEL_NAME)?;
+
+        let outputs_total_count = actual_outputs.size()? / mem::size_of::<cl_float>();
+
+        let accuracies = empty_buffer(outputs_total_count, CL_MEM_READ_WRITE, state)?;
+
+        ExecuteKernel::new(accuracy_kernel)
+            .set_arg(actual_outputs)
+            .set_arg(expected_output_samples)
+            .set_arg(&accuracies)
+            .set_arg(&(outputs_total_count as cl_int))
+            .set_global_work_size(outputs_total_count)
+            .enqueue_nd_range(queue)?;
+
+        queue.finish()?;
+
+        accuracy = Some(accuracies.sum(state)? / outputs_total_count as f32);
+    } else {
+        accuracy = None;
+    }
+
+    Ok((loss, accuracy))
+}
+
+// forward passes the samples in batches so that there is never more than a batch of samples
+// going through the Model at once, and averages the metrics of the batches weighted by their
+// amount of samples
+fn compute_metrics_in_batches<'a, M: TrainableModel<'a>>(
+    model: &mut M,
+    input_samples: &Buffer<cl_float>,
+    expected_output_samples: &Buffer<cl_float>,
+    samples_amount: usize,
+    batch_size: usize,
+    loss_fn: &dyn LossFunction,
+    compute_loss: bool,
+    compute_accuracy: bool,
+) -> Result<(Option<f32>, Option<f32>), ModelEvaluationError> {
+    let steps_amount = calculate_training_steps_amount(samples_amount, batch_size);
+
+    let per_step_inputs = separate_into_sub_buffer_batches(
+        input_samples,
+        steps_amount,
+        samples_amount,
+        batch_size,
+        model.get_inputs_amount(),
+    )?;
+    let per_step_outputs = separate_into_sub_buffer_batches(
+        expected_output_samples,
+        steps_amount,
+        samples_amount,
+        batch_size,
+        model.get_outputs_amount(),
+    )?;
+
+    let mut loss_sum = 0.0;
+    let mut accuracy_sum = 0.0;
+
+    for i_batch in 0..steps_amount {
+        let (_, local_batch_size) =
+            calculate_batch_origin_and_count(steps_amount, batch_size, i_batch, samples_amount);
+
+        let (optional_loss, optional_accuracy) = compute_metrics(
+            model,
+            &per_step_inputs[i_batch],
+            &per_step_outputs[i_batch],
+            local_batch_size,
+            loss_fn,
+            compute_loss,
+            compute_accuracy,
+        )?;
+
+        loss_sum += optional_loss.unwrap_or_default() * local_batch_size as f32;
+        accuracy_sum += optional_accuracy.unwrap_or_default() * local_batch_size as f32;
+    }
+
+    Ok((
+        compute_loss.then(|| loss_sum / samples_amount as f32),
+        compute_accuracy.then(|| accuracy_sum / samples_amount as f32),
+    ))
+}
+
+// rearranges the samples on the device so that the sample at each position is the one at the
+// index in that same position of the indices, without needing to upload the samples again
+fn gather_samples(
+    samples: &Buffer<cl_float>,
+    indices: &[cl_int],
+    features_amount: usize,
+    opencl_state: &OpenCLState,
+) -> Result<Buffer<cl_float>, ModelFittingError> {
+    if opencl_state.queues.is_empty() {
+        return Err(ModelFittingError::NoCommandQueue);
+    }
+
+    let queue = &opencl_state.queues[0];
+
+    let samples_amount = indices.len();
+
+    let mut indices_buffer = Buffer::<cl_int>::create(
+        &opencl_state.context,
+        CL_MEM_READ_ONLY,
+        samples_amount,
+        ptr::null_mut(),
+    )?;
+    queue
+        .enqueue_write_buffer(&mut indices_buffer, CL_NON_BLOCKING, 0, indices, &[])?
+        .wait()?;
+
+    let gathered_samples = empty_buffer(
+        samples_amount * features_amount,
+        CL_MEM_READ_WRITE,
+        opencl_state,
+    )?;
+
+    let program = opencl_state.get_prgm(MODEL_PROGRAM_NAME)?;
+    let gather_kernel = program.get_krnl(GATHER_SAMPLES_KERNEL_NAME)?;
+
+    ExecuteKernel::new(gather_kernel)
+        .set_arg(samples)
+        .set_arg(&indices_buffer)
+        .set_arg(&gathered_samples)
+        .set_arg(&(features_amount as cl_int))
+        .set_arg(&(samples_amount as cl_int))
+        .set_global_work_sizes(&[samples_amount, features_amount])
+        .enqueue_nd_range(queue)?;
+
+    queue.finish()?;
+
+    Ok(gathered_samples)
+}
+
+fn flatten_into_buffer(
+    samples: &[Vec<f32>],
+    opencl_state: &OpenCLState,
+) -> Result<Buffer<cl_float>, BufferConversionError> {
+    samples
+        .par_iter()
+        .flatten()
+        .map(|x| *x)
+        .collect::<Vec<f32>>()
+        .to_buffer(false, opencl_state)
+}
+
+fn calculate_training_steps_amount(samples_amount: usize, batch_size: usize) -> usize {
+    (samples_amount as f32 / batch_size as f32).ceil() as usize
+}
+
+// the shapes can only be compared when both of the layers have one, otherwise only the amounts are
+pub(crate) fn shapes_match(
+    outputs_shape: Option<Vec<usize>>,
+    inputs_shape: Option<Vec<usize>>,
+) -> bool {
+    match (outputs_shape, inputs_shape) {
+        (Some(outputs_shape), Some(inputs_shape)) => outputs_shape == inputs_shape,
+        _ => true,
+    }
+}
+
+#[test]
+fn should_calculate_training_steps_amount_correctly() {
+    let samples_amount = 25;
+    let batch_size = 4;
+
+    let correct_training_steps = 7;
+    let actual_training_steps = calculate_training_steps_amount(samples_amount, batch_size);
+
+    assert_eq!(correct_training_steps, actual_training_steps);
+}
+
+fn calculate_batch_origin_and_count(
+    steps_amount: usize,
+    batch_size: usize,
+    batch_index: usize,
+    samples_amount: usize, //   origin, count
+) -> (usize, usize) {
+    let (origin, count);
+    if batch_index == steps_amount - 1 && samples_amount % batch_size != 0 {
+        count = samples_amount % batch_size;
+        origin = samples_amount - count;
+    } else {
+        count = batch_size;
+        origin = batch_index * count;
+    }
+
+    (origin, count)
+}
+
+#[test]
+fn should_calculate_batch_origin_and_count_correctly_for_normal_batches() {
+    let samples_amount = 6123;
+    let batch_size = 25;
+
+    let steps_amount = 245;
+    let batch_index = 123;
+
+    let expected_origin = batch_size * batch_index;
+    let expected_count = batch_size;
+
+    let (origin, count) =
+        calculate_batch_origin_and_count(steps_amount, batch_size, batch_index, samples_amount);
+
+    assert_eq!(origin, expected_origin);
+    assert_eq!(count, expected_count);
+}
 
 #[test]
 fn should_calculate_batch_origin_and_count_correctly_for_the_last_uneven_batch() {
@@ -1003,3 +1800,448 @@ fn separate_into_sub_buffer_batches(
 
     Ok(per_step_feature)
 }
+
+#[test]
+fn should_compute_the_same_gradients_when_optimizing_for_softmax() {
+    use crate::{
+        layers::{activations::SoftMax, Dense},
+        loss_functions::CategoricalCrossEntropy,
+        utils::{approx_eq::assert_approx_equal_distance, opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut model = Model::new(vec![Dense::new(3, 4), SoftMax::new(4)]);
+    model.init(&state).unwrap();
+
+    let input_samples = vec![0.3, -0.5, 0.8, 0.1, 0.9, -0.4]
+        .to_buffer(false, &state)
+        .unwrap();
+    let expected_output_samples = vec![0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+        .to_buffer(false, &state)
+        .unwrap();
+
+    let mut loss = CategoricalCrossEntropy::new_raw();
+    loss.init(&state).unwrap();
+
+    let full_gradients = model
+        .compute_gradients(&input_samples, &expected_output_samples, &loss)
+        .unwrap();
+
+    loss.set_optimized_for_softmax(true);
+
+    let optimized_gradients = model
+        .compute_gradients(&input_samples, &expected_output_samples, &loss)
+        .unwrap();
+
+    assert_eq!(optimized_gradients.len(), 2);
+    assert!(optimized_gradients[0].is_empty());
+
+    for (full_gradient, optimized_gradient) in
+        full_gradients[1].iter().zip(optimized_gradients[1].iter())
+    {
+        assert_approx_equal_distance(
+            &Vec::<f32>::from_buffer(&optimized_gradient.value, false, &state).unwrap(),
+            &Vec::<f32>::from_buffer(&full_gradient.value, false, &state).unwrap(),
+            0.01,
+        );
+    }
+}
+
+#[test]
+fn should_not_initialize_when_the_layers_shapes_dont_match() {
+    use crate::{
+        layers::{Conv2D, Dense, Flatten},
+        utils::{opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut model = Model::new(vec![
+        Conv2D::new((28, 28), 1, (3, 3), 2),
+        Flatten::new(vec![1, 26, 26]),
+        Dense::new(26 * 26, 10),
+    ]);
+
+    assert!(matches!(
+        model.init(&state),
+        Err(LayerInitializationError::LayersShapesDontMatch(0))
+    ));
+}
+
+#[test]
+fn should_not_initialize_when_the_flattened_shape_is_in_another_order() {
+    use crate::{
+        layers::{Conv2D, Dense, Flatten},
+        utils::{opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    // the amounts are the same but the Conv2D gives out (filters, height, width)
+    let mut model = Model::new(vec![
+        Conv2D::new((28, 28), 1, (3, 3), 2),
+        Flatten::new(vec![26, 26, 2]),
+        Dense::new(26 * 26 * 2, 10),
+    ]);
+
+    assert!(matches!(
+        model.init(&state),
+        Err(LayerInitializationError::LayersShapesDontMatch(0))
+    ));
+
+    let mut model = Model::new(vec![
+        Conv2D::new((28, 28), 1, (3, 3), 2),
+        Flatten::new(vec![2, 26, 26]),
+        Dense::new(26 * 26 * 2, 10),
+    ]);
+
+    assert!(model.init(&state).is_ok());
+}
+
+#[test]
+fn should_compute_the_validation_loss_after_each_epoch() {
+    use crate::{
+        layers::Dense,
+        loss_functions::MeanSquared,
+        optimizers::Basic,
+        utils::{opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut model = Model::new(vec![Dense::new(2, 1)]);
+    model.init(&state).unwrap();
+
+    let input_samples = vec![
+        vec![0.0, 0.0],
+        vec![0.0, 1.0],
+        vec![1.0, 0.0],
+        vec![1.0, 1.0],
+    ];
+    let expected_output_samples = vec![vec![0.0], vec![1.0], vec![1.0], vec![2.0]];
+
+    let mut loss = MeanSquared::new();
+    let mut optimizer = Basic::new(0.1);
+
+    let training_results = model
+        .fit(
+            &input_samples,
+            &expected_output_samples,
+            &mut TrainingOptions::new(&mut loss, &mut optimizer)
+                .set_batch_size(3)
+                .set_epochs(5)
+                .set_validation_split(0.25)
+                .unwrap(),
+        )
+        .unwrap();
+
+    // only the first three samples are trained on, in one step per epoch
+    assert_eq!(training_results.loss_per_training_steps.len(), 5);
+    assert_eq!(training_results.validation_loss_per_epoch.len(), 5);
+    assert!(training_results.validation_accuracy_per_epoch.is_empty());
+
+    let prediction = model.predict(&vec![vec![1.0, 1.0]]).unwrap();
+    let prediction = Vec::<f32>::from_buffer(prediction, false, &state).unwrap();
+
+    assert!(
+        (training_results.validation_loss_per_epoch[4] - (prediction[0] - 2.0).powi(2)).abs()
+            <= 0.0001
+    );
+}
+
+#[test]
+fn should_evaluate_the_loss_and_metrics_over_uneven_batches() {
+    use crate::{
+        layers::Dense,
+        loss_functions::MeanSquared,
+        utils::{opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut dense = Dense::new_raw(2, 1);
+    dense.weights = vec![vec![1.0], vec![2.0]];
+    dense.biases = vec![0.5];
+
+    let mut model = Model::new(vec![dense.into()]);
+    model.init(&state).unwrap();
+
+    let input_samples = vec![
+        vec![0.0, 0.0],
+        vec![0.0, 1.0],
+        vec![1.0, 0.0],
+        vec![1.0, 1.0],
+    ];
+    let expected_output_samples = vec![vec![0.0], vec![2.5], vec![2.5], vec![3.5]];
+
+    let results = model
+        .evaluate(
+            &input_samples,
+            &expected_output_samples,
+            &mut MeanSquared::new(),
+            &[Metric::Accuracy],
+            3,
+        )
+        .unwrap();
+
+    assert!((results.loss - 0.3125).abs() <= 0.0001);
+    assert_eq!(results.metrics.len(), 1);
+    assert_eq!(results.metrics[0].0, Metric::Accuracy);
+    assert!((results.metrics[0].1 - 0.65).abs() <= 0.0001);
+}
+
+#[test]
+fn should_not_evaluate_without_samples_or_with_a_zero_batch_size() {
+    use crate::{
+        layers::Dense,
+        loss_functions::MeanSquared,
+        utils::{opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut model = Model::new(vec![Dense::new(2, 1)]);
+    model.init(&state).unwrap();
+
+    let input_samples = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+    let expected_output_samples = vec![vec![1.0], vec![0.0]];
+
+    assert!(matches!(
+        model.evaluate(&Vec::new(), &Vec::new(), &mut MeanSquared::new(), &[], 2),
+        Err(ModelEvaluationError::NoSamples)
+    ));
+    assert!(matches!(
+        model.evaluate(
+            &input_samples,
+            &expected_output_samples,
+            &mut MeanSquared::new(),
+            &[],
+            0
+        ),
+        Err(ModelEvaluationError::ZeroBatchSize)
+    ));
+    assert!(matches!(
+        model.evaluate(
+            &input_samples,
+            &expected_output_samples[..1].to_vec(),
+            &mut MeanSquared::new(),
+            &[],
+            2
+        ),
+        Err(ModelEvaluationError::SamplesAmountsDontMatch)
+    ));
+}
+
+#[test]
+fn should_gather_the_samples_in_the_order_of_the_indices() {
+    use crate::utils::{opencl::DeviceType, setup_opencl};
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        .to_buffer(false, &state)
+        .unwrap();
+
+    let gathered_samples = gather_samples(&samples, &[2, 0, 1], 2, &state).unwrap();
+
+    assert_eq!(
+        Vec::<f32>::from_buffer(&gathered_samples, false, &state).unwrap(),
+        vec![5.0, 6.0, 1.0, 2.0, 3.0, 4.0]
+    );
+}
+
+#[test]
+fn should_stop_training_when_a_callback_requests_it() {
+    use crate::{
+        callbacks::{CallbackAction, EpochResults, TrainingCallback, TrainingModel},
+        layers::Dense,
+        loss_functions::MeanSquared,
+        optimizers::Basic,
+        utils::{opencl::DeviceType, setup_opencl},
+    };
+
+    #[derive(Debug, Default)]
+    struct StopAfterSecondEpoch {
+        batches_amount: usize,
+    }
+
+    impl<'a> TrainingCallback<'a> for StopAfterSecondEpoch {
+        fn on_batch_end(
+            &mut self,
+            _epoch_index: usize,
+            _batch_index: usize,
+            loss: Option<f32>,
+            _accuracy: Option<f32>,
+            _model: TrainingModel<'_, 'a>,
+        ) -> CallbackAction {
+            assert!(loss.is_some());
+
+            self.batches_amount += 1;
+
+            CallbackAction::Continue
+        }
+
+        fn on_epoch_end(
+            &mut self,
+            epoch_index: usize,
+            results: &EpochResults,
+            model: TrainingModel<'_, 'a>,
+        ) -> CallbackAction {
+            assert!(matches!(model, TrainingModel::Model(_)));
+            assert!(results.loss.is_some());
+            assert!(results.validation_loss.is_none());
+
+            if epoch_index == 1 {
+                CallbackAction::StopTraining
+            } else {
+                CallbackAction::Continue
+            }
+        }
+
+        fn on_train_end(&mut self, results: &TrainingResults, _model: TrainingModel<'_, 'a>) {
+            assert_eq!(self.batches_amount, results.loss_per_training_steps.len());
+        }
+    }
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut model = Model::new(vec![Dense::new(2, 1)]);
+    model.init(&state).unwrap();
+
+    let input_samples = vec![
+        vec![0.0, 0.0],
+        vec![0.0, 1.0],
+        vec![1.0, 0.0],
+        vec![1.0, 1.0],
+    ];
+    let expected_output_samples = vec![vec![0.0], vec![1.0], vec![1.0], vec![2.0]];
+
+    let mut loss = MeanSquared::new();
+    let mut optimizer = Basic::new(0.1);
+    let mut callback = StopAfterSecondEpoch::default();
+
+    let training_results = model
+        .fit(
+            &input_samples,
+            &expected_output_samples,
+            &mut TrainingOptions::new(&mut loss, &mut optimizer)
+                .set_batch_size(2)
+                .set_epochs(5)
+                .add_callback(&mut callback),
+        )
+        .unwrap();
+
+    // two epochs of two training steps each
+    assert_eq!(training_results.loss_per_training_steps.len(), 4);
+}
+
+#[test]
+fn should_stop_early_and_restore_the_best_parameters() {
+    use crate::{
+        layers::Dense,
+        loss_functions::MeanSquared,
+        optimizers::Basic,
+        utils::{opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut dense = Dense::new_raw(2, 1);
+    dense.weights = vec![vec![1.0], vec![2.0]];
+    dense.biases = vec![0.5];
+
+    let mut model = Model::new(vec![dense.into()]);
+    model.init(&state).unwrap();
+
+    let input_samples = vec![
+        vec![0.0, 0.0],
+        vec![0.0, 1.0],
+        vec![1.0, 0.0],
+        vec![1.0, 1.0],
+    ];
+    let expected_output_samples = vec![vec![0.0], vec![1.0], vec![1.0], vec![2.0]];
+
+    let mut loss = MeanSquared::new();
+    let mut optimizer = Basic::new(0.1);
+
+    // the min delta is so big that only the first epoch counts as an improvement
+    let training_results = model
+        .fit(
+            &input_samples,
+            &expected_output_samples,
+            &mut TrainingOptions::new(&mut loss, &mut optimizer)
+                .set_batch_size(4)
+                .set_epochs(10)
+                .set_halting_condition(HaltingCondition::EarlyStopping {
+                    monitor: MonitoredQuantity::Loss,
+                    patience: 2,
+                    min_delta: 1000.0,
+                    restore_best_parameters: true,
+                })
+                .unwrap(),
+        )
+        .unwrap();
+
+    assert_eq!(training_results.loss_per_training_steps.len(), 3);
+
+    let restored_loss = model
+        .evaluate(
+            &input_samples,
+            &expected_output_samples,
+            &mut MeanSquared::new(),
+            &[],
+            4,
+        )
+        .unwrap()
+        .loss;
+
+    assert!((restored_loss - training_results.loss_per_training_steps[0]).abs() <= 0.0001);
+}
+
+#[test]
+fn should_update_the_running_statistics_once_per_training_step() {
+    use crate::{
+        layers::BatchNorm,
+        loss_functions::MeanSquared,
+        optimizers::Basic,
+        utils::{approx_eq::assert_approx_equal_distance, opencl::DeviceType, setup_opencl},
+    };
+
+    let state = setup_opencl(DeviceType::GPU).unwrap();
+
+    let mut model = Model::new(vec![BatchNorm::new_raw(2).set_momentum(0.5).into()]);
+    model.init(&state).unwrap();
+
+    let input_samples = vec![
+        vec![1.0, 2.0],
+        vec![3.0, 4.0],
+        vec![5.0, 6.0],
+        vec![7.0, 8.0],
+    ];
+    let expected_output_samples = vec![vec![0.0, 0.0]; 4];
+
+    let mut loss = MeanSquared::new();
+    let mut optimizer = Basic::new(0.1);
+
+    model
+        .fit(
+            &input_samples,
+            &expected_output_samples,
+            &mut TrainingOptions::new(&mut loss, &mut optimizer)
+                .set_batch_size(4)
+                .set_epochs(1),
+        )
+        .unwrap();
+
+    model.sync_data_from_buffers_to_host().unwrap();
+
+    // the batch has a mean of (4, 5) and a variance of 5 for both inputs, which are mixed in
+    // just once with the initial running mean of 0 and running variance of 1
+    if let ModelLayer::BatchNorm(layer) = &model.layers[0] {
+        assert_approx_equal_distance(&layer.running_mean, &vec![2.0, 2.5], 0.01);
+        assert_approx_equal_distance(&layer.running_variance, &vec![3.0, 3.0], 0.01);
+    } else {
+        panic!("the first layer of the Model should be a BatchNorm");
+    }
+}
@@ -67,6 +67,10 @@ fn should_decrease_error() -> () {
                 optimizer: &mut optimizer,
                 batch_size: 4,
                 epochs: 10000,
+                validation_data: None,
+                shuffle: false,
+                shuffle_seed: None,
+                callbacks: Vec::new(),
             },
         )
         .unwrap();
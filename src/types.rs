@@ -1,15 +1,22 @@
 //! A module containing internal data types for Intricate
 
 use opencl3::error_codes::ClError;
+use savefile::SavefileError;
 use savefile_derive::Savefile;
 
 use intricate_macros::{EnumLayer, FromForAllUnnamedVariants};
 
 use crate::{
     layers::{
-        activations::{ReLU, Sigmoid, SoftMax, TanH},
-        Dense, conv2d::Conv2D,
+        activations::{
+            HardSigmoid, LeakyReLU, PReLU, ReLU, Sigmoid, SoftMax, Softplus, Swish, TanH, ELU, GELU,
+        },
+        BatchNorm, Conv1D, Conv2DTranspose, Dense, Dropout, Embedding, Flatten, GaussianNoise, GroupNorm, LayerNorm, MultiHeadAttention,
+        Reshape, Sampling, SeparableConv2D, TimeDistributed, Upsampling2D, conv2d::Conv2D,
+        recurrent::{GRU, LSTM, SimpleRNN},
+        pooling::{AvgPool2D, GlobalAveragePool, MaxPool2D},
     },
+    callbacks::TrainingCallback,
     loss_functions::LossFn,
     optimizers::Optimizer, utils::opencl::BufferConversionError,
 };
@@ -37,6 +44,8 @@ pub enum SyncDataError {
     BufferConversion(BufferConversionError),
     /// Happens when there is no command queue to be used.
     NoCommandQueue,
+    /// Happens when a layer that is inside of another layer can't be saved.
+    Savefile(SavefileError),
 }
 
 impl From<String> for ProgramNotFoundError {
@@ -62,12 +71,43 @@ impl From<String> for KernelNotFoundError {
 #[allow(missing_docs)]
 pub enum ModelLayer<'a> {
     Dense(Dense<'a>),
+    Embedding(Embedding<'a>),
+    Conv1D(Conv1D<'a>),
     Conv2D(Conv2D<'a>),
+    Conv2DTranspose(Conv2DTranspose<'a>),
+    SeparableConv2D(SeparableConv2D<'a>),
+    Upsampling2D(Upsampling2D<'a>),
+
+    MaxPool2D(MaxPool2D<'a>),
+    AvgPool2D(AvgPool2D<'a>),
+    GlobalAveragePool(GlobalAveragePool<'a>),
+
+    Flatten(Flatten<'a>),
+    Reshape(Reshape<'a>),
+    TimeDistributed(TimeDistributed<'a>),
+
+    Dropout(Dropout<'a>),
+    GaussianNoise(GaussianNoise<'a>),
+    Sampling(Sampling<'a>),
+    BatchNorm(BatchNorm<'a>),
+    LayerNorm(LayerNorm<'a>),
+    GroupNorm(GroupNorm<'a>),
+    SimpleRNN(SimpleRNN<'a>),
+    LSTM(LSTM<'a>),
+    GRU(GRU<'a>),
+    MultiHeadAttention(MultiHeadAttention<'a>),
 
     TanH(TanH<'a>),
     SoftMax(SoftMax<'a>),
     ReLU(ReLU<'a>),
     Sigmoid(Sigmoid<'a>),
+    LeakyReLU(LeakyReLU<'a>),
+    ELU(ELU<'a>),
+    GELU(GELU<'a>),
+    Swish(Swish<'a>),
+    Softplus(Softplus<'a>),
+    HardSigmoid(HardSigmoid<'a>),
+    PReLU(PReLU<'a>),
 }
 
 #[derive(Debug)]
@@ -121,6 +161,51 @@ pub enum HaltingCondition {
     ///
     /// To use this you need to set `compute_accuracy` to true.
     MinAccuracyReached(f32),
+
+    /// Will stop the training process if the monitored quantity has not improved by more than
+    /// `min_delta` for `patience` epochs in a row.
+    ///
+    /// To use this you need to compute the loss or the accuracy, depending on what is monitored,
+    /// and to have validation data if a validation quantity is monitored.
+    EarlyStopping {
+        /// The quantity that is checked for improvements after each epoch
+        monitor: MonitoredQuantity,
+        /// The amount of epochs in a row without improvements after which the training stops
+        patience: usize,
+        /// How much the monitored quantity must improve by to count as an improvement
+        min_delta: f32,
+        /// Weather or not the parameters the Model had at the epoch with the best monitored
+        /// quantity should be restored once the training process is over
+        restore_best_parameters: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A quantity computed after each epoch of training that can be monitored by a
+/// `HaltingCondition::EarlyStopping`, the losses improve by going down while the accuracies
+/// improve by going up.
+pub enum MonitoredQuantity {
+    /// The average loss of the training steps of the epoch
+    Loss,
+    /// The average accuracy of the training steps of the epoch
+    Accuracy,
+    /// The loss on the validation data
+    ValidationLoss,
+    /// The accuracy on the validation data
+    ValidationAccuracy,
+}
+
+#[derive(Debug)]
+/// The samples a Model is evaluated on after each epoch of training, with just a forward pass,
+/// to keep track of how well it does on samples it was not trained on.
+pub enum ValidationData {
+    /// Samples that are separate from the training samples, the first Vec being the input samples
+    /// and the second one the expected output samples.
+    Samples(Vec<Vec<f32>>, Vec<Vec<f32>>),
+
+    /// A fraction, between 0 and 1, of the training samples that will not be trained on and will
+    /// be used for validation instead, which are taken from the end of the training samples.
+    Split(f32),
 }
 
 #[derive(Debug)]
@@ -179,6 +264,20 @@ pub struct TrainingOptions<'a> {
 
     /// The amount of epochs that the Model should train for.
     pub(crate) epochs: usize,
+
+    /// The samples the Model will be evaluated on after each epoch, if any.
+    pub(crate) validation_data: Option<ValidationData>,
+
+    /// Weather or not the training samples should be shuffled before each epoch so that the
+    /// batches are not always the same.
+    pub(crate) shuffle: bool,
+
+    /// The seed used for shuffling the training samples, if there is no seed the samples will be
+    /// shuffled differently every time the Model is fitted.
+    pub(crate) shuffle_seed: Option<u64>,
+
+    /// The callbacks that are called throughout the training process.
+    pub(crate) callbacks: Vec<&'a mut dyn TrainingCallback<'a>>,
 }
 
 #[derive(Debug)]
@@ -207,7 +306,11 @@ impl<'a> TrainingOptions<'a> {
             halting_condition: None, 
             compute_loss: true,
             compute_accuracy: false, 
-            epochs: 0
+            epochs: 0,
+            validation_data: None,
+            shuffle: false,
+            shuffle_seed: None,
+            callbacks: Vec::new(),
         }
     }
 
@@ -242,6 +345,36 @@ impl<'a> TrainingOptions<'a> {
                     });
                 }
             }
+            HaltingCondition::EarlyStopping { monitor, patience, min_delta, .. } => {
+                if patience == 0 || min_delta < 0.0 {
+                    return Err(InvalidTrainingOptionError { 
+                        value_trying_to_be_set: halting_condition, 
+                        parameter_name: "halting_condition", 
+                        error_message: format!("Unable to set the halting condition to EarlyStopping since the patience must be at least one and the min_delta can't be negative!")
+                    });
+                }
+
+                let is_monitoring_loss = matches!(
+                    monitor,
+                    MonitoredQuantity::Loss | MonitoredQuantity::ValidationLoss
+                );
+
+                if is_monitoring_loss && !self.compute_loss {
+                    return Err(InvalidTrainingOptionError { 
+                        value_trying_to_be_set: halting_condition, 
+                        parameter_name: "halting_condition", 
+                        error_message: format!("Unable to set the halting condition to EarlyStopping monitoring a loss since the loss is not set to be calculated!")
+                    });
+                }
+
+                if !is_monitoring_loss && !self.compute_accuracy {
+                    return Err(InvalidTrainingOptionError { 
+                        value_trying_to_be_set: halting_condition, 
+                        parameter_name: "halting_condition", 
+                        error_message: format!("Unable to set the halting condition to EarlyStopping monitoring an accuracy since the accuracy is not set to be calculated!")
+                    });
+                }
+            }
         };
 
         self.halting_condition = Some(halting_condition);
@@ -355,6 +488,72 @@ impl<'a> TrainingOptions<'a> {
 
         self
     }
+
+    /// Sets weather or not the training samples should be shuffled before each epoch.
+    pub fn should_shuffle(mut self, should: bool) -> Self {
+        self.shuffle = should;
+
+        self
+    }
+
+    /// Sets the seed used for shuffling the training samples before each epoch, so that the
+    /// order of the samples is the same every time the Model is fitted, this also makes the
+    /// training samples be shuffled.
+    pub fn set_shuffle_seed(mut self, seed: u64) -> Self {
+        self.shuffle = true;
+        self.shuffle_seed = Some(seed);
+
+        self
+    }
+
+    /// Adds a callback that will be called throughout the training process, the callbacks are
+    /// called in the same order they were added.
+    pub fn add_callback(mut self, callback: &'a mut dyn TrainingCallback<'a>) -> Self {
+        self.callbacks.push(callback);
+
+        self
+    }
+
+    /// Sets samples that are separate from the training samples for the Model to be evaluated on
+    /// after each epoch, the losses and accuracies on them are then returned in the
+    /// `TrainingResults` if the loss and the accuracy are being computed.
+    pub fn set_validation_data(
+        mut self,
+        input_samples: Vec<Vec<f32>>,
+        expected_output_samples: Vec<Vec<f32>>,
+    ) -> Result<Self, InvalidTrainingOptionError<ValidationData>> {
+        if input_samples.is_empty() || input_samples.len() != expected_output_samples.len() {
+            return Err(InvalidTrainingOptionError {
+                value_trying_to_be_set: ValidationData::Samples(input_samples, expected_output_samples),
+                parameter_name: "validation_data",
+                error_message: format!("The validation data must have at least one sample and the same amount of input samples and expected output samples!"),
+            });
+        }
+
+        self.validation_data = Some(ValidationData::Samples(input_samples, expected_output_samples));
+
+        Ok(self)
+    }
+
+    /// Sets a fraction of the training samples, taken from the end of them, that the Model will
+    /// not be trained on and will be evaluated on after each epoch instead, just like with
+    /// `set_validation_data`.
+    pub fn set_validation_split(
+        mut self,
+        fraction: f32,
+    ) -> Result<Self, InvalidTrainingOptionError<f32>> {
+        if !(fraction > 0.0 && fraction < 1.0) {
+            return Err(InvalidTrainingOptionError {
+                value_trying_to_be_set: fraction,
+                parameter_name: "validation_data",
+                error_message: format!("The validation split must be a fraction between 0 and 1!"),
+            });
+        }
+
+        self.validation_data = Some(ValidationData::Split(fraction));
+
+        Ok(self)
+    }
 }
 
 #[derive(Debug)]
@@ -364,4 +563,28 @@ pub struct TrainingResults {
     pub loss_per_training_steps: Vec<f32>,
     /// The history of the accuracies after each one of the training steps
     pub accuracy_per_training_steps: Vec<f32>,
+    /// The history of the losses on the validation data after each one of the epochs, which is
+    /// empty if there is no validation data or if the loss is not being computed
+    pub validation_loss_per_epoch: Vec<f32>,
+    /// The history of the accuracies on the validation data after each one of the epochs, which
+    /// is empty if there is no validation data or if the accuracy is not being computed
+    pub validation_accuracy_per_epoch: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The metrics other than the loss that can be computed when evaluating a Model.
+pub enum Metric {
+    /// The same accuracy that can be computed while fitting a Model, which is how close each
+    /// output is to its expected output on average.
+    Accuracy,
+}
+
+#[derive(Debug)]
+/// The results of evaluating a Model on some samples with the `evaluate` method.
+pub struct EvaluationResults {
+    /// The loss of the Model over all of the samples
+    pub loss: f32,
+    /// The value of each one of the requested metrics over all of the samples, in the same order
+    /// that they were requested
+    pub metrics: Vec<(Metric, f32)>,
 }
\ No newline at end of file
@@ -4,6 +4,7 @@
 use std::{collections::HashMap, mem, ptr};
 
 use crate::{
+    graph_model::compile_graph_model,
     layers::compile_layers,
     loss_functions::compile_losses,
     model::compile_model,
@@ -1152,6 +1153,8 @@ pub fn setup_opencl(device_type: DeviceType) -> Result<OpenCLState, UnableToSetu
 
         compile_model(&mut state)?;
 
+        compile_graph_model(&mut state)?;
+
         compile_losses(&mut state)?;
 
         Ok(state)
@@ -1197,12 +1200,12 @@ pub(crate) fn empty_buffer(
     Ok(buf)
 }
 
-impl BufferLike<cl_float> for Vec<f32> {
+impl<T: Clone + Default> BufferLike<T> for Vec<T> {
     fn to_buffer(
         &self,
         blocking: bool,
         opencl_state: &OpenCLState,
-    ) -> Result<Buffer<cl_float>, BufferConversionError> {
+    ) -> Result<Buffer<T>, BufferConversionError> {
         if let Some(queue) = opencl_state.queues.first() {
             let context = &opencl_state.context;
 
@@ -1226,15 +1229,15 @@ impl BufferLike<cl_float> for Vec<f32> {
     }
 
     fn from_buffer(
-        buffer: &Buffer<cl_float>,
+        buffer: &Buffer<T>,
         blocking: bool,
         opencl_state: &OpenCLState,
-    ) -> Result<Vec<f32>, BufferConversionError> {
+    ) -> Result<Vec<T>, BufferConversionError> {
         if let Some(queue) = opencl_state.queues.first() {
             let size = buffer.size()?;
-            let count = size / mem::size_of::<cl_float>();
+            let count = size / mem::size_of::<T>();
 
-            let mut vec = vec![0.0; count];
+            let mut vec = vec![T::default(); count];
 
             if blocking {
                 queue